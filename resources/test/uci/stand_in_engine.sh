@@ -0,0 +1,35 @@
+#!/usr/bin/env bash
+# Speaks just enough UCI for tests, reporting the same two lines for every position
+
+report() {
+    echo "info depth 1 score cp 20 pv e2e4"
+    echo "info string searching"
+    echo "info depth 2 seldepth 3 score cp 35 nodes 100 pv e2e4 e7e5"
+}
+
+while read -r command; do
+    case "$command" in
+        uci)
+            echo "id name Stand-in"
+            echo "id author pigin"
+            echo "option name Hash type spin default 16 min 1 max 1024"
+            echo "uciok"
+            ;;
+        isready) echo "readyok" ;;
+        "go infinite")
+            report
+            searching=1
+            ;;
+        go*)
+            report
+            echo "bestmove e2e4 ponder e7e5"
+            ;;
+        stop)
+            if [ -n "$searching" ]; then
+                echo "bestmove e2e4 ponder e7e5"
+                searching=
+            fi
+            ;;
+        quit) exit 0 ;;
+    esac
+done
@@ -0,0 +1,18 @@
+use std::{error::Error, fmt::Display};
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct AnnotateError(String);
+
+impl AnnotateError {
+    pub fn new(message: impl Into<String>) -> Self {
+        AnnotateError(message.into())
+    }
+}
+
+impl Error for AnnotateError {}
+
+impl Display for AnnotateError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
@@ -0,0 +1,284 @@
+mod error;
+
+use std::time::Duration;
+
+use crate::{
+    config::{AnnotateConfig, Config},
+    engine,
+    model::{Board, Game, PieceColour},
+    uci::{self, Engine, Limit, Score},
+    write,
+};
+
+pub use error::AnnotateError;
+
+const INACCURACY: u8 = 6;
+const MISTAKE: u8 = 2;
+const BLUNDER: u8 = 4;
+// NAGs from !, ? through to ?!, any of which already judges the move
+const MOVE_ASSESSMENTS: std::ops::RangeInclusive<u8> = 1..=6;
+// Scores are capped when comparing moves so that a won position staying won isn't flagged
+const SCORE_CAP: i32 = 1000;
+// Engines' principal variations run on well past the point of interest
+const VARIATION_PLY: usize = 6;
+
+struct Evaluation {
+    // From White's point of view, or None once the game is over
+    score: Option<Score>,
+    centipawns: i32,
+    best_move: Option<String>,
+    pv: Vec<String>,
+}
+
+impl Evaluation {
+    fn for_colour(&self, colour: PieceColour) -> i32 {
+        match colour {
+            PieceColour::White => self.centipawns,
+            PieceColour::Black => -self.centipawns,
+        }
+    }
+}
+
+// Runs an engine over every position of a game, adding its evaluations as %eval comments and
+// flagging moves which lose too much against the engine's choice, along with what it preferred
+pub struct Annotator {
+    engine: Engine,
+    config: AnnotateConfig,
+}
+
+impl Annotator {
+    pub fn new(config: &Config) -> Result<Self, AnnotateError> {
+        let path = config.engine().path().ok_or_else(|| {
+            AnnotateError::new("No engine configured, set one with --engine or in the config file")
+        })?;
+        let engine = Engine::start(path, config.engine().options())
+            .map_err(|e| AnnotateError::new(e.to_string()))?;
+
+        Ok(Annotator {
+            engine,
+            config: config.annotate().clone(),
+        })
+    }
+
+    pub fn annotate(&mut self, game: &mut Game) -> Result<(), AnnotateError> {
+        let evaluations = game
+            .boards()
+            .iter()
+            .map(|board| self.evaluate(board))
+            .collect::<Result<Vec<Evaluation>, AnnotateError>>()?;
+        let boards = game.boards().to_vec();
+
+        for (index, ply) in game.pgn_mut().ply_mut().iter_mut().enumerate() {
+            let (before, after) = (&evaluations[index], &evaluations[index + 1]);
+
+            // Evaluations from an earlier run are replaced, but anything else in the comment is kept
+            let comment = ply.comment().and_then(|comment| without_eval(comment));
+            let eval = after
+                .score
+                .map(|score| format!("[%eval {}]", eval_text(score)));
+            ply.set_comment(match (eval, comment) {
+                (Some(eval), Some(comment)) => Some(format!("{eval} {comment}")),
+                (eval, comment) => eval.or(comment),
+            });
+
+            if ply.nags().iter().any(|nag| MOVE_ASSESSMENTS.contains(nag)) {
+                continue;
+            }
+
+            let best = before
+                .best_move
+                .as_deref()
+                .and_then(|best_move| uci::legal_move(&boards[index], best_move));
+            if best.is_some_and(|best| *best.board() == boards[index + 1]) {
+                continue;
+            }
+
+            let colour = ply.movement().colour();
+            if let Some(nag) = self.classify(before.for_colour(colour) - after.for_colour(colour)) {
+                ply.add_nag(nag);
+                let pv = &before.pv[..before.pv.len().min(VARIATION_PLY)];
+                let variation = uci::line(&boards[index], pv);
+                if !variation.is_empty() {
+                    ply.add_variation(variation);
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    // Positions where the game is over are scored without asking the engine
+    fn evaluate(&mut self, board: &Board) -> Result<Evaluation, AnnotateError> {
+        let flip = |centipawns: i32| match board.active_colour() {
+            PieceColour::White => centipawns,
+            PieceColour::Black => -centipawns,
+        };
+
+        if engine::legal_moves(board).is_empty() {
+            let centipawns = if engine::in_check(board) {
+                -SCORE_CAP
+            } else {
+                0
+            };
+            return Ok(Evaluation {
+                score: None,
+                centipawns: flip(centipawns),
+                best_move: None,
+                pv: Vec::new(),
+            });
+        }
+
+        let fen = write::fen(board).map_err(|e| AnnotateError::new(e.to_string()))?;
+        let (info, best_move) = self
+            .engine
+            .search(&fen, self.limit())
+            .map_err(|e| AnnotateError::new(e.to_string()))?;
+        let score = info.score().unwrap_or(Score::Centipawns(0));
+        let centipawns = match score {
+            Score::Centipawns(centipawns) => centipawns.clamp(-SCORE_CAP, SCORE_CAP),
+            Score::Mate(moves) if moves > 0 => SCORE_CAP,
+            Score::Mate(_) => -SCORE_CAP,
+        };
+
+        Ok(Evaluation {
+            score: Some(score.for_white(*board.active_colour())),
+            centipawns: flip(centipawns),
+            best_move: Some(best_move),
+            pv: info.pv().to_vec(),
+        })
+    }
+
+    fn limit(&self) -> Limit {
+        match self.config.movetime_ms() {
+            Some(movetime_ms) => Limit::Time(Duration::from_millis(movetime_ms)),
+            None => Limit::Depth(self.config.depth()),
+        }
+    }
+
+    fn classify(&self, loss: i32) -> Option<u8> {
+        if loss >= self.config.blunder() {
+            Some(BLUNDER)
+        } else if loss >= self.config.mistake() {
+            Some(MISTAKE)
+        } else if loss >= self.config.inaccuracy() {
+            Some(INACCURACY)
+        } else {
+            None
+        }
+    }
+}
+
+// Every evaluation goes, as a comment merged from several sources may hold more than one
+fn without_eval(comment: &str) -> Option<String> {
+    let mut comment = comment.to_string();
+    while let Some(start) = comment.find("[%eval ") {
+        let end = comment[start..]
+            .find(']')
+            .map_or(comment.len(), |end| start + end + 1);
+        comment.replace_range(start..end, " ");
+    }
+    let comment = comment.split_whitespace().collect::<Vec<&str>>().join(" ");
+    (!comment.is_empty()).then_some(comment)
+}
+
+// Evaluations are written in pawns, without the sign for positive scores
+fn eval_text(score: Score) -> String {
+    match score {
+        Score::Centipawns(centipawns) => format!("{:.2}", f64::from(centipawns) / 100.0),
+        Score::Mate(moves) => format!("#{moves}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{config, test_support::game};
+
+    use super::*;
+
+    fn annotator() -> Annotator {
+        let config =
+            config::parse("[engine]\npath = \"./resources/test/uci/stand_in_engine.sh\"").unwrap();
+        Annotator::new(&config).unwrap()
+    }
+
+    #[test]
+    fn returns_err_if_no_engine() {
+        assert!(Annotator::new(&Config::default()).is_err())
+    }
+
+    #[test]
+    fn adds_evaluations() {
+        let mut game = game("[Result \"*\"]\n\n1. e4 {Best by test} e5 *\n");
+        annotator().annotate(&mut game).unwrap();
+
+        let ply = game.pgn().ply();
+        assert_eq!(
+            ply[0].comment().map(String::as_str),
+            Some("[%eval -0.35] Best by test")
+        );
+        assert_eq!(ply[1].comment().map(String::as_str), Some("[%eval 0.35]"));
+    }
+
+    #[test]
+    fn flags_moves_other_than_best() {
+        let mut game = game("[Result \"*\"]\n\n1. d4 *\n");
+        annotator().annotate(&mut game).unwrap();
+
+        let ply = &game.pgn().ply()[0];
+        assert_eq!(ply.nags(), &[INACCURACY]);
+        assert_eq!(ply.variations().len(), 1);
+        assert_eq!(ply.variations()[0].len(), 2);
+    }
+
+    #[test]
+    fn scores_checkmate_without_engine() {
+        let mut game = game("[Result \"0-1\"]\n\n1. f3 e5 2. g4 Qh4# 0-1\n");
+        annotator().annotate(&mut game).unwrap();
+
+        let mate = &game.pgn().ply()[3];
+        assert_eq!(mate.comment(), None);
+        assert!(mate.nags().is_empty());
+    }
+
+    #[test]
+    fn replaces_earlier_annotations() {
+        let mut game = game("[Result \"*\"]\n\n1. d4 *\n");
+        let mut annotator = annotator();
+        annotator.annotate(&mut game).unwrap();
+        annotator.annotate(&mut game).unwrap();
+
+        let ply = &game.pgn().ply()[0];
+        assert_eq!(ply.comment().map(String::as_str), Some("[%eval -0.35]"));
+        assert_eq!(ply.nags(), &[INACCURACY]);
+        assert_eq!(ply.variations().len(), 1);
+    }
+
+    #[test]
+    fn removes_evaluation_from_comment() {
+        assert_eq!(
+            without_eval("Sharp [%eval 1.20] line"),
+            Some(String::from("Sharp line"))
+        );
+        assert_eq!(without_eval("[%eval #3]"), None);
+        assert_eq!(
+            without_eval("[%eval 0.20] Good [%eval 0.35]"),
+            Some(String::from("Good"))
+        );
+    }
+
+    #[test]
+    fn classifies_losses() {
+        let annotator = annotator();
+        assert_eq!(annotator.classify(20), None);
+        assert_eq!(annotator.classify(50), Some(INACCURACY));
+        assert_eq!(annotator.classify(150), Some(MISTAKE));
+        assert_eq!(annotator.classify(1000), Some(BLUNDER));
+    }
+
+    #[test]
+    fn formats_evaluations() {
+        assert_eq!(eval_text(Score::Centipawns(35)), "0.35");
+        assert_eq!(eval_text(Score::Centipawns(-120)), "-1.20");
+        assert_eq!(eval_text(Score::Mate(3)), "#3");
+    }
+}
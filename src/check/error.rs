@@ -0,0 +1,18 @@
+use std::{error::Error, fmt::Display};
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct CheckError(String);
+
+impl CheckError {
+    pub fn new(message: impl Into<String>) -> Self {
+        CheckError(message.into())
+    }
+}
+
+impl Error for CheckError {}
+
+impl Display for CheckError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
@@ -0,0 +1,128 @@
+use std::fmt::Display;
+
+use serde::Serialize;
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+impl Display for Severity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Severity::Error => write!(f, "error"),
+            Severity::Warning => write!(f, "warning"),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Kind {
+    Unreadable,
+    IllegalMove,
+    AmbiguousMove,
+    OverQualified,
+    CheckMarker,
+    MoveNumber,
+    ResultMismatch,
+    MissingResult,
+    MissingTag,
+    Date,
+}
+
+impl Kind {
+    // Errors are problems leaving the game unreadable or contradicting itself, while warnings are
+    // departures from the standard which don't change its meaning
+    pub fn severity(self) -> Severity {
+        match self {
+            Kind::Unreadable | Kind::IllegalMove | Kind::AmbiguousMove | Kind::ResultMismatch => {
+                Severity::Error
+            }
+            Kind::OverQualified
+            | Kind::CheckMarker
+            | Kind::MoveNumber
+            | Kind::MissingResult
+            | Kind::MissingTag
+            | Kind::Date => Severity::Warning,
+        }
+    }
+}
+
+// Where in the input a game starts, or one of its moves is, counting games from 1 within each file
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Location {
+    file: String,
+    game: usize,
+    line: usize,
+}
+
+impl Location {
+    pub fn new(file: &str, game: usize, line: usize) -> Self {
+        Location {
+            file: file.to_string(),
+            game,
+            line,
+        }
+    }
+
+    pub fn on_line(&self, line: usize) -> Self {
+        Location {
+            line,
+            ..self.clone()
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Serialize)]
+pub struct Issue {
+    file: String,
+    game: usize,
+    line: usize,
+    // The move the problem is with as written, e.g. "12... Nf6"
+    #[serde(rename = "move")]
+    movement: Option<String>,
+    severity: Severity,
+    kind: Kind,
+    message: String,
+}
+
+impl Issue {
+    pub fn new(
+        location: &Location,
+        movement: Option<String>,
+        kind: Kind,
+        message: impl Into<String>,
+    ) -> Self {
+        Issue {
+            file: location.file.clone(),
+            game: location.game,
+            line: location.line,
+            movement,
+            severity: kind.severity(),
+            kind,
+            message: message.into(),
+        }
+    }
+
+    pub fn severity(&self) -> Severity {
+        self.severity
+    }
+}
+
+// e.g. "games.pgn:14: error: game 2, 5. Nxe9: Not a legal move in this position"
+impl Display for Issue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}:{}: {}: game {}",
+            self.file, self.line, self.severity, self.game
+        )?;
+        if let Some(movement) = &self.movement {
+            write!(f, ", {movement}")?;
+        }
+        write!(f, ": {}", self.message)
+    }
+}
@@ -0,0 +1,139 @@
+use crate::model::Ply;
+
+// The line of the game's text each move is written on, with those of its variations
+#[derive(Debug, PartialEq, Eq)]
+pub struct MoveLine {
+    line: usize,
+    variations: Vec<Vec<MoveLine>>,
+}
+
+impl MoveLine {
+    pub fn line(&self) -> usize {
+        self.line
+    }
+
+    pub fn variations(&self) -> &[Vec<MoveLine>] {
+        &self.variations
+    }
+}
+
+// Moves are found in the order they're written, which is each move followed by its variations.
+// Lines are counted from the first line of the text, and a move which can't be found, such as
+// castling written with zeros, is given the line of the move before it
+pub fn move_lines(text: &str, first_line: usize, ply: &[Ply]) -> Vec<MoveLine> {
+    let mut cursor = Cursor {
+        text: movetext(text),
+        offset: 0,
+        first_line,
+    };
+    cursor.lines(ply)
+}
+
+struct Cursor {
+    text: String,
+    offset: usize,
+    first_line: usize,
+}
+
+impl Cursor {
+    fn lines(&mut self, ply: &[Ply]) -> Vec<MoveLine> {
+        ply.iter()
+            .map(|p| {
+                let line = self.find(&p.movement().to_string());
+                let variations = p
+                    .variations()
+                    .iter()
+                    .map(|variation| self.lines(variation))
+                    .collect();
+                MoveLine { line, variations }
+            })
+            .collect()
+    }
+
+    // Only whole moves count, so Nf3 isn't found in Nf3+ or d7 in Nbd7
+    fn find(&mut self, san: &str) -> usize {
+        let bytes = self.text.as_bytes();
+        let separate = |byte: Option<&u8>| {
+            byte.map_or(true, |&byte| {
+                !byte.is_ascii_alphanumeric() && !b"+#=".contains(&byte)
+            })
+        };
+        let found = self.text[self.offset..]
+            .match_indices(san)
+            .map(|(index, _)| self.offset + index)
+            .find(|&start| {
+                let end = start + san.len();
+                separate(start.checked_sub(1).and_then(|before| bytes.get(before)))
+                    && separate(bytes.get(end))
+            });
+        if let Some(start) = found {
+            self.offset = start + san.len();
+        }
+        self.first_line + self.text[..self.offset].matches('\n').count()
+    }
+}
+
+// The text with its tags and comments blanked out, keeping every line where it was
+fn movetext(text: &str) -> String {
+    let blank = |c: char| if c == '\n' { c } else { ' ' };
+    let mut blanked = String::with_capacity(text.len());
+    // The character closing the comment being blanked
+    let mut closing = None;
+    for line in text.split_inclusive('\n') {
+        if closing.is_none() && line.trim_start().starts_with('[') {
+            blanked.extend(line.chars().map(blank));
+            continue;
+        }
+        for c in line.chars() {
+            match (closing, c) {
+                (Some(close), c) => {
+                    if c == close {
+                        closing = None;
+                    }
+                    blanked.push(blank(c));
+                }
+                (None, '{') => {
+                    closing = Some('}');
+                    blanked.push(' ');
+                }
+                (None, ';') => {
+                    closing = Some('\n');
+                    blanked.push(' ');
+                }
+                (None, c) => blanked.push(c),
+            }
+        }
+    }
+    blanked
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::parse;
+
+    use super::*;
+
+    fn lines(text: &str) -> Vec<MoveLine> {
+        let pgn = parse::parse(text).unwrap().remove(0);
+        move_lines(text, 1, pgn.ply())
+    }
+
+    #[test]
+    fn finds_line_of_each_move() {
+        let lines =
+            lines("[Result \"*\"]\n\n1. Nf3 {Nf6 d4} Nf6\n2. d4 (2. Nc3\nd5) ; d5\n2... d5 *\n");
+        let numbers: Vec<usize> = lines.iter().map(MoveLine::line).collect();
+        assert_eq!(numbers, vec![3, 3, 4, 6]);
+        let variation: Vec<usize> = lines[2].variations()[0]
+            .iter()
+            .map(MoveLine::line)
+            .collect();
+        assert_eq!(variation, vec![4, 5]);
+    }
+
+    #[test]
+    fn matches_whole_moves() {
+        let lines = lines("[Result \"*\"]\n\n1. d4 Nf6 2. Nd2\nd6 *\n");
+        assert_eq!(lines[3].line(), 4);
+    }
+}
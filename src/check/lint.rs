@@ -0,0 +1,512 @@
+use crate::{
+    engine,
+    model::{Board, Check, MoveQualifier, Pgn, PieceColour, Ply, PlyMovement, SEVEN_TAG_ROSTER},
+    parse, write,
+};
+
+use super::{
+    issue::{Issue, Kind, Location},
+    lines::{move_lines, MoveLine},
+};
+
+const DATE_TAGS: [&str; 3] = ["Date", "EventDate", "UTCDate"];
+
+// Reads and replays every game in the input, returning how many games there were and the problems
+// found with them
+pub fn lint(file_name: &str, input: &str) -> (usize, Vec<Issue>) {
+    let games = parse::games(input);
+    let mut issues = Vec::new();
+
+    for (index, (range, pgn)) in games.iter().enumerate() {
+        let line = input[..range.start].matches('\n').count() + 1;
+        let location = Location::new(file_name, index + 1, line);
+        match pgn {
+            Ok(pgn) => {
+                issues.extend(tags(&location, pgn));
+                let text = &input[range.clone()];
+                issues.extend(termination(&location, pgn, text));
+                issues.extend(moves(
+                    &location,
+                    pgn.fen().starting_board(),
+                    pgn.ply(),
+                    &move_lines(text, line, pgn.ply()),
+                    false,
+                ));
+            }
+            Err(e) => issues.push(Issue::new(&location, None, Kind::Unreadable, e.message())),
+        }
+    }
+    (games.len(), issues)
+}
+
+fn tags(location: &Location, pgn: &Pgn) -> Vec<Issue> {
+    let mut issues = Vec::new();
+    // The Result tag is left out as games without one can't be read at all
+    let missing: Vec<&str> = SEVEN_TAG_ROSTER
+        .into_iter()
+        .filter(|&tag| tag != "Result" && pgn.tags().get(tag).is_none())
+        .collect();
+    if !missing.is_empty() {
+        issues.push(Issue::new(
+            location,
+            None,
+            Kind::MissingTag,
+            format!("Missing Seven Tag Roster tags: {}", missing.join(", ")),
+        ));
+    }
+
+    for tag in DATE_TAGS {
+        let Some(value) = pgn.tags().get(tag) else {
+            continue;
+        };
+        let message = match date(value) {
+            Ok(()) => continue,
+            Err(DateError::Format) => format!(
+                "{tag} \"{value}\" should be written YYYY.MM.DD, with question marks for unknown parts"
+            ),
+            Err(DateError::Range) => format!("{tag} \"{value}\" is not a real date"),
+        };
+        issues.push(Issue::new(location, None, Kind::Date, message));
+    }
+    issues
+}
+
+enum DateError {
+    Format,
+    Range,
+}
+
+// Each part is either all digits or all question marks, e.g. "2024.??.??"
+fn date(value: &str) -> Result<(), DateError> {
+    let parts: Vec<&str> = value.split('.').collect();
+    let [year, month, day] = parts[..] else {
+        return Err(DateError::Format);
+    };
+
+    let mut numbers = [(year, 4, 1..=9999), (month, 2, 1..=12), (day, 2, 1..=31)]
+        .into_iter()
+        .map(|(part, length, range)| {
+            if part.len() != length {
+                Err(DateError::Format)
+            } else if part.chars().all(|c| c == '?') {
+                Ok(None)
+            } else if part.chars().all(|c| c.is_ascii_digit()) {
+                part.parse::<u32>()
+                    .ok()
+                    .filter(|number| range.contains(number))
+                    .map(Some)
+                    .ok_or(DateError::Range)
+            } else {
+                Err(DateError::Format)
+            }
+        });
+    let (Some(year), Some(month), Some(day)) = (
+        numbers.next().unwrap_or(Ok(None))?,
+        numbers.next().unwrap_or(Ok(None))?,
+        numbers.next().unwrap_or(Ok(None))?,
+    ) else {
+        return Ok(());
+    };
+
+    let leap = year % 4 == 0 && (year % 100 != 0 || year % 400 == 0);
+    let days = match month {
+        2 if leap => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    };
+    if day > days {
+        return Err(DateError::Range);
+    }
+    Ok(())
+}
+
+// The movetext should end with the same result as the Result tag, though comments can follow it
+fn termination(location: &Location, pgn: &Pgn, text: &str) -> Option<Issue> {
+    let expected = write::result(pgn.result());
+    let message = match without_comments(text).split_whitespace().last() {
+        Some(token) if token == expected => return None,
+        Some(token) if parse::result(token).is_ok() => {
+            format!("The movetext ends in {token} but the Result tag is {expected}")
+        }
+        _ => {
+            return Some(Issue::new(
+                location,
+                None,
+                Kind::MissingResult,
+                format!("The movetext should end with the result, {expected}"),
+            ))
+        }
+    };
+    Some(Issue::new(location, None, Kind::ResultMismatch, message))
+}
+
+fn without_comments(text: &str) -> String {
+    let mut kept = String::new();
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        let end = match c {
+            '{' => '}',
+            ';' => '\n',
+            c => {
+                kept.push(c);
+                continue;
+            }
+        };
+        chars.by_ref().take_while(|&c| c != end).for_each(drop);
+        kept.push(' ');
+    }
+    kept
+}
+
+// Replays the line from the board, along with its variations, stopping at the first move which
+// can't be played. Problems with a move are reported on the line it's written on
+fn moves(
+    location: &Location,
+    board: &Board,
+    ply: &[Ply],
+    lines: &[MoveLine],
+    variation: bool,
+) -> Vec<Issue> {
+    let mut issues = Vec::new();
+    let mut board = board.clone();
+
+    for (index, (p, move_line)) in ply.iter().zip(lines).enumerate() {
+        let location = location.on_line(move_line.line());
+        let movement = p.movement();
+        let mut written = match movement.colour() {
+            PieceColour::White => format!("{}. {movement}", p.move_number()),
+            PieceColour::Black => format!("{}... {movement}", p.move_number()),
+        };
+        if variation {
+            written.push_str(" in a variation");
+        }
+        let mut issue = |kind: Kind, message: String| {
+            issues.push(Issue::new(&location, Some(written.clone()), kind, message));
+        };
+
+        // Black's moves take the number of White's move before them unless numbered themselves,
+        // so only White's numbering is checked where both are in the same move
+        let expected = board.fullmove_clock();
+        let inherited = movement.colour() == PieceColour::Black
+            && index
+                .checked_sub(1)
+                .is_some_and(|previous| ply[previous].move_number() == p.move_number());
+        if usize::try_from(p.move_number()).ok() != Some(expected) && !inherited {
+            issue(
+                Kind::MoveNumber,
+                format!("Numbered {} but is move {expected}", p.move_number()),
+            );
+        }
+
+        let legal_move = match &engine::matching_moves(&board, movement)[..] {
+            [] => {
+                issue(
+                    Kind::IllegalMove,
+                    String::from("Not a legal move in this position"),
+                );
+                break;
+            }
+            [legal_move] => legal_move.clone(),
+            candidates => {
+                let mut candidates: Vec<String> = candidates
+                    .iter()
+                    .map(|candidate| candidate.movement().to_string())
+                    .collect();
+                candidates.sort();
+                issue(
+                    Kind::AmbiguousMove,
+                    format!("Could be any of {}", candidates.join(", ")),
+                );
+                break;
+            }
+        };
+
+        let canonical = legal_move.movement();
+        if qualifier(movement) != qualifier(canonical) {
+            let message = match (qualifier(movement), qualifier(canonical)) {
+                (Some(_), None) | (Some(MoveQualifier::Position(_)), Some(_)) => {
+                    format!("Over-qualified, {canonical} is enough")
+                }
+                _ => format!("Should be written {canonical}"),
+            };
+            issue(Kind::OverQualified, message);
+        }
+
+        match (check(movement), check(canonical)) {
+            (given, actual) if given == actual => {}
+            (_, Some(Check::Checkmate)) => {
+                issue(
+                    Kind::CheckMarker,
+                    format!("Checkmate, so should be {canonical}"),
+                );
+            }
+            (_, Some(Check::Check)) => {
+                issue(
+                    Kind::CheckMarker,
+                    format!("Gives check, so should be {canonical}"),
+                );
+            }
+            (Some(Check::Checkmate), None) => {
+                issue(
+                    Kind::CheckMarker,
+                    String::from("Marked as checkmate but isn't check"),
+                );
+            }
+            (_, None) => {
+                issue(Kind::CheckMarker, String::from("Marked as check but isn't"));
+            }
+        }
+
+        for (line, lines) in p.variations().iter().zip(move_line.variations()) {
+            issues.extend(moves(&location, &board, line, lines, true));
+        }
+        board = legal_move.board().clone();
+    }
+    issues
+}
+
+fn qualifier(movement: &PlyMovement) -> Option<&MoveQualifier> {
+    match movement {
+        PlyMovement::Move { qualifier, .. } | PlyMovement::Promotion { qualifier, .. } => {
+            qualifier.as_ref()
+        }
+        PlyMovement::KingsideCastle { .. } | PlyMovement::QueensideCastle { .. } => None,
+    }
+}
+
+fn check(movement: &PlyMovement) -> Option<&Check> {
+    match movement {
+        PlyMovement::KingsideCastle { check, .. }
+        | PlyMovement::QueensideCastle { check, .. }
+        | PlyMovement::Move { check, .. }
+        | PlyMovement::Promotion { check, .. } => check.as_ref(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TAGS: &str = "[Event \"Casual\"]\n[Site \"London\"]\n[Date \"2024.05.01\"]\n\
+        [Round \"1\"]\n[White \"White\"]\n[Black \"Black\"]\n";
+
+    fn issues(tags: &str, movetext: &str) -> Vec<Issue> {
+        lint("games.pgn", &format!("{tags}\n{movetext}\n")).1
+    }
+
+    // Problems with moves are on the line of the movetext, after the tags and a blank line
+    fn issue(kind: Kind, movement: Option<&str>, message: &str) -> Issue {
+        let line = if movement.is_some() { 9 } else { 1 };
+        Issue::new(
+            &Location::new("games.pgn", 1, line),
+            movement.map(ToString::to_string),
+            kind,
+            message,
+        )
+    }
+
+    fn with_result(result: &str) -> String {
+        format!("{TAGS}[Result \"{result}\"]\n")
+    }
+
+    #[test]
+    fn accepts_clean_game() {
+        let movetext = "1. e4 e5 2. Qh5 Nc6 3. Bc4 Nf6 4. Qxf7# 1-0";
+        assert!(issues(&with_result("1-0"), movetext).is_empty());
+    }
+
+    #[test]
+    fn reports_illegal_move() {
+        assert_eq!(
+            issues(&with_result("*"), "1. e4 e5 2. Ke3 Nc6 3. Qh5 *"),
+            vec![issue(
+                Kind::IllegalMove,
+                Some("2. Ke3"),
+                "Not a legal move in this position"
+            )]
+        );
+    }
+
+    #[test]
+    fn reports_ambiguous_move() {
+        assert_eq!(
+            issues(&with_result("*"), "1. e4 e5 2. Nf3 Nc6 3. d3 Nf6 4. Nd2 *"),
+            vec![issue(
+                Kind::AmbiguousMove,
+                Some("4. Nd2"),
+                "Could be any of Nbd2, Nfd2"
+            )]
+        );
+    }
+
+    #[test]
+    fn reports_over_qualified_moves() {
+        assert_eq!(
+            issues(
+                &with_result("*"),
+                "1. e4 e5 2. Ng1f3 Nc6 3. d3 Nf6 4. N1d2 *"
+            ),
+            vec![
+                issue(
+                    Kind::OverQualified,
+                    Some("2. Ng1f3"),
+                    "Over-qualified, Nf3 is enough"
+                ),
+                issue(
+                    Kind::OverQualified,
+                    Some("4. N1d2"),
+                    "Should be written Nbd2"
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn reports_check_markers() {
+        assert_eq!(
+            issues(
+                &with_result("1-0"),
+                "1. e4 e5 2. Qh5+ Nc6 3. Bc4 Nf6 4. Qxf7 1-0"
+            ),
+            vec![
+                issue(
+                    Kind::CheckMarker,
+                    Some("2. Qh5+"),
+                    "Marked as check but isn't"
+                ),
+                issue(
+                    Kind::CheckMarker,
+                    Some("4. Qxf7"),
+                    "Checkmate, so should be Qxf7#"
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn reports_move_numbers() {
+        assert_eq!(
+            issues(&with_result("*"), "1. e4 e5 3. Nf3 Nc6 *"),
+            vec![issue(
+                Kind::MoveNumber,
+                Some("3. Nf3"),
+                "Numbered 3 but is move 2"
+            )]
+        );
+    }
+
+    #[test]
+    fn checks_variations() {
+        assert_eq!(
+            issues(&with_result("*"), "1. e4 e5 (1... Ke7 2. d4) 2. Nf3 *"),
+            vec![issue(
+                Kind::IllegalMove,
+                Some("1... Ke7 in a variation"),
+                "Not a legal move in this position"
+            )]
+        );
+    }
+
+    #[test]
+    fn accepts_comments_after_result() {
+        let movetext = "1. e4 e5 1-0 {White wins on time} ; Blitz";
+        assert!(issues(&with_result("1-0"), movetext).is_empty());
+    }
+
+    #[test]
+    fn reports_line_of_move() {
+        let text = format!(
+            "{}\n1. e4 e5\n2. Nf3 Nc6\n(2... Ke6\n3. d4) *\n",
+            with_result("*")
+        );
+        let (_, issues) = lint("games.pgn", &format!("\n\n{text}\n{text}"));
+        let lines: Vec<String> = issues.iter().map(ToString::to_string).collect();
+        assert_eq!(
+            lines,
+            vec![
+                "games.pgn:13: error: game 1, 2... Ke6 in a variation: Not a legal move in this position",
+                "games.pgn:26: error: game 2, 2... Ke6 in a variation: Not a legal move in this position",
+            ]
+        );
+    }
+
+    #[test]
+    fn reports_result_disagreeing_with_tag() {
+        assert_eq!(
+            issues(&with_result("1-0"), "1. e4 e5 0-1"),
+            vec![issue(
+                Kind::ResultMismatch,
+                None,
+                "The movetext ends in 0-1 but the Result tag is 1-0"
+            )]
+        );
+        assert_eq!(
+            issues(&with_result("1-0"), "1. e4 e5"),
+            vec![issue(
+                Kind::MissingResult,
+                None,
+                "The movetext should end with the result, 1-0"
+            )]
+        );
+    }
+
+    #[test]
+    fn reports_missing_tags() {
+        assert_eq!(
+            issues("[White \"White\"]\n[Result \"*\"]\n", "1. e4 *"),
+            vec![issue(
+                Kind::MissingTag,
+                None,
+                "Missing Seven Tag Roster tags: Event, Site, Date, Round, Black"
+            )]
+        );
+    }
+
+    #[test]
+    fn reports_bad_dates() {
+        let tags = TAGS.replace("2024.05.01", "2024-05-01");
+        assert_eq!(
+            issues(&format!("{tags}[EventDate \"2023.02.29\"]\n[Result \"*\"]\n"), "*"),
+            vec![
+                issue(
+                    Kind::Date,
+                    None,
+                    "Date \"2024-05-01\" should be written YYYY.MM.DD, with question marks for unknown parts"
+                ),
+                issue(Kind::Date, None, "EventDate \"2023.02.29\" is not a real date"),
+            ]
+        );
+    }
+
+    #[test]
+    fn accepts_partial_dates() {
+        for value in ["2024.02.29", "2024.??.??", "????.??.??", "1990.11.??"] {
+            assert!(date(value).is_ok(), "{value}");
+        }
+        for value in [
+            "24.05.01",
+            "2024.5.1",
+            "2024.05",
+            "2024.1?.01",
+            "2024.13.01",
+        ] {
+            assert!(date(value).is_err(), "{value}");
+        }
+    }
+
+    #[test]
+    fn reports_unreadable_game() {
+        let input = format!(
+            "{}\n1. e4 e5 1-0\n\n[Result \"*\"]\n\n1. e4 {{ *\n",
+            with_result("1-0")
+        );
+        let (games, issues) = lint("games.pgn", &input);
+        assert_eq!(games, 2);
+        assert_eq!(issues.len(), 1);
+        assert_eq!(
+            issues[0].to_string(),
+            "games.pgn:11: error: game 2: Failed to read the game at line 13, near '{ *'"
+        );
+    }
+}
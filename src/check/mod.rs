@@ -0,0 +1,9 @@
+mod error;
+mod issue;
+mod lines;
+mod lint;
+mod report;
+
+use lint::lint;
+
+pub use report::Report;
@@ -0,0 +1,136 @@
+use std::fmt::Display;
+
+use serde::Serialize;
+
+use super::{
+    error::CheckError,
+    issue::{Issue, Severity},
+    lint,
+};
+
+// The problems found across all the files checked
+#[derive(Debug, PartialEq, Eq, Default, Serialize)]
+pub struct Report {
+    games: usize,
+    errors: usize,
+    warnings: usize,
+    issues: Vec<Issue>,
+}
+
+impl Report {
+    pub fn new() -> Self {
+        Report::default()
+    }
+
+    pub fn add(&mut self, file_name: &str, input: &str) {
+        let (games, issues) = lint(file_name, input);
+        self.games += games;
+        for issue in issues {
+            match issue.severity() {
+                Severity::Error => self.errors += 1,
+                Severity::Warning => self.warnings += 1,
+            }
+            self.issues.push(issue);
+        }
+    }
+
+    pub fn errors(&self) -> usize {
+        self.errors
+    }
+
+    pub fn warnings(&self) -> usize {
+        self.warnings
+    }
+
+    pub fn json(&self) -> Result<String, CheckError> {
+        serde_json::to_string_pretty(self)
+            .map_err(|e| CheckError::new(format!("Failed to write JSON: {e}")))
+    }
+}
+
+// One issue per line, followed by the totals
+impl Display for Report {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for issue in &self.issues {
+            writeln!(f, "{issue}")?;
+        }
+        if self.issues.is_empty() {
+            write!(
+                f,
+                "Checked {}: no problems found",
+                plural(self.games, "game")
+            )
+        } else {
+            write!(
+                f,
+                "Checked {}: {}, {}",
+                plural(self.games, "game"),
+                plural(self.errors, "error"),
+                plural(self.warnings, "warning")
+            )
+        }
+    }
+}
+
+fn plural(count: usize, noun: &str) -> String {
+    match count {
+        1 => format!("1 {noun}"),
+        _ => format!("{count} {noun}s"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GAMES: &str = "[Event \"Casual\"]\n[Site \"London\"]\n[Date \"2024.05.01\"]\n\
+        [Round \"1\"]\n[White \"White\"]\n[Black \"Black\"]\n[Result \"1-0\"]\n\n\
+        1. e4 e5 2. Ke3 1-0\n\n[Result \"*\"]\n\n1. e4 *\n";
+
+    #[test]
+    fn counts_issues() {
+        let mut report = Report::new();
+        report.add("games.pgn", GAMES);
+        report.add("more.pgn", GAMES);
+
+        assert_eq!(report.games, 4);
+        assert_eq!(report.errors(), 2);
+        assert_eq!(report.warnings(), 2);
+    }
+
+    #[test]
+    fn writes_text() {
+        let mut report = Report::new();
+        report.add("games.pgn", GAMES);
+        assert_eq!(
+            report.to_string(),
+            [
+                "games.pgn:9: error: game 1, 2. Ke3: Not a legal move in this position",
+                "games.pgn:11: warning: game 2: Missing Seven Tag Roster tags: Event, Site, Date, Round, White, Black",
+                "Checked 2 games: 1 error, 1 warning",
+            ]
+            .join("\n")
+        );
+
+        let mut report = Report::new();
+        let first = GAMES.split("\n\n[Result \"*\"]").next().unwrap();
+        report.add("games.pgn", &first.replace("2. Ke3", "2. Ke2"));
+        assert_eq!(report.to_string(), "Checked 1 game: no problems found");
+    }
+
+    #[test]
+    fn writes_json() {
+        let mut report = Report::new();
+        report.add("games.pgn", GAMES);
+        let json: serde_json::Value = serde_json::from_str(&report.json().unwrap()).unwrap();
+
+        assert_eq!(json["games"], 2);
+        assert_eq!(json["errors"], 1);
+        assert_eq!(json["issues"][0]["file"], "games.pgn");
+        assert_eq!(json["issues"][0]["line"], 9);
+        assert_eq!(json["issues"][0]["move"], "2. Ke3");
+        assert_eq!(json["issues"][0]["severity"], "error");
+        assert_eq!(json["issues"][0]["kind"], "illegal-move");
+        assert_eq!(json["issues"][1]["move"], serde_json::Value::Null);
+    }
+}
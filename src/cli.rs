@@ -1,14 +1,469 @@
-use clap::{Arg, Command};
+use clap::{value_parser, Arg, ArgAction, Command};
 
 pub fn pigin() -> Command {
-    Command::new("pgn").arg(
-        Arg::new("file")
-            .short('f')
-            .long("file")
-            .required(true)
-            .num_args(1..)
-            .help("File or files to visualise"),
-    )
+    Command::new("pgn")
+        .subcommand_negates_reqs(true)
+        .args_conflicts_with_subcommands(true)
+        .subcommand(annotate())
+        .subcommand(position())
+        .subcommand(pattern())
+        .subcommand(stats())
+        .subcommand(export())
+        .subcommand(show())
+        .subcommand(diagram())
+        .subcommand(gif())
+        .subcommand(check())
+        .subcommand(fmt())
+        .subcommand(filter())
+        .subcommand(split())
+        .subcommand(merge())
+        .subcommand(duplicates())
+        .arg(
+            Arg::new("file")
+                .short('f')
+                .long("file")
+                .required(true)
+                .num_args(1..)
+                .help("File or files to visualise"),
+        )
+        .arg(
+            Arg::new("config")
+                .short('c')
+                .long("config")
+                .help("Configuration file to use in place of the default"),
+        )
+        .arg(
+            Arg::new("engine")
+                .long("engine")
+                .help("UCI engine to analyse positions with, in place of the configured one"),
+        )
+        .arg(
+            Arg::new("autoplay")
+                .long("autoplay")
+                .action(ArgAction::SetTrue)
+                .help("Start playing through games automatically"),
+        )
+        .arg(
+            Arg::new("encoding").long("encoding").global(true).help(
+                "Character encoding of the files, e.g. windows-1252, in place of detecting it",
+            ),
+        )
+}
+
+fn annotate() -> Command {
+    Command::new("annotate")
+        .about("Annotate games with an engine and write them back out as PGN")
+        .arg(
+            Arg::new("file")
+                .short('f')
+                .long("file")
+                .required(true)
+                .num_args(1..)
+                .help("File or files to annotate"),
+        )
+        .arg(
+            Arg::new("output")
+                .short('o')
+                .long("output")
+                .help("File to write the annotated games to, in place of standard output"),
+        )
+        .arg(
+            Arg::new("config")
+                .short('c')
+                .long("config")
+                .help("Configuration file to use in place of the default"),
+        )
+        .arg(
+            Arg::new("engine")
+                .long("engine")
+                .help("UCI engine to annotate with, in place of the configured one"),
+        )
+        .arg(
+            Arg::new("depth")
+                .long("depth")
+                .value_parser(value_parser!(u32))
+                .conflicts_with("movetime")
+                .help("Depth to search each position to"),
+        )
+        .arg(
+            Arg::new("movetime")
+                .long("movetime")
+                .value_parser(value_parser!(u64))
+                .help("Milliseconds to search each position for"),
+        )
+}
+
+fn position() -> Command {
+    Command::new("position")
+        .about("Find the games reaching a position, by transposition or otherwise")
+        .arg(
+            Arg::new("file")
+                .short('f')
+                .long("file")
+                .required(true)
+                .num_args(1..)
+                .help("File or files to search"),
+        )
+        .arg(
+            Arg::new("fen")
+                .long("fen")
+                .required(true)
+                .help("Position to look for, as a FEN string"),
+        )
+}
+
+fn pattern() -> Command {
+    Command::new("pattern")
+        .about("Find the games matching a material or piece placement pattern")
+        .arg(
+            Arg::new("file")
+                .short('f')
+                .long("file")
+                .required(true)
+                .num_args(1..)
+                .help("File or files to search"),
+        )
+        .arg(
+            Arg::new("pattern")
+                .short('p')
+                .long("pattern")
+                .required(true)
+                .help(
+                    "Pattern to look for, e.g. \"R+P vs R\", \"opposite-bishops\" or \"Kg1 Pf2\"",
+                ),
+        )
+}
+
+fn stats() -> Command {
+    Command::new("stats")
+        .about("Summarise each player's results across the games")
+        .arg(
+            Arg::new("file")
+                .short('f')
+                .long("file")
+                .required(true)
+                .num_args(1..)
+                .help("File or files to summarise"),
+        )
+        .arg(
+            Arg::new("player")
+                .short('p')
+                .long("player")
+                .help("Only show players whose name contains this, ignoring case"),
+        )
+}
+
+fn export() -> Command {
+    Command::new("export")
+        .about("Export games in another format")
+        .arg(
+            Arg::new("file")
+                .short('f')
+                .long("file")
+                .required(true)
+                .num_args(1..)
+                .help("File or files to export"),
+        )
+        .arg(
+            Arg::new("format")
+                .long("format")
+                .value_parser(["json"])
+                .default_value("json")
+                .help("Format to export to"),
+        )
+        .arg(
+            Arg::new("output")
+                .short('o')
+                .long("output")
+                .help("File to write the exported games to, in place of standard output"),
+        )
+}
+
+fn show() -> Command {
+    Command::new("show")
+        .about("Print a position from a game as text")
+        .arg(
+            Arg::new("file")
+                .short('f')
+                .long("file")
+                .required(true)
+                .num_args(1..)
+                .help("File or files containing the game"),
+        )
+        .arg(
+            Arg::new("game")
+                .short('g')
+                .long("game")
+                .value_parser(value_parser!(usize))
+                .default_value("1")
+                .help("Game to show, counting from 1 across the files"),
+        )
+        .arg(
+            Arg::new("ply")
+                .short('p')
+                .long("ply")
+                .value_parser(value_parser!(usize))
+                .help("Number of plies into the game to show the board after, or the final position if not given"),
+        )
+        .arg(
+            Arg::new("perspective")
+                .long("perspective")
+                .value_parser(["white", "black"])
+                .default_value("white")
+                .help("Side to view the board from"),
+        )
+        .arg(
+            Arg::new("unicode")
+                .short('u')
+                .long("unicode")
+                .action(ArgAction::SetTrue)
+                .help("Draw pieces with Unicode chess symbols"),
+        )
+}
+
+fn diagram() -> Command {
+    Command::new("diagram")
+        .about("Draw a position from a game as an SVG image")
+        .arg(
+            Arg::new("file")
+                .short('f')
+                .long("file")
+                .required(true)
+                .num_args(1..)
+                .help("File or files containing the game"),
+        )
+        .arg(
+            Arg::new("game")
+                .short('g')
+                .long("game")
+                .value_parser(value_parser!(usize))
+                .default_value("1")
+                .help("Game to draw, counting from 1 across the files"),
+        )
+        .arg(
+            Arg::new("ply")
+                .short('p')
+                .long("ply")
+                .value_parser(value_parser!(usize))
+                .help("Number of plies into the game to draw the board after, or the final position if not given"),
+        )
+        .arg(
+            Arg::new("perspective")
+                .long("perspective")
+                .value_parser(["white", "black"])
+                .default_value("white")
+                .help("Side to view the board from"),
+        )
+        .arg(
+            Arg::new("output")
+                .short('o')
+                .long("output")
+                .help("File to write the image to, or standard output if not given"),
+        )
+}
+
+fn gif() -> Command {
+    Command::new("gif")
+        .about("Animate a game as a GIF, one frame per move")
+        .arg(
+            Arg::new("file")
+                .short('f')
+                .long("file")
+                .required(true)
+                .num_args(1..)
+                .help("File or files containing the game"),
+        )
+        .arg(
+            Arg::new("game")
+                .short('g')
+                .long("game")
+                .value_parser(value_parser!(usize))
+                .default_value("1")
+                .help("Game to animate, counting from 1 across the files"),
+        )
+        .arg(
+            Arg::new("delay")
+                .short('d')
+                .long("delay")
+                .value_parser(value_parser!(u64))
+                .default_value("1000")
+                .help("Time each move is shown for in milliseconds"),
+        )
+        .arg(
+            Arg::new("perspective")
+                .long("perspective")
+                .value_parser(["white", "black"])
+                .default_value("white")
+                .help("Side to view the board from"),
+        )
+        .arg(
+            Arg::new("output")
+                .short('o')
+                .long("output")
+                .help("File to write the animation to, or game<N>.gif if not given"),
+        )
+}
+
+fn check() -> Command {
+    Command::new("check")
+        .about("Read and replay every game, reporting problems with them")
+        .arg(
+            Arg::new("file")
+                .required(true)
+                .num_args(1..)
+                .help("File or files to check"),
+        )
+        .arg(
+            Arg::new("format")
+                .long("format")
+                .value_parser(["text", "json"])
+                .default_value("text")
+                .help("Format to report problems in"),
+        )
+        .arg(
+            Arg::new("strict")
+                .long("strict")
+                .action(ArgAction::SetTrue)
+                .help("Exit with an error status on warnings as well as errors"),
+        )
+}
+
+fn fmt() -> Command {
+    Command::new("fmt")
+        .about("Rewrite games as standard PGN, repairing their moves and tags")
+        .arg(
+            Arg::new("file")
+                .required(true)
+                .num_args(1..)
+                .help("File or files to format"),
+        )
+        .arg(
+            Arg::new("in-place")
+                .short('i')
+                .long("in-place")
+                .action(ArgAction::SetTrue)
+                .help("Overwrite each file rather than printing the formatted games"),
+        )
+}
+
+fn filter() -> Command {
+    Command::new("filter")
+        .about("Write the games matching every condition given")
+        .arg(
+            Arg::new("file")
+                .required(true)
+                .num_args(1..)
+                .help("File or files to filter"),
+        )
+        .arg(
+            Arg::new("player")
+                .long("player")
+                .help("Part of either player's name"),
+        )
+        .arg(
+            Arg::new("from")
+                .long("from")
+                .help("Earliest date, as a year, month or day, e.g. 2023.07"),
+        )
+        .arg(
+            Arg::new("to")
+                .long("to")
+                .help("Latest date, as a year, month or day, e.g. 2023.07"),
+        )
+        .arg(
+            Arg::new("result")
+                .long("result")
+                .value_parser(["1-0", "0-1", "1/2-1/2", "*"])
+                .help("Result of the game"),
+        )
+        .arg(
+            Arg::new("min-rating")
+                .long("min-rating")
+                .value_parser(value_parser!(u32))
+                .help("Lowest rating both players must have"),
+        )
+        .arg(
+            Arg::new("max-rating")
+                .long("max-rating")
+                .value_parser(value_parser!(u32))
+                .help("Highest rating both players may have"),
+        )
+        .arg(
+            Arg::new("eco")
+                .long("eco")
+                .help("ECO code, group or range of codes, e.g. B9 or C60-C99"),
+        )
+        .arg(
+            Arg::new("query")
+                .short('q')
+                .long("query")
+                .help("Other conditions on tags, e.g. 'Event=Olympiad Round<5'"),
+        )
+        .arg(
+            Arg::new("output")
+                .short('o')
+                .long("output")
+                .help("File to write the games to, or standard output if not given"),
+        )
+}
+
+fn split() -> Command {
+    Command::new("split")
+        .about("Split games into a file for each event, round or player")
+        .arg(
+            Arg::new("file")
+                .required(true)
+                .num_args(1..)
+                .help("File or files to split"),
+        )
+        .arg(
+            Arg::new("by")
+                .long("by")
+                .required(true)
+                .value_parser(["event", "round", "player"])
+                .help("Tag to split the games by"),
+        )
+        .arg(
+            Arg::new("directory")
+                .short('d')
+                .long("directory")
+                .default_value(".")
+                .help("Directory to write the files to"),
+        )
+}
+
+fn merge() -> Command {
+    Command::new("merge")
+        .about("Combine the games from several files into one")
+        .arg(
+            Arg::new("file")
+                .required(true)
+                .num_args(1..)
+                .help("Files to merge"),
+        )
+        .arg(
+            Arg::new("dedupe")
+                .long("dedupe")
+                .action(ArgAction::SetTrue)
+                .help("Keep only the richest copy of a game appearing more than once"),
+        )
+        .arg(
+            Arg::new("output")
+                .short('o')
+                .long("output")
+                .help("File to write the games to, or standard output if not given"),
+        )
+}
+
+fn duplicates() -> Command {
+    Command::new("duplicates")
+        .about("List the games appearing more than once, even with their tags written differently")
+        .arg(
+            Arg::new("file")
+                .required(true)
+                .num_args(1..)
+                .help("File or files to look for duplicates in"),
+        )
 }
 
 #[cfg(test)]
@@ -17,21 +472,292 @@ mod tests {
 
     #[test]
     fn returns_err_if_no_file_provided() {
-        let matches = pigin().try_get_matches_from(&["pgn"]);
+        let matches = pigin().try_get_matches_from(["pgn"]);
         assert!(matches.is_err())
     }
 
     #[test]
     fn parses_file() {
-        let matches = pigin().get_matches_from(&["pgn", "--file", "example.pgn"]);
+        let matches = pigin().get_matches_from(["pgn", "--file", "example.pgn"]);
         let files: Vec<_> = matches.get_many::<String>("file").unwrap().collect();
         assert_eq!(files, vec!["example.pgn"])
     }
 
     #[test]
     fn parses_multiple_files() {
-        let matches = pigin().get_matches_from(&["pgn", "--file", "example1.pgn", "example2.pgn"]);
+        let matches = pigin().get_matches_from(["pgn", "--file", "example1.pgn", "example2.pgn"]);
         let files: Vec<_> = matches.get_many::<String>("file").unwrap().collect();
         assert_eq!(files, vec!["example1.pgn", "example2.pgn"])
     }
+
+    #[test]
+    fn parses_encoding() {
+        let matches =
+            pigin().get_matches_from(["pgn", "-f", "example.pgn", "--encoding", "latin1"]);
+        assert_eq!(matches.get_one::<String>("encoding").unwrap(), "latin1");
+
+        let matches =
+            pigin().get_matches_from(["pgn", "check", "example.pgn", "--encoding", "latin1"]);
+        let (_, matches) = matches.subcommand().unwrap();
+        assert_eq!(matches.get_one::<String>("encoding").unwrap(), "latin1");
+    }
+
+    #[test]
+    fn parses_config() {
+        let matches =
+            pigin().get_matches_from(["pgn", "--file", "example.pgn", "--config", "pigin.toml"]);
+        let config = matches.get_one::<String>("config").unwrap();
+        assert_eq!(config, "pigin.toml")
+    }
+
+    #[test]
+    fn parses_engine() {
+        let matches = pigin().get_matches_from([
+            "pgn",
+            "--file",
+            "example.pgn",
+            "--engine",
+            "/usr/bin/stockfish",
+        ]);
+        let engine = matches.get_one::<String>("engine").unwrap();
+        assert_eq!(engine, "/usr/bin/stockfish")
+    }
+
+    #[test]
+    fn parses_autoplay() {
+        let matches = pigin().get_matches_from(["pgn", "--file", "example.pgn", "--autoplay"]);
+        assert!(matches.get_flag("autoplay"))
+    }
+
+    #[test]
+    fn parses_annotate() {
+        let matches = pigin().get_matches_from([
+            "pgn",
+            "annotate",
+            "--file",
+            "example.pgn",
+            "--output",
+            "annotated.pgn",
+            "--depth",
+            "20",
+        ]);
+        let (name, annotate) = matches.subcommand().unwrap();
+        assert_eq!(name, "annotate");
+        assert_eq!(
+            annotate.get_one::<String>("output").unwrap(),
+            "annotated.pgn"
+        );
+        assert_eq!(annotate.get_one::<u32>("depth"), Some(&20));
+    }
+
+    #[test]
+    fn parses_position() {
+        let fen = "rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq - 0 1";
+        let matches =
+            pigin().get_matches_from(["pgn", "position", "--file", "example.pgn", "--fen", fen]);
+        let (name, position) = matches.subcommand().unwrap();
+        assert_eq!(name, "position");
+        assert_eq!(position.get_one::<String>("fen").unwrap(), fen);
+    }
+
+    #[test]
+    fn parses_pattern() {
+        let matches = pigin().get_matches_from([
+            "pgn",
+            "pattern",
+            "--file",
+            "example.pgn",
+            "--pattern",
+            "R+P vs R",
+        ]);
+        let (name, pattern) = matches.subcommand().unwrap();
+        assert_eq!(name, "pattern");
+        assert_eq!(pattern.get_one::<String>("pattern").unwrap(), "R+P vs R");
+    }
+
+    #[test]
+    fn parses_stats() {
+        let matches = pigin().get_matches_from([
+            "pgn",
+            "stats",
+            "--file",
+            "example.pgn",
+            "--player",
+            "fischer",
+        ]);
+        let (name, stats) = matches.subcommand().unwrap();
+        assert_eq!(name, "stats");
+        assert_eq!(stats.get_one::<String>("player").unwrap(), "fischer");
+    }
+
+    #[test]
+    fn parses_export() {
+        let matches = pigin().get_matches_from([
+            "pgn",
+            "export",
+            "--file",
+            "example.pgn",
+            "--format",
+            "json",
+        ]);
+        let (name, export) = matches.subcommand().unwrap();
+        assert_eq!(name, "export");
+        assert_eq!(export.get_one::<String>("format").unwrap(), "json");
+    }
+
+    #[test]
+    fn returns_err_if_unknown_format() {
+        assert!(pigin()
+            .try_get_matches_from(["pgn", "export", "--file", "example.pgn", "--format", "xml"])
+            .is_err());
+    }
+
+    #[test]
+    fn parses_show() {
+        let matches = pigin().get_matches_from([
+            "pgn",
+            "show",
+            "--file",
+            "example.pgn",
+            "--game",
+            "3",
+            "--ply",
+            "24",
+        ]);
+        let (name, show) = matches.subcommand().unwrap();
+        assert_eq!(name, "show");
+        assert_eq!(show.get_one::<usize>("game"), Some(&3));
+        assert_eq!(show.get_one::<usize>("ply"), Some(&24));
+        assert_eq!(show.get_one::<String>("perspective").unwrap(), "white");
+    }
+
+    #[test]
+    fn parses_diagram() {
+        let matches = pigin().get_matches_from([
+            "pgn",
+            "diagram",
+            "--file",
+            "example.pgn",
+            "--perspective",
+            "black",
+            "-o",
+            "position.svg",
+        ]);
+        let (name, diagram) = matches.subcommand().unwrap();
+        assert_eq!(name, "diagram");
+        assert_eq!(diagram.get_one::<usize>("game"), Some(&1));
+        assert_eq!(diagram.get_one::<usize>("ply"), None);
+        assert_eq!(diagram.get_one::<String>("perspective").unwrap(), "black");
+        assert_eq!(diagram.get_one::<String>("output").unwrap(), "position.svg");
+    }
+
+    #[test]
+    fn parses_gif() {
+        let matches = pigin().get_matches_from([
+            "pgn",
+            "gif",
+            "--file",
+            "example.pgn",
+            "--game",
+            "2",
+            "--delay",
+            "500",
+        ]);
+        let (name, gif) = matches.subcommand().unwrap();
+        assert_eq!(name, "gif");
+        assert_eq!(gif.get_one::<usize>("game"), Some(&2));
+        assert_eq!(gif.get_one::<u64>("delay"), Some(&500));
+        assert_eq!(gif.get_one::<String>("perspective").unwrap(), "white");
+        assert_eq!(gif.get_one::<String>("output"), None);
+    }
+
+    #[test]
+    fn parses_check() {
+        let matches =
+            pigin().get_matches_from(["pgn", "check", "one.pgn", "two.pgn", "--format", "json"]);
+        let (name, check) = matches.subcommand().unwrap();
+        assert_eq!(name, "check");
+        assert_eq!(
+            check
+                .get_many::<String>("file")
+                .unwrap()
+                .collect::<Vec<&String>>(),
+            vec!["one.pgn", "two.pgn"]
+        );
+        assert_eq!(check.get_one::<String>("format").unwrap(), "json");
+        assert!(!check.get_flag("strict"));
+    }
+
+    #[test]
+    fn parses_fmt() {
+        let matches = pigin().get_matches_from(["pgn", "fmt", "one.pgn", "-i"]);
+        let (name, fmt) = matches.subcommand().unwrap();
+        assert_eq!(name, "fmt");
+        assert_eq!(fmt.get_one::<String>("file").unwrap(), "one.pgn");
+        assert!(fmt.get_flag("in-place"));
+    }
+
+    #[test]
+    fn parses_filter() {
+        let matches = pigin().get_matches_from([
+            "pgn",
+            "filter",
+            "one.pgn",
+            "--player",
+            "Carlsen",
+            "--from",
+            "2023",
+            "--min-rating",
+            "2700",
+            "--eco",
+            "B90",
+        ]);
+        let (name, filter) = matches.subcommand().unwrap();
+        assert_eq!(name, "filter");
+        assert_eq!(filter.get_one::<String>("player").unwrap(), "Carlsen");
+        assert_eq!(filter.get_one::<String>("from").unwrap(), "2023");
+        assert_eq!(filter.get_one::<u32>("min-rating"), Some(&2700));
+        assert_eq!(filter.get_one::<String>("eco").unwrap(), "B90");
+    }
+
+    #[test]
+    fn parses_split() {
+        let matches = pigin().get_matches_from(["pgn", "split", "one.pgn", "--by", "player"]);
+        let (name, split) = matches.subcommand().unwrap();
+        assert_eq!(name, "split");
+        assert_eq!(split.get_one::<String>("by").unwrap(), "player");
+        assert_eq!(split.get_one::<String>("directory").unwrap(), ".");
+    }
+
+    #[test]
+    fn parses_merge() {
+        let matches = pigin().get_matches_from(["pgn", "merge", "one.pgn", "two.pgn", "--dedupe"]);
+        let (name, merge) = matches.subcommand().unwrap();
+        assert_eq!(name, "merge");
+        assert_eq!(merge.get_many::<String>("file").unwrap().count(), 2);
+        assert!(merge.get_flag("dedupe"));
+    }
+
+    #[test]
+    fn parses_duplicates() {
+        let matches = pigin().get_matches_from(["pgn", "duplicates", "one.pgn", "two.pgn"]);
+        let (name, duplicates) = matches.subcommand().unwrap();
+        assert_eq!(name, "duplicates");
+        assert_eq!(duplicates.get_many::<String>("file").unwrap().count(), 2);
+    }
+
+    #[test]
+    fn returns_err_if_depth_and_movetime() {
+        let matches = pigin().try_get_matches_from([
+            "pgn",
+            "annotate",
+            "--file",
+            "example.pgn",
+            "--depth",
+            "20",
+            "--movetime",
+            "500",
+        ]);
+        assert!(matches.is_err())
+    }
 }
@@ -0,0 +1,233 @@
+use std::collections::HashMap;
+
+use crate::{
+    model::{GameResult, Pgn, Ply},
+    write,
+};
+
+// Copies of the same game, by their index among the games searched
+#[derive(Debug, PartialEq, Eq)]
+pub struct Duplicates {
+    games: Vec<usize>,
+    kept: usize,
+}
+
+impl Duplicates {
+    pub fn games(&self) -> &[usize] {
+        &self.games
+    }
+
+    // The copy with the most tags and annotations, or the first of those with as many
+    pub fn kept(&self) -> usize {
+        self.kept
+    }
+}
+
+// Games are the same when they have the same moves from the same position, and their players,
+// dates and results agree, allowing for names written differently and for what isn't known
+pub fn duplicates(pgns: &[Pgn]) -> Vec<Duplicates> {
+    let mut by_moves: HashMap<String, Vec<usize>> = HashMap::new();
+    for (index, pgn) in pgns.iter().enumerate() {
+        by_moves.entry(moves(pgn)).or_default().push(index);
+    }
+
+    let mut groups: Vec<Vec<usize>> = Vec::new();
+    for indices in by_moves.into_values().filter(|indices| indices.len() > 1) {
+        let mut same_moves: Vec<Vec<usize>> = Vec::new();
+        for index in indices {
+            match same_moves
+                .iter_mut()
+                .find(|group| same_game(&pgns[group[0]], &pgns[index]))
+            {
+                Some(group) => group.push(index),
+                None => same_moves.push(vec![index]),
+            }
+        }
+        groups.extend(same_moves.into_iter().filter(|group| group.len() > 1));
+    }
+    groups.sort();
+
+    groups
+        .into_iter()
+        .map(|games| {
+            let kept = games
+                .iter()
+                .copied()
+                .rev()
+                .max_by_key(|&index| richness(&pgns[index]))
+                .unwrap_or(games[0]);
+            Duplicates { games, kept }
+        })
+        .collect()
+}
+
+// Check markers are left out, as not every site writes them
+fn moves(pgn: &Pgn) -> String {
+    let fen = write::fen(pgn.fen().starting_board()).unwrap_or_default();
+    let moves: Vec<String> = pgn
+        .ply()
+        .iter()
+        .map(|ply| ply.movement().to_string().replace(['+', '#'], ""))
+        .collect();
+    format!("{fen}\n{}", moves.join(" "))
+}
+
+fn same_game(a: &Pgn, b: &Pgn) -> bool {
+    let tag = |pgn: &Pgn, key: &str| pgn.tags().get(key).cloned().unwrap_or_default();
+    same_player(&tag(a, "White"), &tag(b, "White"))
+        && same_player(&tag(a, "Black"), &tag(b, "Black"))
+        && same_date(&tag(a, "Date"), &tag(b, "Date"))
+        && (a.result() == b.result()
+            || a.result() == GameResult::Ongoing
+            || b.result() == GameResult::Ongoing)
+}
+
+// Names match when their surnames do and their first given names agree, allowing for either being
+// an initial or left out, so "Carlsen, Magnus", "Magnus Carlsen", "Carlsen, M" and "Carlsen" are the
+// same player but "Carlsen, Henrik" isn't. An unknown name matches any
+fn same_player(a: &str, b: &str) -> bool {
+    let (Some((surname_a, given_a)), Some((surname_b, given_b))) = (name(a), name(b)) else {
+        return true;
+    };
+    let initial = |given: &str, other: &str| given.chars().count() == 1 && other.starts_with(given);
+    surname_a == surname_b
+        && match (given_a, given_b) {
+            (Some(a), Some(b)) => a == b || initial(&a, &b) || initial(&b, &a),
+            _ => true,
+        }
+}
+
+// The last word of the surname and the first given name, in lower case. Without a comma the
+// surname is taken to be the last word, unless that is an initial as in "Carlsen M"
+fn name(name: &str) -> Option<(String, Option<String>)> {
+    let words = |part: &str| -> Vec<String> {
+        part.split(|c: char| !c.is_alphabetic())
+            .filter(|word| !word.is_empty())
+            .map(str::to_lowercase)
+            .collect()
+    };
+    let (mut surname, given) = match name.split_once(',') {
+        Some((surname, given)) => (words(surname), words(given)),
+        None => {
+            let mut words = words(name);
+            match words.last() {
+                Some(last) if last.chars().count() == 1 && words.len() > 1 => {
+                    let given = words.split_off(1);
+                    (words, given)
+                }
+                _ => {
+                    let surname = words.pop().into_iter().collect();
+                    (surname, words)
+                }
+            }
+        }
+    };
+    let surname = surname.pop()?;
+    Some((surname, given.into_iter().next()))
+}
+
+// Unknown parts of a date match anything, and days may be one apart as sites in different time zones
+// can disagree
+fn same_date(a: &str, b: &str) -> bool {
+    let parts = |date: &str| -> Vec<Option<u32>> {
+        date.split('.')
+            .map(|part| part.parse().ok())
+            .chain(std::iter::repeat(None))
+            .take(3)
+            .collect()
+    };
+    let (a, b) = (parts(a), parts(b));
+    a.iter()
+        .zip(&b)
+        .enumerate()
+        .all(|(index, parts)| match parts {
+            (Some(a), Some(b)) if index == 2 => a.abs_diff(*b) <= 1,
+            (Some(a), Some(b)) => a == b,
+            _ => true,
+        })
+}
+
+fn richness(pgn: &Pgn) -> usize {
+    pgn.tags().inner().len() + usize::from(pgn.comment().is_some()) + annotations(pgn.ply())
+}
+
+fn annotations(ply: &[Ply]) -> usize {
+    ply.iter()
+        .map(|ply| {
+            usize::from(ply.comment().is_some())
+                + usize::from(ply.comment_before().is_some())
+                + ply.nags().len()
+                + ply
+                    .variations()
+                    .iter()
+                    .map(|variation| 1 + annotations(variation))
+                    .sum::<usize>()
+        })
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::parse;
+
+    use super::*;
+
+    fn game(white: &str, date: &str, movetext: &str) -> String {
+        format!(
+            "[White \"{white}\"]\n[Black \"Caruana, Fabiano\"]\n[Date \"{date}\"]\n\
+            [Result \"1-0\"]\n\n{movetext} 1-0\n\n"
+        )
+    }
+
+    fn games(games: &[String]) -> Vec<Pgn> {
+        parse::parse(&games.concat()).unwrap()
+    }
+
+    #[test]
+    fn groups_copies_of_a_game() {
+        let pgns = games(&[
+            game("Carlsen, Magnus", "2023.07.30", "1. e4 e5 2. Qh5"),
+            game("Carlsen, Magnus", "2023.07.30", "1. d4 d5"),
+            game("Magnus Carlsen", "2023.07.31", "1. e4 e5 2. Qh5+"),
+            game("Carlsen, M", "2023.??.??", "1. e4 e5 2. Qh5"),
+        ]);
+        let duplicates = duplicates(&pgns);
+        assert_eq!(duplicates.len(), 1);
+        assert_eq!(duplicates[0].games(), &[0, 2, 3]);
+    }
+
+    #[test]
+    fn tells_apart_games_with_the_same_moves() {
+        let pgns = games(&[
+            game("Carlsen, Magnus", "2023.07.30", "1. e4 e5"),
+            game("Nakamura, Hikaru", "2023.07.30", "1. e4 e5"),
+            game("Carlsen, Magnus", "2022.07.30", "1. e4 e5"),
+            game("?", "2022.07.30", "1. e4 e5"),
+        ]);
+        let duplicates = duplicates(&pgns);
+        assert_eq!(duplicates.len(), 1);
+        assert_eq!(duplicates[0].games(), &[2, 3]);
+    }
+
+    #[test]
+    fn tells_apart_players_sharing_a_surname() {
+        assert!(same_player("Carlsen, Magnus", "Carlsen M."));
+        assert!(same_player("Li, Chao", "Chao Li"));
+        assert!(same_player("Van Foreest, Jorden", "Jorden van Foreest"));
+        assert!(same_player("Carlsen", "Carlsen, Magnus"));
+        assert!(!same_player("Carlsen, Magnus", "Carlsen, Henrik"));
+        assert!(!same_player("Li, Chao", "Li, Wei"));
+        assert!(!same_player("Carlsen, M", "Henrik Carlsen"));
+        assert!(!same_player("Magnus Carlsen", "Magnus Nakamura"));
+    }
+
+    #[test]
+    fn keeps_richest_copy() {
+        let pgns = games(&[
+            game("Carlsen", "2023.07.30", "1. e4 e5"),
+            game("Carlsen", "2023.07.30", "1. e4 {Best by test} e5 $1"),
+            game("Carlsen", "2023.07.30", "1. e4 e5 (1... c5)"),
+        ]);
+        assert_eq!(duplicates(&pgns)[0].kept(), 1);
+    }
+}
@@ -0,0 +1,18 @@
+use std::{error::Error, fmt::Display};
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct CollectionError(String);
+
+impl CollectionError {
+    pub fn new(message: impl Into<String>) -> Self {
+        CollectionError(message.into())
+    }
+}
+
+impl Error for CollectionError {}
+
+impl Display for CollectionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
@@ -0,0 +1,206 @@
+use crate::{
+    model::{GameResult, Pgn},
+    parse,
+    search::TagQuery,
+};
+
+use super::CollectionError;
+
+// Games are kept when they match every condition set
+#[derive(Debug, Default)]
+pub struct Filter {
+    player: Option<String>,
+    from: Option<String>,
+    to: Option<String>,
+    result: Option<GameResult>,
+    min_rating: Option<u32>,
+    max_rating: Option<u32>,
+    eco: Option<(String, String)>,
+    query: TagQuery,
+}
+
+impl Filter {
+    pub fn new() -> Self {
+        Filter::default()
+    }
+
+    // Part of either player's name, ignoring case
+    pub fn player(&mut self, player: &str) -> &mut Self {
+        self.player = Some(player.to_lowercase());
+        self
+    }
+
+    // Dates can be given as a year, a month or a day, e.g. 2023, 2023.07 or 2023-07-30
+    pub fn dates(&mut self, from: Option<&str>, to: Option<&str>) -> &mut Self {
+        self.from = from.map(|from| from.replace('-', "."));
+        self.to = to.map(|to| to.replace('-', "."));
+        self
+    }
+
+    pub fn result(&mut self, result: &str) -> Result<&mut Self, CollectionError> {
+        let result = parse::result(result).map_err(|e| CollectionError::new(e.message()))?;
+        self.result = Some(result);
+        Ok(self)
+    }
+
+    // Both players must be rated within the range
+    pub fn ratings(&mut self, min: Option<u32>, max: Option<u32>) -> &mut Self {
+        self.min_rating = min;
+        self.max_rating = max;
+        self
+    }
+
+    // A code, a group of codes such as B9, or a range such as B20-B99
+    pub fn eco(&mut self, eco: &str) -> Result<&mut Self, CollectionError> {
+        let eco = eco.trim().to_uppercase();
+        let (low, high) = eco.split_once('-').unwrap_or((&eco, &eco));
+        if !is_eco(low) || !is_eco(high) {
+            return Err(CollectionError::new(format!(
+                "'{eco}' is not an ECO code or range"
+            )));
+        }
+        self.eco = Some((low.to_string(), high.to_string()));
+        Ok(self)
+    }
+
+    // Any other conditions on tags, written as in the viewer's search
+    pub fn query(&mut self, query: &str) -> Result<&mut Self, CollectionError> {
+        self.query = TagQuery::parse(query).map_err(|e| CollectionError::new(e.to_string()))?;
+        Ok(self)
+    }
+
+    pub fn matches(&self, pgn: &Pgn) -> bool {
+        let tag = |key: &str| pgn.tags().get(key).map(|value| value.trim());
+
+        let player = self.player.as_ref().map_or(true, |player| {
+            ["White", "Black"].into_iter().any(|key| {
+                tag(key).is_some_and(|name| name.to_lowercase().contains(player.as_str()))
+            })
+        });
+        let date = match tag("Date") {
+            Some(date) => {
+                self.from
+                    .as_ref()
+                    .map_or(true, |from| within(date, from, true))
+                    && self.to.as_ref().map_or(true, |to| within(date, to, false))
+            }
+            None => self.from.is_none() && self.to.is_none(),
+        };
+        let result = self.result.map_or(true, |result| result == pgn.result());
+        let ratings = (self.min_rating.is_none() && self.max_rating.is_none())
+            || ["WhiteElo", "BlackElo"].into_iter().all(|key| {
+                tag(key)
+                    .and_then(|rating| rating.parse::<u32>().ok())
+                    .is_some_and(|rating| {
+                        self.min_rating.map_or(true, |min| rating >= min)
+                            && self.max_rating.map_or(true, |max| rating <= max)
+                    })
+            });
+        let eco = self.eco.as_ref().map_or(true, |(low, high)| {
+            tag("ECO").is_some_and(|eco| within(eco, low, true) && within(eco, high, false))
+        });
+
+        player && date && result && ratings && eco && self.query.matches(pgn)
+    }
+}
+
+// Compares on as many characters as the bound has, so that a date in July 2023 is both on or after
+// 2023.07 and on or before it. Unknown parts of a date, written with ?, match neither way
+fn within(value: &str, bound: &str, lower: bool) -> bool {
+    let prefix: String = value.chars().take(bound.chars().count()).collect();
+    if prefix.contains('?') {
+        return false;
+    }
+    if lower {
+        prefix.as_str() >= bound
+    } else {
+        prefix.as_str() <= bound
+    }
+}
+
+fn is_eco(eco: &str) -> bool {
+    let mut chars = eco.chars();
+    chars.next().is_some_and(|c| ('A'..='E').contains(&c))
+        && eco.len() <= 3
+        && chars.all(|c| c.is_ascii_digit())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::parse;
+
+    use super::*;
+
+    fn games() -> Vec<Pgn> {
+        parse::parse(
+            "[White \"Carlsen, Magnus\"]\n[Black \"Caruana, Fabiano\"]\n[Date \"2023.07.30\"]\n\
+            [WhiteElo \"2835\"]\n[BlackElo \"2782\"]\n[ECO \"B90\"]\n[Result \"1-0\"]\n\n1. e4 1-0\n\n\
+            [White \"Nakamura, Hikaru\"]\n[Black \"Carlsen, Magnus\"]\n[Date \"2021.??.??\"]\n\
+            [WhiteElo \"2736\"]\n[ECO \"C65\"]\n[Result \"1/2-1/2\"]\n\n1. e4 1/2-1/2\n\n",
+        )
+        .unwrap()
+    }
+
+    fn kept(filter: &Filter) -> Vec<usize> {
+        games()
+            .iter()
+            .enumerate()
+            .filter(|(_, pgn)| filter.matches(pgn))
+            .map(|(index, _)| index)
+            .collect()
+    }
+
+    #[test]
+    fn keeps_everything_by_default() {
+        assert_eq!(kept(&Filter::new()), vec![0, 1]);
+    }
+
+    #[test]
+    fn filters_by_player() {
+        assert_eq!(kept(Filter::new().player("carlsen")), vec![0, 1]);
+        assert_eq!(kept(Filter::new().player("Caruana")), vec![0]);
+        assert_eq!(kept(Filter::new().player("Anand")), Vec::<usize>::new());
+    }
+
+    #[test]
+    fn filters_by_date() {
+        assert_eq!(kept(Filter::new().dates(Some("2023-07"), None)), vec![0]);
+        assert_eq!(
+            kept(Filter::new().dates(None, Some("2023.07.29"))),
+            Vec::<usize>::new()
+        );
+        assert_eq!(
+            kept(Filter::new().dates(Some("2021"), Some("2021"))),
+            vec![1]
+        );
+    }
+
+    #[test]
+    fn filters_by_result() {
+        assert_eq!(kept(Filter::new().result("1/2-1/2").unwrap()), vec![1]);
+        assert!(Filter::new().result("draw").is_err());
+    }
+
+    #[test]
+    fn filters_by_rating() {
+        assert_eq!(kept(Filter::new().ratings(Some(2700), None)), vec![0]);
+        assert_eq!(
+            kept(Filter::new().ratings(None, Some(2800))),
+            Vec::<usize>::new()
+        );
+    }
+
+    #[test]
+    fn filters_by_eco() {
+        assert_eq!(kept(Filter::new().eco("b9").unwrap()), vec![0]);
+        assert_eq!(kept(Filter::new().eco("C00-C99").unwrap()), vec![1]);
+        assert!(Filter::new().eco("Z00").is_err());
+    }
+
+    #[test]
+    fn combines_conditions() {
+        let mut filter = Filter::new();
+        filter.player("Carlsen").query("Black=Carlsen").unwrap();
+        assert_eq!(kept(&filter), vec![1]);
+    }
+}
@@ -0,0 +1,69 @@
+use std::collections::HashMap;
+
+use crate::model::Pgn;
+
+use super::duplicate::duplicates;
+
+// The games from every collection in turn. When deduplicating, each game found more than once is
+// kept where it first appears, but as its richest copy
+pub fn merge(collections: Vec<Vec<Pgn>>, dedupe: bool) -> Vec<Pgn> {
+    let pgns: Vec<Pgn> = collections.into_iter().flatten().collect();
+    if !dedupe {
+        return pgns;
+    }
+
+    // Where each game goes, by the index of the copy written there, or None to leave it out
+    let mut placed: HashMap<usize, Option<usize>> = HashMap::new();
+    for group in duplicates(&pgns) {
+        for &index in group.games() {
+            placed.insert(index, None);
+        }
+        placed.insert(group.games()[0], Some(group.kept()));
+    }
+
+    let mut pgns: Vec<Option<Pgn>> = pgns.into_iter().map(Some).collect();
+    (0..pgns.len())
+        .filter_map(|index| {
+            let copy = placed.get(&index).copied().unwrap_or(Some(index))?;
+            pgns[copy].take()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::parse;
+
+    use super::*;
+
+    const GAME: &str = "[White \"One\"]\n[Black \"Two\"]\n[Result \"1-0\"]\n\n1. e4 e5 1-0\n\n";
+
+    fn games(input: &str) -> Vec<Pgn> {
+        parse::parse(input).unwrap()
+    }
+
+    #[test]
+    fn merges_in_order() {
+        let merged = merge(vec![games(GAME), games(&GAME.replace("e5", "c5"))], false);
+        assert_eq!(merged.len(), 2);
+        assert_eq!(merged[1].ply()[1].movement().to_string(), "c5");
+    }
+
+    #[test]
+    fn drops_duplicates() {
+        let annotated = GAME.replace("e5", "e5 {A comment}");
+        let merged = merge(
+            vec![
+                games(&format!("{GAME}{annotated}")),
+                games(&GAME.replace("One", "Three")),
+            ],
+            true,
+        );
+        assert_eq!(merged.len(), 2);
+        assert_eq!(
+            merged[0].ply()[1].comment(),
+            Some(&String::from("A comment"))
+        );
+        assert_eq!(merged[1].tags().get("White"), Some(&String::from("Three")));
+    }
+}
@@ -0,0 +1,11 @@
+mod duplicate;
+mod error;
+mod filter;
+mod merge;
+mod split;
+
+pub use duplicate::duplicates;
+pub use error::CollectionError;
+pub use filter::Filter;
+pub use merge::merge;
+pub use split::{split, SplitBy};
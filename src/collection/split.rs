@@ -0,0 +1,141 @@
+use std::collections::BTreeMap;
+
+use crate::model::Pgn;
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum SplitBy {
+    Event,
+    Round,
+    Player,
+}
+
+// The games to go in each file, by file name. A game is put in a file for each of its players when
+// splitting by player, and games without the tag are put together in unknown.pgn. Rounds are often
+// numbered by board as well, as in 3.2, so only the part before the first full stop counts
+pub fn split(pgns: &[Pgn], by: SplitBy) -> BTreeMap<String, Vec<&Pgn>> {
+    let mut files: BTreeMap<String, Vec<&Pgn>> = BTreeMap::new();
+    for pgn in pgns {
+        let tags: &[&str] = match by {
+            SplitBy::Event => &["Event"],
+            SplitBy::Round => &["Round"],
+            SplitBy::Player => &["White", "Black"],
+        };
+        let mut names: Vec<String> = tags
+            .iter()
+            .map(|&tag| {
+                let value = pgn.tags().get(tag).map_or("", |value| value.trim());
+                let value = match by {
+                    SplitBy::Round => value.split('.').next().unwrap_or_default().trim(),
+                    _ => value,
+                };
+                match (value, by) {
+                    ("" | "?", _) => String::from("unknown.pgn"),
+                    (value, SplitBy::Round) => file_name(&format!("round-{value}")),
+                    (value, _) => file_name(value),
+                }
+            })
+            .collect();
+        // Someone playing themselves only needs the game once
+        names.dedup();
+        for name in names {
+            files.entry(name).or_default().push(pgn);
+        }
+    }
+    files
+}
+
+// Anything but letters, digits, hyphens and full stops is replaced, so the name is safe to use
+fn file_name(value: &str) -> String {
+    let mut name = String::new();
+    for c in value.chars() {
+        if c.is_alphanumeric() || c == '-' || c == '.' {
+            name.push(c);
+        } else if !name.ends_with('_') {
+            name.push('_');
+        }
+    }
+    let name = name.trim_matches(['_', '.']);
+    match name {
+        "" => String::from("unknown.pgn"),
+        name => format!("{name}.pgn"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::parse;
+
+    use super::*;
+
+    fn games() -> Vec<Pgn> {
+        parse::parse(
+            "[Event \"Tata Steel\"]\n[Round \"1\"]\n[White \"Carlsen, Magnus\"]\n\
+            [Black \"Giri, Anish\"]\n[Result \"*\"]\n\n1. e4 *\n\n\
+            [Event \"Tata Steel\"]\n[Round \"2\"]\n[White \"Giri, Anish\"]\n\
+            [Black \"?\"]\n[Result \"*\"]\n\n1. d4 *\n\n\
+            [Event \"../Norway Chess\"]\n[Result \"*\"]\n\n1. c4 *\n\n",
+        )
+        .unwrap()
+    }
+
+    fn counts(files: BTreeMap<String, Vec<&Pgn>>) -> Vec<(String, usize)> {
+        files
+            .into_iter()
+            .map(|(name, pgns)| (name, pgns.len()))
+            .collect()
+    }
+
+    #[test]
+    fn splits_by_event() {
+        assert_eq!(
+            counts(split(&games(), SplitBy::Event)),
+            vec![
+                (String::from("Norway_Chess.pgn"), 1),
+                (String::from("Tata_Steel.pgn"), 2)
+            ]
+        );
+    }
+
+    #[test]
+    fn splits_by_round() {
+        assert_eq!(
+            counts(split(&games(), SplitBy::Round)),
+            vec![
+                (String::from("round-1.pgn"), 1),
+                (String::from("round-2.pgn"), 1),
+                (String::from("unknown.pgn"), 1)
+            ]
+        );
+    }
+
+    #[test]
+    fn splits_board_numbered_rounds_by_round() {
+        let games = parse::parse(
+            &["1.1", "1.2", "2.1", "10.3", "?.1"]
+                .map(|round| format!("[Round \"{round}\"]\n[Result \"*\"]\n\n1. e4 *\n\n"))
+                .concat(),
+        )
+        .unwrap();
+        assert_eq!(
+            counts(split(&games, SplitBy::Round)),
+            vec![
+                (String::from("round-1.pgn"), 2),
+                (String::from("round-10.pgn"), 1),
+                (String::from("round-2.pgn"), 1),
+                (String::from("unknown.pgn"), 1)
+            ]
+        );
+    }
+
+    #[test]
+    fn splits_by_player() {
+        assert_eq!(
+            counts(split(&games(), SplitBy::Player)),
+            vec![
+                (String::from("Carlsen_Magnus.pgn"), 1),
+                (String::from("Giri_Anish.pgn"), 2),
+                (String::from("unknown.pgn"), 2)
+            ]
+        );
+    }
+}
@@ -0,0 +1,57 @@
+use serde::Deserialize;
+
+// Thresholds are how many centipawns a move has to lose, compared to the engine's best move, to be
+// marked as an inaccuracy, mistake or blunder
+#[derive(Debug, PartialEq, Eq, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct AnnotateConfig {
+    depth: u32,
+    movetime_ms: Option<u64>,
+    inaccuracy: i32,
+    mistake: i32,
+    blunder: i32,
+}
+
+impl AnnotateConfig {
+    pub fn depth(&self) -> u32 {
+        self.depth
+    }
+
+    // When set, each position is searched for this long rather than to a depth
+    pub fn movetime_ms(&self) -> Option<u64> {
+        self.movetime_ms
+    }
+
+    pub fn inaccuracy(&self) -> i32 {
+        self.inaccuracy
+    }
+
+    pub fn mistake(&self) -> i32 {
+        self.mistake
+    }
+
+    pub fn blunder(&self) -> i32 {
+        self.blunder
+    }
+
+    pub fn use_depth(&mut self, depth: u32) {
+        self.depth = depth;
+        self.movetime_ms = None;
+    }
+
+    pub fn use_movetime_ms(&mut self, movetime_ms: u64) {
+        self.movetime_ms = Some(movetime_ms);
+    }
+}
+
+impl Default for AnnotateConfig {
+    fn default() -> Self {
+        AnnotateConfig {
+            depth: 16,
+            movetime_ms: None,
+            inaccuracy: 50,
+            mistake: 100,
+            blunder: 300,
+        }
+    }
+}
@@ -0,0 +1,37 @@
+use serde::Deserialize;
+
+#[derive(Debug, PartialEq, Eq, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct AutoplayConfig {
+    interval_ms: u64,
+    next_game: bool,
+    start: bool,
+}
+
+impl AutoplayConfig {
+    pub fn interval_ms(&self) -> u64 {
+        self.interval_ms
+    }
+
+    pub fn next_game(&self) -> bool {
+        self.next_game
+    }
+
+    pub fn start(&self) -> bool {
+        self.start
+    }
+
+    pub fn start_on_launch(&mut self) {
+        self.start = true;
+    }
+}
+
+impl Default for AutoplayConfig {
+    fn default() -> Self {
+        AutoplayConfig {
+            interval_ms: 1500,
+            next_game: false,
+            start: false,
+        }
+    }
+}
@@ -0,0 +1,25 @@
+use std::collections::BTreeMap;
+
+use serde::Deserialize;
+
+#[derive(Debug, Default, PartialEq, Eq, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct EngineConfig {
+    path: Option<String>,
+    options: BTreeMap<String, String>,
+}
+
+impl EngineConfig {
+    pub fn path(&self) -> Option<&str> {
+        self.path.as_deref()
+    }
+
+    // UCI options sent to the engine on start-up, e.g. Threads or Hash
+    pub fn options(&self) -> &BTreeMap<String, String> {
+        &self.options
+    }
+
+    pub fn use_path(&mut self, path: &str) {
+        self.path = Some(path.to_string());
+    }
+}
@@ -0,0 +1,45 @@
+use serde::Deserialize;
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Column {
+    White,
+    Black,
+    WhiteElo,
+    BlackElo,
+    Result,
+    Date,
+    Event,
+    Eco,
+    Moves,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct GamesConfig {
+    columns: Vec<Column>,
+}
+
+impl GamesConfig {
+    pub fn columns(&self) -> &[Column] {
+        &self.columns
+    }
+}
+
+impl Default for GamesConfig {
+    fn default() -> Self {
+        GamesConfig {
+            columns: vec![
+                Column::White,
+                Column::WhiteElo,
+                Column::Black,
+                Column::BlackElo,
+                Column::Result,
+                Column::Date,
+                Column::Event,
+                Column::Eco,
+                Column::Moves,
+            ],
+        }
+    }
+}
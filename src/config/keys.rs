@@ -0,0 +1,344 @@
+use std::{collections::BTreeMap, fmt::Display, str::FromStr};
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use serde::{Deserialize, Deserializer};
+
+use super::ConfigError;
+
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Hash, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Action {
+    PreviousPly,
+    NextPly,
+    FirstPly,
+    LastPly,
+    JumpBackwards,
+    JumpForwards,
+    GoToMove,
+    Search,
+    FindPosition,
+    EnterPosition,
+    FindPattern,
+    NextMatch,
+    PreviousMatch,
+    ToggleAutoplay,
+    AutoplayFaster,
+    AutoplaySlower,
+    PreviousGame,
+    NextGame,
+    SortGames,
+    ReverseSort,
+    Analyse,
+    EnterMove,
+    CursorLeft,
+    CursorRight,
+    CursorUp,
+    CursorDown,
+    PickSquare,
+    SaveVariation,
+    ToggleEngine,
+    ToggleExplorer,
+    PreviousExplorerMove,
+    NextExplorerMove,
+    PlayExplorerMove,
+    FlipPerspective,
+    ToggleMetadata,
+    ToggleStats,
+    ToggleHelp,
+    DisplayFen,
+    SaveDiagram,
+    Quit,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub struct Key {
+    code: KeyCode,
+    modifiers: KeyModifiers,
+}
+
+impl Key {
+    pub fn new(code: KeyCode, modifiers: KeyModifiers) -> Self {
+        // Shift is already reflected in the case of a character, so is ignored for them
+        let modifiers = match code {
+            KeyCode::Char(_) => modifiers.difference(KeyModifiers::SHIFT),
+            _ => modifiers,
+        };
+        Key { code, modifiers }
+    }
+}
+
+impl From<KeyEvent> for Key {
+    fn from(event: KeyEvent) -> Self {
+        Key::new(event.code, event.modifiers)
+    }
+}
+
+impl FromStr for Key {
+    type Err = ConfigError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (modifiers, name) = match s.split_once('-') {
+            Some((modifier, name)) if !name.is_empty() => {
+                let modifier = match modifier.to_lowercase().as_str() {
+                    "ctrl" => KeyModifiers::CONTROL,
+                    "alt" => KeyModifiers::ALT,
+                    _ => return Err(ConfigError::new(format!("'{s}' is not a valid key"))),
+                };
+                (modifier, name)
+            }
+            _ => (KeyModifiers::NONE, s),
+        };
+
+        let mut chars = name.chars();
+        let code = match (chars.next(), chars.next()) {
+            (Some(c), None) => KeyCode::Char(c),
+            _ => match name.to_lowercase().as_str() {
+                "left" => KeyCode::Left,
+                "right" => KeyCode::Right,
+                "up" => KeyCode::Up,
+                "down" => KeyCode::Down,
+                "home" => KeyCode::Home,
+                "end" => KeyCode::End,
+                "pageup" => KeyCode::PageUp,
+                "pagedown" => KeyCode::PageDown,
+                "enter" => KeyCode::Enter,
+                "esc" => KeyCode::Esc,
+                "tab" => KeyCode::Tab,
+                "backspace" => KeyCode::Backspace,
+                "delete" => KeyCode::Delete,
+                "insert" => KeyCode::Insert,
+                "space" => KeyCode::Char(' '),
+                other => other
+                    .strip_prefix('f')
+                    .and_then(|number| number.parse::<u8>().ok())
+                    .filter(|number| (1..=12).contains(number))
+                    .map(KeyCode::F)
+                    .ok_or_else(|| ConfigError::new(format!("'{s}' is not a valid key")))?,
+            },
+        };
+
+        Ok(Key::new(code, modifiers))
+    }
+}
+
+impl<'de> Deserialize<'de> for Key {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let key = String::deserialize(deserializer)?;
+        key.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+impl Display for Key {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.modifiers.contains(KeyModifiers::CONTROL) {
+            write!(f, "Ctrl-")?;
+        }
+        if self.modifiers.contains(KeyModifiers::ALT) {
+            write!(f, "Alt-")?;
+        }
+
+        match self.code {
+            KeyCode::Char(' ') => write!(f, "Space"),
+            KeyCode::Char(c) => write!(f, "{c}"),
+            KeyCode::Left => write!(f, "←"),
+            KeyCode::Right => write!(f, "→"),
+            KeyCode::Up => write!(f, "↑"),
+            KeyCode::Down => write!(f, "↓"),
+            KeyCode::Home => write!(f, "Home"),
+            KeyCode::End => write!(f, "End"),
+            KeyCode::PageUp => write!(f, "PgUp"),
+            KeyCode::PageDown => write!(f, "PgDn"),
+            KeyCode::Enter => write!(f, "Enter"),
+            KeyCode::Esc => write!(f, "Esc"),
+            KeyCode::Tab => write!(f, "Tab"),
+            KeyCode::Backspace => write!(f, "Backspace"),
+            KeyCode::Delete => write!(f, "Del"),
+            KeyCode::Insert => write!(f, "Ins"),
+            KeyCode::F(number) => write!(f, "F{number}"),
+            _ => write!(f, "?"),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct KeyBindings(BTreeMap<Action, Vec<Key>>);
+
+impl KeyBindings {
+    pub fn keys(&self, action: Action) -> &[Key] {
+        self.0.get(&action).map_or(&[], Vec::as_slice)
+    }
+
+    pub fn action(&self, key: Key) -> Option<Action> {
+        self.0
+            .iter()
+            .find_map(|(&action, keys)| keys.contains(&key).then_some(action))
+    }
+
+    // Bindings given in the config file replace the defaults for that action only
+    fn with_overrides(overrides: BTreeMap<Action, Vec<Key>>) -> Result<Self, ConfigError> {
+        let mut bindings = KeyBindings::default();
+        bindings.0.extend(overrides);
+
+        let mut bound: Vec<(Key, Action)> = Vec::new();
+        for (&action, keys) in &bindings.0 {
+            for &key in keys {
+                if let Some((_, other)) = bound.iter().find(|(k, _)| *k == key) {
+                    return Err(ConfigError::new(format!(
+                        "Key '{key}' is bound to both {other:?} and {action:?}"
+                    )));
+                }
+                bound.push((key, action));
+            }
+        }
+
+        Ok(bindings)
+    }
+}
+
+impl Default for KeyBindings {
+    fn default() -> Self {
+        let char_key = |c: char| Key::new(KeyCode::Char(c), KeyModifiers::NONE);
+        let key = |code: KeyCode| Key::new(code, KeyModifiers::NONE);
+
+        KeyBindings(BTreeMap::from([
+            (Action::PreviousPly, vec![char_key('a'), key(KeyCode::Left)]),
+            (Action::NextPly, vec![char_key('d'), key(KeyCode::Right)]),
+            (Action::FirstPly, vec![key(KeyCode::Home)]),
+            (Action::LastPly, vec![key(KeyCode::End)]),
+            (Action::JumpBackwards, vec![key(KeyCode::PageUp)]),
+            (Action::JumpForwards, vec![key(KeyCode::PageDown)]),
+            (Action::GoToMove, vec![char_key(':')]),
+            (Action::Search, vec![char_key('/')]),
+            (Action::FindPosition, vec![char_key('p')]),
+            (Action::EnterPosition, vec![char_key('P')]),
+            (Action::FindPattern, vec![char_key('#')]),
+            (Action::NextMatch, vec![char_key(']')]),
+            (Action::PreviousMatch, vec![char_key('[')]),
+            (Action::ToggleAutoplay, vec![char_key(' ')]),
+            (Action::AutoplayFaster, vec![char_key('+')]),
+            (Action::AutoplaySlower, vec![char_key('-')]),
+            (Action::PreviousGame, vec![char_key('w'), key(KeyCode::Up)]),
+            (Action::NextGame, vec![char_key('s'), key(KeyCode::Down)]),
+            (Action::SortGames, vec![char_key('o')]),
+            (Action::ReverseSort, vec![char_key('O')]),
+            (Action::Analyse, vec![char_key('n')]),
+            (Action::EnterMove, vec![char_key('m')]),
+            (Action::CursorLeft, vec![char_key('H')]),
+            (Action::CursorRight, vec![char_key('L')]),
+            (Action::CursorUp, vec![char_key('K')]),
+            (Action::CursorDown, vec![char_key('J')]),
+            (Action::PickSquare, vec![key(KeyCode::Enter)]),
+            (Action::SaveVariation, vec![char_key('v')]),
+            (Action::ToggleEngine, vec![char_key('g')]),
+            (Action::ToggleExplorer, vec![char_key('t')]),
+            (Action::PreviousExplorerMove, vec![char_key('i')]),
+            (Action::NextExplorerMove, vec![char_key('k')]),
+            (Action::PlayExplorerMove, vec![key(KeyCode::Tab)]),
+            (Action::FlipPerspective, vec![char_key('e')]),
+            (Action::ToggleMetadata, vec![char_key('x')]),
+            (Action::ToggleStats, vec![char_key('c')]),
+            (Action::ToggleHelp, vec![char_key('?')]),
+            (Action::DisplayFen, vec![char_key('f')]),
+            (Action::SaveDiagram, vec![char_key('D')]),
+            (Action::Quit, vec![char_key('q')]),
+        ]))
+    }
+}
+
+impl<'de> Deserialize<'de> for KeyBindings {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let overrides = BTreeMap::<Action, Vec<Key>>::deserialize(deserializer)?;
+        KeyBindings::with_overrides(overrides).map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod key_tests {
+        use super::*;
+
+        #[test]
+        fn returns_err_if_unknown_key() {
+            assert!("something".parse::<Key>().is_err());
+            assert!("super-a".parse::<Key>().is_err());
+            assert!("f13".parse::<Key>().is_err());
+        }
+
+        #[test]
+        fn parses_character() {
+            let key: Key = "j".parse().unwrap();
+            assert_eq!(key, Key::new(KeyCode::Char('j'), KeyModifiers::NONE))
+        }
+
+        #[test]
+        fn parses_named_key() {
+            let key: Key = "PageDown".parse().unwrap();
+            assert_eq!(key, Key::new(KeyCode::PageDown, KeyModifiers::NONE))
+        }
+
+        #[test]
+        fn parses_modifier() {
+            let key: Key = "ctrl-n".parse().unwrap();
+            assert_eq!(key, Key::new(KeyCode::Char('n'), KeyModifiers::CONTROL))
+        }
+
+        #[test]
+        fn parses_dash() {
+            let key: Key = "-".parse().unwrap();
+            assert_eq!(key, Key::new(KeyCode::Char('-'), KeyModifiers::NONE))
+        }
+
+        #[test]
+        fn ignores_shift_for_characters() {
+            let key = Key::new(KeyCode::Char('A'), KeyModifiers::SHIFT);
+            assert_eq!(key, Key::new(KeyCode::Char('A'), KeyModifiers::NONE))
+        }
+
+        #[test]
+        fn displays_key() {
+            let key = Key::new(KeyCode::Left, KeyModifiers::ALT);
+            assert_eq!(key.to_string(), "Alt-←")
+        }
+    }
+
+    mod key_bindings_tests {
+        use super::*;
+
+        #[test]
+        fn finds_action_for_key() {
+            let bindings = KeyBindings::default();
+            let action = bindings.action(Key::new(KeyCode::Right, KeyModifiers::NONE));
+            assert_eq!(action, Some(Action::NextPly))
+        }
+
+        #[test]
+        fn overrides_only_given_actions() {
+            let overrides = BTreeMap::from([(Action::Quit, vec!["esc".parse().unwrap()])]);
+            let bindings = KeyBindings::with_overrides(overrides).unwrap();
+
+            assert_eq!(
+                bindings.keys(Action::Quit),
+                &[Key::new(KeyCode::Esc, KeyModifiers::NONE)]
+            );
+            assert_eq!(
+                bindings.keys(Action::FlipPerspective),
+                KeyBindings::default().keys(Action::FlipPerspective)
+            );
+        }
+
+        #[test]
+        fn returns_err_if_key_bound_twice() {
+            let overrides = BTreeMap::from([(Action::Quit, vec!["d".parse().unwrap()])]);
+            let bindings = KeyBindings::with_overrides(overrides);
+            assert!(bindings.is_err())
+        }
+    }
+}
@@ -0,0 +1,241 @@
+mod annotate;
+mod autoplay;
+mod engine;
+mod error;
+mod games;
+mod keys;
+mod theme;
+
+use std::{
+    env,
+    ffi::OsString,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use serde::Deserialize;
+
+pub use annotate::AnnotateConfig;
+pub use autoplay::AutoplayConfig;
+pub use engine::EngineConfig;
+pub use error::ConfigError;
+pub use games::{Column, GamesConfig};
+pub use keys::{Action, KeyBindings};
+pub use theme::{MovesTheme, PiecesTheme, Theme};
+
+const CONFIG_DIRECTORY: &str = "pigin";
+const CONFIG_FILE: &str = "config.toml";
+
+#[derive(Debug, Default, PartialEq, Eq, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    theme: Theme,
+    keys: KeyBindings,
+    autoplay: AutoplayConfig,
+    games: GamesConfig,
+    engine: EngineConfig,
+    annotate: AnnotateConfig,
+}
+
+impl Config {
+    pub fn theme(&self) -> &Theme {
+        &self.theme
+    }
+
+    pub fn keys(&self) -> &KeyBindings {
+        &self.keys
+    }
+
+    pub fn autoplay(&self) -> &AutoplayConfig {
+        &self.autoplay
+    }
+
+    pub fn autoplay_mut(&mut self) -> &mut AutoplayConfig {
+        &mut self.autoplay
+    }
+
+    pub fn games(&self) -> &GamesConfig {
+        &self.games
+    }
+
+    pub fn engine(&self) -> &EngineConfig {
+        &self.engine
+    }
+
+    pub fn engine_mut(&mut self) -> &mut EngineConfig {
+        &mut self.engine
+    }
+
+    pub fn annotate(&self) -> &AnnotateConfig {
+        &self.annotate
+    }
+
+    pub fn annotate_mut(&mut self) -> &mut AnnotateConfig {
+        &mut self.annotate
+    }
+}
+
+// An explicitly provided file must exist, whereas a missing file in the default location falls
+// back to the default configuration
+pub fn load(file_name: Option<&str>) -> Result<Config, ConfigError> {
+    match file_name {
+        Some(file_name) => from_file(Path::new(file_name)),
+        None => match default_path(env::var_os("XDG_CONFIG_HOME"), env::var_os("HOME")) {
+            Some(path) if path.is_file() => from_file(&path),
+            _ => Ok(Config::default()),
+        },
+    }
+}
+
+pub fn parse(input: &str) -> Result<Config, ConfigError> {
+    toml::from_str(input).map_err(|e| ConfigError::new(format!("Failed to parse config: {e}")))
+}
+
+fn from_file(path: &Path) -> Result<Config, ConfigError> {
+    let content = fs::read_to_string(path).map_err(|e| {
+        ConfigError::new(format!(
+            "Failed to read config file '{}': {e}",
+            path.display()
+        ))
+    })?;
+    parse(&content)
+}
+
+fn default_path(xdg_config_home: Option<OsString>, home: Option<OsString>) -> Option<PathBuf> {
+    let config_home = xdg_config_home
+        .filter(|directory| !directory.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| home.map(|home| PathBuf::from(home).join(".config")))?;
+
+    Some(config_home.join(CONFIG_DIRECTORY).join(CONFIG_FILE))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod parse_tests {
+        use ratatui::style::Color;
+
+        use super::*;
+
+        #[test]
+        fn returns_default_config_if_empty() {
+            let config = parse("").unwrap();
+            assert_eq!(config, Config::default())
+        }
+
+        #[test]
+        fn returns_err_if_unknown_key() {
+            let config = parse("[theme.board]\nsquares = \"red\"");
+            assert!(config.is_err())
+        }
+
+        #[test]
+        fn returns_err_if_invalid_colour() {
+            let config = parse("[theme.board]\nlight_square = \"not a colour\"");
+            assert!(config.is_err())
+        }
+
+        #[test]
+        fn parses_theme() {
+            let config = parse(
+                "[theme.board]\nlight_square = \"#f0d9b5\"\n\n[theme.pieces]\nunicode = true\nblack = \"black\"",
+            )
+            .unwrap();
+            let theme = config.theme();
+
+            assert_eq!(theme.board().light_square(), Color::Rgb(240, 217, 181));
+            assert_eq!(theme.board().dark_square(), Color::LightBlue);
+            assert_eq!(theme.pieces().black(), Color::Black);
+            assert!(theme.pieces().unicode());
+            assert_eq!(theme.moves(), &MovesTheme::default());
+        }
+
+        #[test]
+        fn parses_keys() {
+            let config = parse("[keys]\nnext_ply = [\"l\", \"ctrl-f\"]").unwrap();
+            let keys: Vec<String> = config
+                .keys()
+                .keys(Action::NextPly)
+                .iter()
+                .map(ToString::to_string)
+                .collect();
+
+            assert_eq!(keys, vec!["l", "Ctrl-f"]);
+        }
+
+        #[test]
+        fn parses_autoplay() {
+            let config = parse("[autoplay]\ninterval_ms = 500\nnext_game = true").unwrap();
+            assert_eq!(config.autoplay().interval_ms(), 500);
+            assert!(config.autoplay().next_game());
+            assert!(!config.autoplay().start());
+        }
+
+        #[test]
+        fn parses_game_columns() {
+            let config = parse("[games]\ncolumns = [\"white\", \"white_elo\", \"eco\"]").unwrap();
+            assert_eq!(
+                config.games().columns(),
+                &[Column::White, Column::WhiteElo, Column::Eco]
+            );
+        }
+
+        #[test]
+        fn returns_err_if_unknown_column() {
+            let config = parse("[games]\ncolumns = [\"opening\"]");
+            assert!(config.is_err())
+        }
+
+        #[test]
+        fn parses_engine() {
+            let config =
+                parse("[engine]\npath = \"/usr/bin/stockfish\"\noptions = { Threads = \"4\" }")
+                    .unwrap();
+            assert_eq!(config.engine().path(), Some("/usr/bin/stockfish"));
+            assert_eq!(
+                config.engine().options().get("Threads").map(String::as_str),
+                Some("4")
+            );
+        }
+
+        #[test]
+        fn parses_annotate() {
+            let config = parse("[annotate]\nmovetime_ms = 500\nblunder = 250").unwrap();
+            assert_eq!(config.annotate().movetime_ms(), Some(500));
+            assert_eq!(config.annotate().blunder(), 250);
+            assert_eq!(config.annotate().mistake(), 100);
+        }
+
+        #[test]
+        fn returns_err_if_unknown_action() {
+            let config = parse("[keys]\njump = [\"j\"]");
+            assert!(config.is_err())
+        }
+    }
+
+    mod default_path_tests {
+        use super::*;
+
+        #[test]
+        fn returns_none_if_no_directories_set() {
+            assert_eq!(default_path(None, None), None)
+        }
+
+        #[test]
+        fn uses_xdg_config_home() {
+            let path = default_path(Some("/xdg".into()), Some("/home/user".into()));
+            assert_eq!(path, Some(PathBuf::from("/xdg/pigin/config.toml")))
+        }
+
+        #[test]
+        fn falls_back_to_home_directory() {
+            let path = default_path(Some("".into()), Some("/home/user".into()));
+            assert_eq!(
+                path,
+                Some(PathBuf::from("/home/user/.config/pigin/config.toml"))
+            )
+        }
+    }
+}
@@ -0,0 +1,132 @@
+use ratatui::style::Color;
+use serde::Deserialize;
+
+#[derive(Debug, Default, PartialEq, Eq, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Theme {
+    board: BoardTheme,
+    pieces: PiecesTheme,
+    moves: MovesTheme,
+}
+
+impl Theme {
+    pub fn board(&self) -> &BoardTheme {
+        &self.board
+    }
+
+    pub fn pieces(&self) -> &PiecesTheme {
+        &self.pieces
+    }
+
+    pub fn moves(&self) -> &MovesTheme {
+        &self.moves
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct BoardTheme {
+    light_square: Color,
+    dark_square: Color,
+    coordinates: Color,
+    cursor: Color,
+    selected: Color,
+}
+
+impl BoardTheme {
+    pub fn light_square(&self) -> Color {
+        self.light_square
+    }
+
+    pub fn dark_square(&self) -> Color {
+        self.dark_square
+    }
+
+    pub fn coordinates(&self) -> Color {
+        self.coordinates
+    }
+
+    pub fn cursor(&self) -> Color {
+        self.cursor
+    }
+
+    pub fn selected(&self) -> Color {
+        self.selected
+    }
+}
+
+impl Default for BoardTheme {
+    fn default() -> Self {
+        BoardTheme {
+            light_square: Color::LightRed,
+            dark_square: Color::LightBlue,
+            coordinates: Color::Gray,
+            cursor: Color::Yellow,
+            selected: Color::Green,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PiecesTheme {
+    white: Color,
+    black: Color,
+    unicode: bool,
+}
+
+impl PiecesTheme {
+    pub fn white(&self) -> Color {
+        self.white
+    }
+
+    pub fn black(&self) -> Color {
+        self.black
+    }
+
+    pub fn unicode(&self) -> bool {
+        self.unicode
+    }
+}
+
+impl Default for PiecesTheme {
+    fn default() -> Self {
+        PiecesTheme {
+            white: Color::White,
+            black: Color::DarkGray,
+            unicode: false,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct MovesTheme {
+    standard: Color,
+    highlighted: Color,
+    result: Color,
+}
+
+impl MovesTheme {
+    pub fn standard(&self) -> Color {
+        self.standard
+    }
+
+    pub fn highlighted(&self) -> Color {
+        self.highlighted
+    }
+
+    pub fn result(&self) -> Color {
+        self.result
+    }
+}
+
+impl Default for MovesTheme {
+    fn default() -> Self {
+        MovesTheme {
+            standard: Color::DarkGray,
+            highlighted: Color::Yellow,
+            result: Color::LightGreen,
+        }
+    }
+}
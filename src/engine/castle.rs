@@ -88,6 +88,7 @@ pub fn kingside(board: &Board, colour: PieceColour) -> Result<Board, EngineError
 
     active_colour::update(&mut next_board);
     remove_castling_for_colour(&mut next_board, colour);
+    next_board.remove_en_passant_square();
     clocks::halfmove(&mut next_board, PieceType::King, false);
     clocks::fullmove(&mut next_board, colour);
 
@@ -130,6 +131,7 @@ pub fn queenside(board: &Board, colour: PieceColour) -> Result<Board, EngineErro
 
     active_colour::update(&mut next_board);
     remove_castling_for_colour(&mut next_board, colour);
+    next_board.remove_en_passant_square();
     clocks::halfmove(&mut next_board, PieceType::King, false);
     clocks::fullmove(&mut next_board, colour);
 
@@ -0,0 +1,567 @@
+use crate::model::{
+    AvailableCastle, Board, Check, MoveQualifier, Movement, Piece, PieceColour, PieceType,
+    PlyMovement, Position, MAX_POSITION, MIN_POSITION,
+};
+
+use super::{apply_move, castle, error::EngineError, moves};
+
+const PIECE_TYPES: &[PieceType] = &[
+    PieceType::Pawn,
+    PieceType::Knight,
+    PieceType::Bishop,
+    PieceType::Rook,
+    PieceType::Queen,
+    PieceType::King,
+];
+
+const PROMOTIONS: &[PieceType] = &[
+    PieceType::Queen,
+    PieceType::Rook,
+    PieceType::Bishop,
+    PieceType::Knight,
+];
+
+const KING_COLUMN: i8 = 4;
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct LegalMove {
+    from: Position,
+    to: Position,
+    movement: PlyMovement,
+    board: Board,
+}
+
+impl LegalMove {
+    // For castling, the squares are those the king moves between
+    pub fn from(&self) -> Position {
+        self.from
+    }
+
+    pub fn to(&self) -> Position {
+        self.to
+    }
+
+    pub fn movement(&self) -> &PlyMovement {
+        &self.movement
+    }
+
+    pub fn board(&self) -> &Board {
+        &self.board
+    }
+
+    pub fn into_movement(self) -> PlyMovement {
+        self.movement
+    }
+}
+
+pub fn moves(board: &Board) -> Vec<LegalMove> {
+    let candidates = candidates(board);
+    candidates
+        .iter()
+        .map(|candidate| legal_move(candidate, &candidates))
+        .collect()
+}
+
+fn legal_move(candidate: &Candidate, candidates: &[Candidate]) -> LegalMove {
+    let check = check(&candidate.board);
+    let movement = match candidate.kind {
+        Kind::Castle(AvailableCastle::WhiteKingside | AvailableCastle::BlackKingside) => {
+            PlyMovement::KingsideCastle {
+                colour: *candidate.piece.colour(),
+                check,
+            }
+        }
+        Kind::Castle(_) => PlyMovement::QueensideCastle {
+            colour: *candidate.piece.colour(),
+            check,
+        },
+        Kind::Piece {
+            promotes_to,
+            capture,
+        } => {
+            let movement = Movement::new(candidate.piece, candidate.to);
+            let qualifier = qualifier(candidate, candidates);
+            match promotes_to {
+                None => PlyMovement::Move {
+                    movement,
+                    qualifier,
+                    check,
+                    capture,
+                },
+                Some(promotes_to) => PlyMovement::Promotion {
+                    movement,
+                    promotes_to,
+                    qualifier,
+                    check,
+                    capture,
+                },
+            }
+        }
+    };
+
+    LegalMove {
+        from: candidate.from,
+        to: candidate.to,
+        movement,
+        board: candidate.board.clone(),
+    }
+}
+
+// Check markers and captures are ignored, as is a qualifier which isn't needed, but one which
+// doesn't match the moving piece rules the move out
+pub fn resolve(board: &Board, movement: &PlyMovement) -> Result<LegalMove, EngineError> {
+    let mut matches = matching(board, movement);
+    match matches.len() {
+        0 => Err(EngineError::new("Not a legal move in this position")),
+        1 => Ok(matches.remove(0)),
+        _ => Err(EngineError::new(
+            "Move is ambiguous: qualify it with the piece's file or rank",
+        )),
+    }
+}
+
+pub fn matching(board: &Board, movement: &PlyMovement) -> Vec<LegalMove> {
+    // Working out check is slow, so only do it for the moves which match
+    let candidates = candidates(board);
+    candidates
+        .iter()
+        .filter(|candidate| is_match(candidate, movement))
+        .map(|candidate| legal_move(candidate, &candidates))
+        .collect()
+}
+
+pub fn in_check(board: &Board, colour: PieceColour) -> bool {
+    board
+        .search(Piece::new(colour, PieceType::King))
+        .first()
+        .is_some_and(|&king| attacked(board, king, opposition(colour)))
+}
+
+pub fn attacked(board: &Board, square: Position, by: PieceColour) -> bool {
+    PIECE_TYPES.iter().any(|&piece_type| {
+        let piece = Piece::new(by, piece_type);
+        board
+            .search(piece)
+            .into_iter()
+            .any(|from| match piece_type {
+                // Pawns move forwards but capture diagonally, so their moves aren't their attacks
+                PieceType::Pawn => {
+                    square.row() - from.row() == forwards(by)
+                        && (square.col() - from.col()).abs() == 1
+                }
+                _ => moves::find(piece, from, board).contains(&square),
+            })
+    })
+}
+
+enum Kind {
+    Castle(AvailableCastle),
+    Piece {
+        promotes_to: Option<PieceType>,
+        capture: bool,
+    },
+}
+
+struct Candidate {
+    piece: Piece,
+    from: Position,
+    to: Position,
+    kind: Kind,
+    board: Board,
+}
+
+fn candidates(board: &Board) -> Vec<Candidate> {
+    let colour = *board.active_colour();
+
+    let mut candidates: Vec<Candidate> = PIECE_TYPES
+        .iter()
+        .flat_map(|&piece_type| {
+            let piece = Piece::new(colour, piece_type);
+            board
+                .search(piece)
+                .into_iter()
+                .flat_map(move |from| piece_candidates(board, piece, from))
+        })
+        .filter(|candidate| !in_check(&candidate.board, colour))
+        .collect();
+
+    candidates.extend(castle_candidates(board, colour));
+    candidates
+}
+
+fn piece_candidates(board: &Board, piece: Piece, from: Position) -> Vec<Candidate> {
+    let is_pawn = *piece.piece_type() == PieceType::Pawn;
+    let last_row = match piece.colour() {
+        PieceColour::White => MAX_POSITION,
+        PieceColour::Black => MIN_POSITION,
+    };
+
+    moves::find(piece, from, board)
+        .into_iter()
+        .flat_map(|to| {
+            let capture =
+                board.occupant(to).is_some() || (is_pawn && board.en_passant_square() == Some(&to));
+            let promotions: Vec<Option<PieceType>> = if is_pawn && to.row() == last_row {
+                PROMOTIONS.iter().copied().map(Some).collect()
+            } else {
+                vec![None]
+            };
+
+            promotions.into_iter().map(move |promotes_to| Candidate {
+                piece,
+                from,
+                to,
+                kind: Kind::Piece {
+                    promotes_to,
+                    capture,
+                },
+                board: apply_move(board, piece, from, to, promotes_to, capture),
+            })
+        })
+        .collect()
+}
+
+fn castle_candidates(board: &Board, colour: PieceColour) -> Vec<Candidate> {
+    let (row, castles) = match colour {
+        PieceColour::White => (
+            MIN_POSITION,
+            [
+                AvailableCastle::WhiteKingside,
+                AvailableCastle::WhiteQueenside,
+            ],
+        ),
+        PieceColour::Black => (
+            MAX_POSITION,
+            [
+                AvailableCastle::BlackKingside,
+                AvailableCastle::BlackQueenside,
+            ],
+        ),
+    };
+    let king = Piece::new(colour, PieceType::King);
+    let rook = Piece::new(colour, PieceType::Rook);
+    let king_from = Position::new(row, KING_COLUMN);
+
+    castles
+        .into_iter()
+        .filter(|castle| board.available_castles().contains(castle))
+        .filter_map(|castle| {
+            let kingside = matches!(
+                castle,
+                AvailableCastle::WhiteKingside | AvailableCastle::BlackKingside
+            );
+            // The squares between king and rook must be empty, and the king mustn't start in,
+            // pass through or land on an attacked square
+            let (rook_column, between, king_path) = if kingside {
+                (MAX_POSITION, 5..=6, 4..=6)
+            } else {
+                (MIN_POSITION, 1..=3, 2..=4)
+            };
+
+            let in_place = board.occupant(king_from) == Some(&king)
+                && board.occupant(Position::new(row, rook_column)) == Some(&rook);
+            let clear = between
+                .into_iter()
+                .all(|column| board.occupant(Position::new(row, column)).is_none());
+            let safe = king_path
+                .into_iter()
+                .all(|column| !attacked(board, Position::new(row, column), opposition(colour)));
+            if !(in_place && clear && safe) {
+                return None;
+            }
+
+            let next_board = if kingside {
+                castle::kingside(board, colour)
+            } else {
+                castle::queenside(board, colour)
+            }
+            .ok()?;
+
+            Some(Candidate {
+                piece: king,
+                from: king_from,
+                to: Position::new(row, if kingside { 6 } else { 2 }),
+                kind: Kind::Castle(castle),
+                board: next_board,
+            })
+        })
+        .collect()
+}
+
+// Pawn captures always name their file. Other pieces are named by file, rank or both when another
+// piece of the same type can also reach the square
+fn qualifier(candidate: &Candidate, candidates: &[Candidate]) -> Option<MoveQualifier> {
+    if *candidate.piece.piece_type() == PieceType::Pawn {
+        return match candidate.kind {
+            Kind::Piece { capture: true, .. } => Some(MoveQualifier::Col(candidate.from.col())),
+            _ => None,
+        };
+    }
+
+    let rivals: Vec<Position> = candidates
+        .iter()
+        .filter(|other| {
+            other.piece == candidate.piece
+                && other.to == candidate.to
+                && other.from != candidate.from
+                && matches!(other.kind, Kind::Piece { .. })
+        })
+        .map(|other| other.from)
+        .collect();
+
+    if rivals.is_empty() {
+        None
+    } else if rivals
+        .iter()
+        .all(|rival| rival.col() != candidate.from.col())
+    {
+        Some(MoveQualifier::Col(candidate.from.col()))
+    } else if rivals
+        .iter()
+        .all(|rival| rival.row() != candidate.from.row())
+    {
+        Some(MoveQualifier::Row(candidate.from.row()))
+    } else {
+        Some(MoveQualifier::Position(candidate.from))
+    }
+}
+
+fn check(board: &Board) -> Option<Check> {
+    let colour = *board.active_colour();
+    if !in_check(board, colour) {
+        None
+    } else if candidates(board).is_empty() {
+        Some(Check::Checkmate)
+    } else {
+        Some(Check::Check)
+    }
+}
+
+fn is_match(candidate: &Candidate, movement: &PlyMovement) -> bool {
+    let colour = candidate.piece.colour();
+    match (&candidate.kind, movement) {
+        (
+            Kind::Castle(AvailableCastle::WhiteKingside | AvailableCastle::BlackKingside),
+            PlyMovement::KingsideCastle { colour: c, .. },
+        )
+        | (
+            Kind::Castle(AvailableCastle::WhiteQueenside | AvailableCastle::BlackQueenside),
+            PlyMovement::QueensideCastle { colour: c, .. },
+        ) => colour == c,
+        (
+            Kind::Piece {
+                promotes_to: None, ..
+            },
+            PlyMovement::Move {
+                movement,
+                qualifier,
+                ..
+            },
+        ) => is_movement(candidate, movement, qualifier.as_ref()),
+        (
+            Kind::Piece {
+                promotes_to: Some(p),
+                ..
+            },
+            PlyMovement::Promotion {
+                movement,
+                promotes_to,
+                qualifier,
+                ..
+            },
+        ) => p == promotes_to && is_movement(candidate, movement, qualifier.as_ref()),
+        _ => false,
+    }
+}
+
+fn is_movement(
+    candidate: &Candidate,
+    movement: &Movement,
+    qualifier: Option<&MoveQualifier>,
+) -> bool {
+    Movement::new(candidate.piece, candidate.to) == *movement
+        && qualifies(candidate.from, qualifier)
+}
+
+fn qualifies(from: Position, qualifier: Option<&MoveQualifier>) -> bool {
+    match qualifier {
+        None => true,
+        Some(MoveQualifier::Col(col)) => from.col() == *col,
+        Some(MoveQualifier::Row(row)) => from.row() == *row,
+        Some(MoveQualifier::Position(position)) => from == *position,
+    }
+}
+
+fn forwards(colour: PieceColour) -> i8 {
+    match colour {
+        PieceColour::White => 1,
+        PieceColour::Black => -1,
+    }
+}
+
+fn opposition(colour: PieceColour) -> PieceColour {
+    match colour {
+        PieceColour::White => PieceColour::Black,
+        PieceColour::Black => PieceColour::White,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::parse;
+
+    use super::*;
+
+    mod moves_tests {
+        use super::*;
+
+        #[test]
+        fn finds_twenty_moves_from_start() {
+            assert_eq!(
+                moves(&board(
+                    "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1"
+                ))
+                .len(),
+                20
+            )
+        }
+
+        #[test]
+        fn excludes_moves_leaving_king_in_check() {
+            // The knight is pinned against the king by the bishop
+            let moves = moves(&board("4k3/8/8/b7/8/8/3N4/4K3 w - - 0 1"));
+            assert!(moves.iter().all(|m| m.from() != Position::new(1, 3)))
+        }
+
+        #[test]
+        fn finds_castles_only_if_path_is_safe() {
+            let moves = moves(&board("4k3/8/8/8/8/8/8/R3K2R w KQ - 0 1"));
+            assert_eq!(castles(&moves), 2);
+
+            // The bishop covers f1, which the king would pass through
+            let moves = super::moves(&board("4k3/8/8/8/8/8/6b1/R3K2R w KQ - 0 1"));
+            assert_eq!(castles(&moves), 1);
+        }
+
+        #[test]
+        fn finds_promotions() {
+            let moves = moves(&board("4k3/P7/8/8/8/8/8/4K3 w - - 0 1"));
+            let promotions = moves
+                .iter()
+                .filter(|m| matches!(m.movement(), PlyMovement::Promotion { .. }))
+                .count();
+            assert_eq!(promotions, 4)
+        }
+
+        #[test]
+        fn finds_en_passant() {
+            let moves = moves(&board("4k3/8/8/3Pp3/8/8/8/4K3 w - e6 0 1"));
+            let en_passant = moves
+                .iter()
+                .find(|m| m.to() == Position::new(5, 4))
+                .unwrap();
+            assert_eq!(en_passant.board().occupant(Position::new(4, 4)), None)
+        }
+
+        #[test]
+        fn qualifies_ambiguous_moves() {
+            let moves = moves(&board("4k3/8/8/8/8/8/4K3/R6R w - - 0 1"));
+            let rook_move = moves
+                .iter()
+                .find(|m| m.from() == Position::new(0, 0) && m.to() == Position::new(0, 3))
+                .unwrap();
+            assert!(matches!(
+                rook_move.movement(),
+                PlyMovement::Move {
+                    qualifier: Some(MoveQualifier::Col(0)),
+                    ..
+                }
+            ))
+        }
+
+        #[test]
+        fn marks_checkmate() {
+            let moves = moves(&board("6k1/5ppp/8/8/8/8/8/R3K3 w - - 0 1"));
+            let mate = moves
+                .iter()
+                .find(|m| m.to() == Position::new(7, 0))
+                .unwrap();
+            assert!(matches!(
+                mate.movement(),
+                PlyMovement::Move {
+                    check: Some(Check::Checkmate),
+                    ..
+                }
+            ))
+        }
+
+        #[test]
+        fn counts_positions_correctly() {
+            let board =
+                board("r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1");
+            assert_eq!(perft(&board, 2), 2039)
+        }
+
+        // Counts the positions reached after the given number of ply, which is well known for
+        // some positions
+        fn perft(board: &Board, depth: u32) -> usize {
+            match depth {
+                0 => 1,
+                _ => candidates(board)
+                    .iter()
+                    .map(|candidate| perft(&candidate.board, depth - 1))
+                    .sum(),
+            }
+        }
+
+        fn castles(moves: &[LegalMove]) -> usize {
+            moves
+                .iter()
+                .filter(|m| {
+                    matches!(
+                        m.movement(),
+                        PlyMovement::KingsideCastle { .. } | PlyMovement::QueensideCastle { .. }
+                    )
+                })
+                .count()
+        }
+    }
+
+    mod resolve_tests {
+        use super::*;
+
+        #[test]
+        fn returns_err_if_illegal() {
+            let board = board("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1");
+            assert!(resolve(&board, &san("e5", PieceColour::White)).is_err())
+        }
+
+        #[test]
+        fn returns_err_if_ambiguous() {
+            let board = board("4k3/8/8/8/8/8/4K3/R6R w - - 0 1");
+            assert!(resolve(&board, &san("Rd1", PieceColour::White)).is_err());
+            assert!(resolve(&board, &san("Rad1", PieceColour::White)).is_ok());
+        }
+
+        #[test]
+        fn adds_missing_check_marker() {
+            let board = board("4k3/8/8/8/8/8/8/R3K3 w - - 0 1");
+            let legal_move = resolve(&board, &san("Ra8", PieceColour::White)).unwrap();
+            assert!(matches!(
+                legal_move.movement(),
+                PlyMovement::Move {
+                    check: Some(Check::Check),
+                    ..
+                }
+            ))
+        }
+
+        fn san(input: &str, colour: PieceColour) -> PlyMovement {
+            parse::san(input, colour).unwrap()
+        }
+    }
+
+    fn board(fen: &str) -> Board {
+        parse::fen(fen).unwrap()
+    }
+}
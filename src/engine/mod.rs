@@ -3,6 +3,7 @@ mod castle;
 mod clocks;
 mod en_passant;
 mod error;
+mod legal;
 mod legality;
 mod moves;
 
@@ -19,6 +20,8 @@ use self::{
     error::EngineError,
 };
 
+pub use legal::LegalMove;
+
 pub fn execute_moves(board: &Board, ply: &[Ply]) -> Result<Vec<Board>, EngineError> {
     let mut boards: Vec<Board> = vec![board.clone()];
 
@@ -33,6 +36,27 @@ pub fn execute_moves(board: &Board, ply: &[Ply]) -> Result<Vec<Board>, EngineErr
     Ok(boards)
 }
 
+// Every move the side to move can play, with SAN qualifiers and check markers filled in
+pub fn legal_moves(board: &Board) -> Vec<LegalMove> {
+    legal::moves(board)
+}
+
+pub fn in_check(board: &Board) -> bool {
+    legal::in_check(board, *board.active_colour())
+}
+
+// Finds the legal move a possibly loosely written movement refers to, e.g. one missing its check
+// marker
+pub fn resolve_move(board: &Board, movement: &PlyMovement) -> Result<LegalMove, EngineError> {
+    legal::resolve(board, movement)
+}
+
+// Every legal move the movement could refer to, so none if it's illegal and several if it's
+// ambiguous
+pub fn matching_moves(board: &Board, movement: &PlyMovement) -> Vec<LegalMove> {
+    legal::matching(board, movement)
+}
+
 fn execute_move(board: &Board, ply: &PlyMovement) -> Result<Board, EngineError> {
     match ply {
         PlyMovement::KingsideCastle { colour, check: _ } => castle::kingside(board, *colour),
@@ -123,25 +147,44 @@ fn piece_move(
         }
     }?;
 
+    Ok(apply_move(
+        board,
+        piece,
+        candidate,
+        position,
+        promotes_to.copied(),
+        capture,
+    ))
+}
+
+fn apply_move(
+    board: &Board,
+    piece: Piece,
+    from: Position,
+    to: Position,
+    promotes_to: Option<PieceType>,
+    capture: bool,
+) -> Board {
     let mut next_board = board.clone();
-    next_board.remove(candidate);
+    next_board.remove(from);
 
     if let Some(&en_passant_square) = board.en_passant_square() {
-        en_passant::current(piece, position, en_passant_square, &mut next_board);
+        en_passant::current(piece, to, en_passant_square, &mut next_board);
     }
 
     match promotes_to {
-        None => next_board.add(piece, position),
-        Some(&other) => next_board.add(Piece::new(*piece.colour(), other), position),
+        None => next_board.add(piece, to),
+        Some(other) => next_board.add(Piece::new(*piece.colour(), other), to),
     };
 
     active_colour::update(&mut next_board);
-    update_available_castles(piece, candidate, &mut next_board);
-    en_passant::next(piece, candidate, position, &mut next_board);
+    update_available_castles(piece, from, &mut next_board);
+    remove_captured_castle(to, &mut next_board);
+    en_passant::next(piece, from, to, &mut next_board);
     clocks::halfmove(&mut next_board, *piece.piece_type(), capture);
     clocks::fullmove(&mut next_board, *piece.colour());
 
-    Ok(next_board)
+    next_board
 }
 
 // TODO: write tests
@@ -186,6 +229,20 @@ fn qualified_position(
     }
 }
 
+// A rook captured on its starting square can no longer castle
+fn remove_captured_castle(position: Position, board: &mut Board) -> &mut Board {
+    if position == *WHITE_KINGS_ROOK_POSITION {
+        board.remove_available_castle(AvailableCastle::WhiteKingside);
+    } else if position == *WHITE_QUEENS_ROOK_POSITION {
+        board.remove_available_castle(AvailableCastle::WhiteQueenside);
+    } else if position == *BLACK_KINGS_ROOK_POSITION {
+        board.remove_available_castle(AvailableCastle::BlackKingside);
+    } else if position == *BLACK_QUEENS_ROOK_POSITION {
+        board.remove_available_castle(AvailableCastle::BlackQueenside);
+    }
+    board
+}
+
 fn update_available_castles(piece: Piece, position: Position, board: &mut Board) -> &mut Board {
     match (piece.piece_type(), piece.colour()) {
         (PieceType::King, PieceColour::White) => {
@@ -0,0 +1,231 @@
+use crate::{
+    engine::{self, LegalMove},
+    model::{Board, Fen, GameResult, Pgn, Ply, Tags, DEFAULT_FEN, SEVEN_TAG_ROSTER},
+    parse, uci,
+};
+
+use super::{
+    token::{tokens, Token},
+    FormatError,
+};
+
+// Reads the game however its moves are written, replaying them so they can be written again as
+// they should be
+pub fn read(text: &str) -> Result<Pgn, FormatError> {
+    let lines: Vec<&str> = text.lines().collect();
+    let movetext = lines
+        .iter()
+        .position(|line| !line.trim_start().starts_with('['))
+        .unwrap_or(lines.len());
+
+    let mut tags = lines[..movetext]
+        .iter()
+        .map(|line| parse::tag(line).map_err(|e| FormatError::new(e.message())))
+        .collect::<Result<Tags, FormatError>>()?;
+    // Export format has every game carry the Seven Tag Roster, with unknown values marked. The
+    // result is taken from the movetext instead
+    for key in SEVEN_TAG_ROSTER.into_iter().filter(|&key| key != "Result") {
+        if tags.get(key).is_none() {
+            let unknown = match key {
+                "Date" => "????.??.??",
+                _ => "?",
+            };
+            tags.insert(key.to_string(), unknown.to_string());
+        }
+    }
+
+    let fen = tags.remove("FEN");
+    tags.remove("SetUp");
+    let board = parse::fen(fen.as_deref().unwrap_or(DEFAULT_FEN))
+        .map_err(|e| FormatError::new(e.message()))?;
+
+    let tokens = tokens(&lines[movetext..].join("\n"))?;
+    let played = tokens
+        .iter()
+        .filter_map(|token| match token {
+            Token::Result(result) => Some(*result),
+            _ => None,
+        })
+        .last();
+    // The tag is taken over the movetext when they disagree
+    let result = tags
+        .remove("Result")
+        .and_then(|result| parse::result(&result).ok())
+        .or(played)
+        .unwrap_or(GameResult::Ongoing);
+
+    // With no moves to keep them with, comments are on the game
+    let comments: Vec<String> = tokens
+        .iter()
+        .filter_map(|token| match token {
+            Token::Comment(comment) => Some(comment.clone()),
+            _ => None,
+        })
+        .collect();
+    let ply = line(&mut tokens.into_iter(), &board, false)?;
+    let fen = Fen::new(
+        board.clone(),
+        *board.active_colour(),
+        board.fullmove_clock(),
+    );
+    let no_moves = ply.is_empty();
+    let mut pgn = Pgn::new(tags, fen, result, ply);
+    if no_moves && !comments.is_empty() {
+        pgn.set_comment(Some(comments.join(" ")));
+    }
+    Ok(pgn)
+}
+
+// The moves up to the end of the variation, or of the game if it isn't one. Comments before the
+// first move are on the position before it, and any other comment is on the move before
+fn line(
+    tokens: &mut impl Iterator<Item = Token>,
+    board: &Board,
+    variation: bool,
+) -> Result<Vec<Ply>, FormatError> {
+    let mut ply: Vec<Ply> = Vec::new();
+    let mut board = board.clone();
+    // Variations are played from the board before the last move
+    let mut before = board.clone();
+    let mut leading = Vec::new();
+
+    while let Some(token) = tokens.next() {
+        match token {
+            Token::Move(text) => {
+                let legal_move = legal_move(&board, &text)?;
+                let move_number = i16::try_from(board.fullmove_clock()).unwrap_or(i16::MAX);
+                let next = legal_move.board().clone();
+                let mut next_ply = Ply::new(move_number, legal_move.into_movement(), None);
+                if ply.is_empty() && !leading.is_empty() {
+                    next_ply.set_comment_before(Some(leading.join(" ")));
+                }
+                ply.push(next_ply);
+                before = std::mem::replace(&mut board, next);
+            }
+            Token::Comment(comment) => match ply.last_mut() {
+                Some(last) => {
+                    let comment = match last.comment() {
+                        Some(existing) => format!("{existing} {comment}"),
+                        None => comment,
+                    };
+                    last.set_comment(Some(comment));
+                }
+                None => leading.push(comment),
+            },
+            Token::Nag(nag) => {
+                if let Some(last) = ply.last_mut() {
+                    last.add_nag(nag);
+                }
+            }
+            Token::Open => {
+                let variation = line(tokens, &before, true)?;
+                let last = ply
+                    .last_mut()
+                    .ok_or_else(|| FormatError::new("A variation comes before any moves"))?;
+                if !variation.is_empty() {
+                    last.add_variation(variation);
+                }
+            }
+            Token::Close if variation => return Ok(ply),
+            Token::Close => {
+                return Err(FormatError::new(
+                    "A variation is closed without being opened",
+                ))
+            }
+            Token::Result(_) => {}
+        }
+    }
+    // A variation left open is closed by the end of the game
+    Ok(ply)
+}
+
+// UCI's long algebraic notation, or SAN however it's qualified and marked
+fn legal_move(board: &Board, text: &str) -> Result<LegalMove, FormatError> {
+    if let Some(legal_move) = uci::legal_move(board, text) {
+        return Ok(legal_move);
+    }
+    let number = board.fullmove_clock();
+    let movement = parse::san(text, *board.active_colour())
+        .map_err(|e| FormatError::new(format!("Move {number}: {}", e.message())))?;
+    engine::resolve_move(board, &movement)
+        .map_err(|e| FormatError::new(format!("Move {number}, {text}: {e}")))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::write;
+
+    use super::*;
+
+    fn movetext(text: &str) -> String {
+        let pgn = read(text).unwrap();
+        let written = write::pgn(&pgn).unwrap();
+        written
+            .split_once("\n\n")
+            .map(|(_, movetext)| movetext.trim().to_string())
+            .unwrap()
+    }
+
+    #[test]
+    fn writes_canonical_san() {
+        assert_eq!(
+            movetext("1. e4 e5 2. Ngf3 f6 3. Nxe5 fxe5 4. Qh5 Ke7 5. Qxe5 *"),
+            "1. e4 e5 2. Nf3 f6 3. Nxe5 fxe5 4. Qh5+ Ke7 5. Qxe5+ *"
+        );
+    }
+
+    #[test]
+    fn reads_uci_and_missing_numbers() {
+        assert_eq!(
+            movetext("e2e4 e7e5 g1f3 b8c6 f1b5"),
+            "1. e4 e5 2. Nf3 Nc6 3. Bb5 *"
+        );
+        assert_eq!(
+            movetext("[FEN \"4k3/P7/8/8/8/8/8/4K3 w - - 0 40\"]\n\na7a8q Kd7"),
+            "40. a8=Q+ Kd7 *"
+        );
+    }
+
+    #[test]
+    fn corrects_move_numbers() {
+        assert_eq!(
+            movetext("[Result \"1-0\"]\n\n7. e4 e5 (8... c5 9. Nf3) 2. Nf3 1-0"),
+            "1. e4 e5 (1... c5 2. Nf3) 2. Nf3 1-0"
+        );
+    }
+
+    #[test]
+    fn fills_in_tags() {
+        let pgn = read("[White \"One\"]\n[SetUp \"1\"]\n\n1. e4 0-1").unwrap();
+        assert_eq!(pgn.tags().get("White"), Some(&String::from("One")));
+        assert_eq!(pgn.tags().get("Date"), Some(&String::from("????.??.??")));
+        assert_eq!(pgn.tags().get("SetUp"), None);
+        assert_eq!(pgn.result(), GameResult::BlackWin);
+    }
+
+    #[test]
+    fn keeps_comments() {
+        assert_eq!(
+            movetext("{Before} 1. e4 {After} {Again} $1 (1. d4 {Also}) e5 *"),
+            "{Before} 1. e4 $1 {After Again} (1. d4 {Also}) 1... e5 *"
+        );
+    }
+
+    #[test]
+    fn keeps_comments_without_moves() {
+        assert_eq!(
+            movetext("{Forfeit} ; No show\n1-0"),
+            "{Forfeit No show} 1-0"
+        );
+    }
+
+    #[test]
+    fn returns_err_if_move_illegal() {
+        let error = read("1. e4 e5 2. Ke3 *").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Move 2, Ke3: Not a legal move in this position"
+        );
+        assert!(read("1. e4 e5 2. Zz9 *").is_err());
+    }
+}
@@ -0,0 +1,101 @@
+mod error;
+mod game;
+mod token;
+
+use crate::write;
+
+pub use error::FormatError;
+
+// Rewrites every game in the input in export format, replaying the moves to write them as SAN with
+// the right move numbers
+pub fn format(input: &str) -> Result<String, FormatError> {
+    let input = input.strip_prefix('\u{feff}').unwrap_or(input);
+    let games = games(input)
+        .iter()
+        .enumerate()
+        .map(|(index, text)| {
+            let pgn = game::read(text)
+                .map_err(|e| FormatError::new(format!("Game {}: {e}", index + 1)))?;
+            write::pgn(&pgn).map_err(|e| FormatError::new(e.to_string()))
+        })
+        .collect::<Result<Vec<String>, FormatError>>()?;
+    Ok(games.join("\n"))
+}
+
+// The text of each game, without blank lines. A game starts at the first tag after some movetext,
+// which needn't be separated from it by a blank line, so long as it isn't inside a comment
+fn games(input: &str) -> Vec<String> {
+    let mut games: Vec<Vec<&str>> = Vec::new();
+    let mut movetext = true;
+    let mut in_comment = false;
+
+    for line in input.lines() {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        let tag = line.starts_with('[') && !in_comment;
+        if tag && movetext {
+            games.push(Vec::new());
+        }
+        if !tag {
+            in_comment = comment_left_open(line, in_comment);
+        }
+        movetext = !tag;
+        match games.last_mut() {
+            Some(game) => game.push(line),
+            None => games.push(vec![line]),
+        }
+    }
+
+    games.iter().map(|lines| lines.join("\n")).collect()
+}
+
+fn comment_left_open(line: &str, mut in_comment: bool) -> bool {
+    for c in line.chars() {
+        match c {
+            '{' if !in_comment => in_comment = true,
+            '}' if in_comment => in_comment = false,
+            ';' if !in_comment => return false,
+            _ => {}
+        }
+    }
+    in_comment
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn formats_games() {
+        let input = "[Black \"Two\"]\r\n[White \"One\"]\r\n[Result \"1-0\"]\r\n\
+            1.e4 e5 2.Qh5 Nc6 3.Bc4 Nf6 4.Qxf7 1-0\r\n\
+            [Result \"*\"]\r\n1. d4 {A comment\r\n[%clk 0:10:00]} *\r\n";
+        assert_eq!(
+            format(input).unwrap(),
+            "[Event \"?\"]\n[Site \"?\"]\n[Date \"????.??.??\"]\n[Round \"?\"]\n\
+            [White \"One\"]\n[Black \"Two\"]\n[Result \"1-0\"]\n\n\
+            1. e4 e5 2. Qh5 Nc6 3. Bc4 Nf6 4. Qxf7# 1-0\n\n\
+            [Event \"?\"]\n[Site \"?\"]\n[Date \"????.??.??\"]\n[Round \"?\"]\n\
+            [White \"?\"]\n[Black \"?\"]\n[Result \"*\"]\n\n\
+            1. d4 {A comment [%clk 0:10:00]} *\n"
+        );
+    }
+
+    #[test]
+    fn is_unchanged_by_formatting_again() {
+        let input = std::fs::read_to_string("./samples/example.pgn").unwrap();
+        let formatted = format(&input).unwrap();
+        assert_eq!(format(&formatted).unwrap(), formatted);
+    }
+
+    #[test]
+    fn names_game_which_cannot_be_formatted() {
+        let error = format("[Result \"*\"]\n\n1. e4 *\n\n[Result \"*\"]\n\n1. e5 *\n").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Game 2: Move 1, e5: Not a legal move in this position"
+        );
+    }
+}
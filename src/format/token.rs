@@ -0,0 +1,156 @@
+use crate::{model::GameResult, parse};
+
+use super::FormatError;
+
+// Move numbers aren't kept, as they're worked out again from the position
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum Token {
+    Move(String),
+    Comment(String),
+    Nag(u8),
+    Open,
+    Close,
+    Result(GameResult),
+}
+
+// Move suffixes, in the order of the NAGs they stand for from $1
+const SUFFIXES: [&str; 6] = ["!", "?", "!!", "??", "!?", "?!"];
+
+pub fn tokens(movetext: &str) -> Result<Vec<Token>, FormatError> {
+    // Lines starting with % are escaped from the rest of the movetext
+    let movetext: Vec<&str> = movetext
+        .lines()
+        .filter(|line| !line.starts_with('%'))
+        .collect();
+    let movetext = movetext.join("\n");
+
+    let mut tokens = Vec::new();
+    let mut rest = movetext.as_str();
+    while let Some(c) = rest.chars().next() {
+        let end = match c {
+            '{' => {
+                let end = rest
+                    .find('}')
+                    .ok_or_else(|| FormatError::new("A comment is never closed"))?;
+                tokens.extend(comment(&rest[1..end]));
+                end + 1
+            }
+            ';' => {
+                let end = rest.find('\n').unwrap_or(rest.len());
+                tokens.extend(comment(&rest[1..end]));
+                end
+            }
+            '(' => {
+                tokens.push(Token::Open);
+                1
+            }
+            ')' => {
+                tokens.push(Token::Close);
+                1
+            }
+            c if c.is_whitespace() => c.len_utf8(),
+            _ => {
+                let end = rest
+                    .find(|c: char| c.is_whitespace() || "{;()".contains(c))
+                    .unwrap_or(rest.len());
+                tokens.extend(word(&rest[..end])?);
+                end
+            }
+        };
+        rest = &rest[end..];
+    }
+    Ok(tokens)
+}
+
+fn comment(text: &str) -> Option<Token> {
+    let words: Vec<&str> = text.split_whitespace().collect();
+    (!words.is_empty()).then(|| Token::Comment(words.join(" ")))
+}
+
+// A move along with anything run together with it, such as its number or a suffix like "!?"
+fn word(word: &str) -> Result<Vec<Token>, FormatError> {
+    if let Ok(result) = parse::result(word) {
+        return Ok(vec![Token::Result(result)]);
+    }
+    if let Some(nag) = word.strip_prefix('$') {
+        let nag = nag
+            .parse()
+            .map_err(|_| FormatError::new(format!("'{word}' is not a valid NAG")))?;
+        return Ok(vec![Token::Nag(nag)]);
+    }
+
+    // Castling written with zeros starts with a digit too, but is never followed by a full stop
+    let after_number = word.trim_start_matches(|c: char| c.is_ascii_digit());
+    let word = if after_number.len() < word.len()
+        && (after_number.is_empty() || after_number.starts_with('.'))
+    {
+        after_number.trim_start_matches('.')
+    } else {
+        word
+    };
+    let word = word.strip_suffix("e.p.").unwrap_or(word);
+    if word.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let movement = word.trim_end_matches(['!', '?']);
+    let suffix = &word[movement.len()..];
+    let mut tokens = vec![Token::Move(movement.to_string())];
+    if !suffix.is_empty() {
+        let nag = (1..)
+            .zip(SUFFIXES)
+            .find_map(|(nag, known)| (known == suffix).then_some(nag))
+            .ok_or_else(|| FormatError::new(format!("'{suffix}' is not a valid move suffix")))?;
+        tokens.push(Token::Nag(nag));
+    }
+    Ok(tokens)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn moves(movements: &[&str]) -> Vec<Token> {
+        movements
+            .iter()
+            .map(|movement| Token::Move(movement.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn drops_move_numbers() {
+        let expected = moves(&["e4", "e5", "Nf3", "O-O", "0-0-0"]);
+        assert_eq!(tokens("1. e4 e5 2.Nf3 3...O-O 4 0-0-0").unwrap(), expected);
+        assert_eq!(tokens("e4 e5 Nf3 O-O 0-0-0").unwrap(), expected);
+    }
+
+    #[test]
+    fn reads_annotations() {
+        assert_eq!(
+            tokens("1. e4!? {A\n  comment} $14 (1. d4) ; To the end\n1... c5 1/2-1/2").unwrap(),
+            vec![
+                Token::Move(String::from("e4")),
+                Token::Nag(5),
+                Token::Comment(String::from("A comment")),
+                Token::Nag(14),
+                Token::Open,
+                Token::Move(String::from("d4")),
+                Token::Close,
+                Token::Comment(String::from("To the end")),
+                Token::Move(String::from("c5")),
+                Token::Result(GameResult::Draw),
+            ]
+        );
+    }
+
+    #[test]
+    fn skips_escaped_lines() {
+        assert_eq!(tokens("% Exported\n1. e4 {}").unwrap(), moves(&["e4"]));
+    }
+
+    #[test]
+    fn returns_err_if_comment_not_closed() {
+        assert!(tokens("1. e4 {Never closed").is_err());
+        assert!(tokens("1. e4!!? e5").is_err());
+    }
+}
@@ -0,0 +1,69 @@
+use std::{
+    fs::File,
+    io::{self, Read},
+    path::Path,
+};
+
+use bzip2::read::MultiBzDecoder;
+use flate2::read::MultiGzDecoder;
+use ruzstd::StreamingDecoder;
+use zip::ZipArchive;
+
+use super::InputError;
+
+// The bytes of the file, decompressed according to its extension
+pub fn read(path: &Path) -> Result<Vec<u8>, InputError> {
+    let error = |e: io::Error| InputError::new(format!("Failed to read {}: {e}", path.display()));
+    let mut file = File::open(path).map_err(error)?;
+    let mut bytes = Vec::new();
+    match extension(path).as_str() {
+        "gz" => MultiGzDecoder::new(file).read_to_end(&mut bytes),
+        "bz2" => MultiBzDecoder::new(file).read_to_end(&mut bytes),
+        "zst" => return zstd(file).map_err(error),
+        _ => file.read_to_end(&mut bytes),
+    }
+    .map_err(error)?;
+    Ok(bytes)
+}
+
+// Each file in the archive with a name ending .pgn, with its name
+pub fn archive(path: &Path) -> Result<Vec<(String, Vec<u8>)>, InputError> {
+    let error = |e: &dyn std::fmt::Display| {
+        InputError::new(format!("Failed to read {}: {e}", path.display()))
+    };
+    let file = File::open(path).map_err(|e| error(&e))?;
+    let mut archive = ZipArchive::new(file).map_err(|e| error(&e))?;
+
+    let mut files = Vec::new();
+    for index in 0..archive.len() {
+        let mut file = archive.by_index(index).map_err(|e| error(&e))?;
+        if !file.is_file() || !file.name().to_lowercase().ends_with(".pgn") {
+            continue;
+        }
+        let mut bytes = Vec::new();
+        file.read_to_end(&mut bytes).map_err(|e| error(&e))?;
+        files.push((file.name().to_string(), bytes));
+    }
+    Ok(files)
+}
+
+pub fn extension(path: &Path) -> String {
+    path.extension()
+        .map(|extension| extension.to_string_lossy().to_lowercase())
+        .unwrap_or_default()
+}
+
+// A file may hold several frames one after another, each decoded in turn
+fn zstd(mut file: File) -> io::Result<Vec<u8>> {
+    let mut compressed = Vec::new();
+    file.read_to_end(&mut compressed)?;
+
+    let mut rest = compressed.as_slice();
+    let mut bytes = Vec::new();
+    while !rest.is_empty() {
+        StreamingDecoder::new(&mut rest)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e.to_string()))?
+            .read_to_end(&mut bytes)?;
+    }
+    Ok(bytes)
+}
@@ -0,0 +1,236 @@
+mod decompress;
+mod error;
+
+use std::{
+    io::{self, Read},
+    path::{Path, PathBuf},
+};
+
+use chardetng::EncodingDetector;
+use encoding_rs::{Encoding, UTF_8};
+use walkdir::WalkDir;
+
+pub use error::InputError;
+
+// Extensions of the files read when searching a directory
+const EXTENSIONS: [&str; 5] = [".pgn", ".pgn.gz", ".pgn.bz2", ".pgn.zst", ".zip"];
+
+// Text read from a file, an archive or standard input, with a name to report it by
+#[derive(Debug, PartialEq, Eq)]
+pub struct Source {
+    name: String,
+    text: String,
+    path: Option<PathBuf>,
+}
+
+impl Source {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    // Only for a file read as it is, so one which could be written back to
+    pub fn path(&self) -> Option<&Path> {
+        self.path.as_deref()
+    }
+}
+
+// Everything named by an argument, which is - for standard input, a file, a directory to search for
+// games, or a glob pattern matching any of those. Text is decoded with the encoding named, such as
+// windows-1252, or else the one it looks to be in
+pub fn read(argument: &str, encoding: Option<&str>) -> Result<Vec<Source>, InputError> {
+    let encoding = encoding
+        .map(|label| {
+            Encoding::for_label(label.as_bytes())
+                .ok_or_else(|| InputError::new(format!("'{label}' is not a known encoding")))
+        })
+        .transpose()?;
+
+    if argument == "-" {
+        let mut bytes = Vec::new();
+        io::stdin()
+            .read_to_end(&mut bytes)
+            .map_err(|e| InputError::new(format!("Failed to read standard input: {e}")))?;
+        return Ok(vec![source("standard input", &bytes, None, encoding)]);
+    }
+
+    let path = Path::new(argument);
+    if path.exists() || !argument.contains(['*', '?', '[']) {
+        return from_path(path, encoding);
+    }
+
+    let paths = glob::glob(argument)
+        .map_err(|e| InputError::new(format!("'{argument}' is not a valid pattern: {e}")))?
+        .collect::<Result<Vec<PathBuf>, _>>()
+        .map_err(|e| InputError::new(e.to_string()))?;
+    if paths.is_empty() {
+        return Err(InputError::new(format!("No files match '{argument}'")));
+    }
+    let mut sources = Vec::new();
+    for path in paths {
+        sources.extend(from_path(&path, encoding)?);
+    }
+    Ok(sources)
+}
+
+fn from_path(path: &Path, encoding: Option<&'static Encoding>) -> Result<Vec<Source>, InputError> {
+    if !path.is_dir() {
+        return from_file(path, encoding);
+    }
+
+    let mut sources = Vec::new();
+    for entry in WalkDir::new(path).sort_by_file_name() {
+        let entry = entry.map_err(|e| InputError::new(e.to_string()))?;
+        let name = entry.file_name().to_string_lossy().to_lowercase();
+        if entry.file_type().is_file() && EXTENSIONS.iter().any(|ext| name.ends_with(ext)) {
+            sources.extend(from_file(entry.path(), encoding)?);
+        }
+    }
+    Ok(sources)
+}
+
+fn from_file(path: &Path, encoding: Option<&'static Encoding>) -> Result<Vec<Source>, InputError> {
+    let name = path.display().to_string();
+    Ok(match decompress::extension(path).as_str() {
+        "zip" => decompress::archive(path)?
+            .into_iter()
+            .map(|(file_name, bytes)| {
+                source(&format!("{name}:{file_name}"), &bytes, None, encoding)
+            })
+            .collect(),
+        "gz" | "bz2" | "zst" => vec![source(&name, &decompress::read(path)?, None, encoding)],
+        _ => vec![source(
+            &name,
+            &decompress::read(path)?,
+            Some(path.to_path_buf()),
+            encoding,
+        )],
+    })
+}
+
+fn source(
+    name: &str,
+    bytes: &[u8],
+    path: Option<PathBuf>,
+    encoding: Option<&'static Encoding>,
+) -> Source {
+    Source {
+        name: name.to_string(),
+        text: decode(bytes, encoding),
+        path,
+    }
+}
+
+// Without an encoding given, text which isn't UTF-8 is most often Latin-1 or Windows-1252 from older
+// databases, but is decoded as whatever it most looks like. A byte order mark is always removed, and
+// says which UTF it is in whatever the encoding given
+fn decode(bytes: &[u8], encoding: Option<&'static Encoding>) -> String {
+    let encoding = encoding.unwrap_or_else(|| {
+        if std::str::from_utf8(bytes).is_ok() {
+            return UTF_8;
+        }
+        let mut detector = EncodingDetector::new();
+        detector.feed(bytes, true);
+        detector.guess(None, false)
+    });
+    encoding.decode(bytes).0.into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DIRECTORY: &str = "./resources/test/input";
+
+    fn names(sources: &[Source]) -> Vec<&str> {
+        sources.iter().map(Source::name).collect()
+    }
+
+    #[test]
+    fn reads_compressed_files() {
+        let plain = "[Event \"Compressed\"]\n[Result \"1-0\"]\n\n1. e4 e5 2. Qh5 Nc6 3. Bc4 Nf6 4. Qxf7# 1-0\n\n\
+            [Event \"Compressed\"]\n[Result \"*\"]\n\n1. d4 *\n";
+        for extension in ["gz", "bz2", "zst"] {
+            let sources = read(&format!("{DIRECTORY}/games.pgn.{extension}"), None).unwrap();
+            assert_eq!(sources[0].text(), plain);
+            assert_eq!(sources[0].path(), None);
+        }
+    }
+
+    #[test]
+    fn reads_games_in_archive() {
+        let sources = read(&format!("{DIRECTORY}/games.zip"), None).unwrap();
+        assert_eq!(
+            names(&sources),
+            vec!["./resources/test/input/games.zip:one.pgn"]
+        );
+        assert!(sources[0].text().starts_with("[Event \"Compressed\"]"));
+    }
+
+    #[test]
+    fn searches_directories() {
+        let sources = read(DIRECTORY, None).unwrap();
+        assert_eq!(
+            names(&sources),
+            vec![
+                "./resources/test/input/games.pgn.bz2",
+                "./resources/test/input/games.pgn.gz",
+                "./resources/test/input/games.pgn.zst",
+                "./resources/test/input/games.zip:one.pgn",
+                "./resources/test/input/nested/deeper/game.pgn",
+            ]
+        );
+        assert_eq!(
+            sources[4].path(),
+            Some(Path::new("./resources/test/input/nested/deeper/game.pgn"))
+        );
+    }
+
+    #[test]
+    fn expands_globs() {
+        let sources = read(&format!("{DIRECTORY}/games.pgn.*z*"), None).unwrap();
+        assert_eq!(
+            names(&sources),
+            vec![
+                "resources/test/input/games.pgn.bz2",
+                "resources/test/input/games.pgn.gz",
+                "resources/test/input/games.pgn.zst",
+            ]
+        );
+        assert!(read(&format!("{DIRECTORY}/*.none"), None).is_err());
+    }
+
+    #[test]
+    fn detects_latin_1() {
+        let sources = read("./resources/test/encoding/latin1.pgn", None).unwrap();
+        assert!(sources[0].text().contains("[White \"Müller, Jürgen\"]"));
+        assert!(sources[0].text().contains("{Très bien}"));
+    }
+
+    #[test]
+    fn decodes_encoding_given() {
+        let latin1 = read("./resources/test/encoding/latin1.pgn", Some("latin1")).unwrap();
+        assert!(latin1[0].text().contains("Ståhlberg"));
+        let cyrillic = read("./resources/test/encoding/latin1.pgn", Some("koi8-r")).unwrap();
+        assert!(!cyrillic[0].text().contains("Ståhlberg"));
+        assert!(read("./resources/test/encoding/latin1.pgn", Some("klingon")).is_err());
+    }
+
+    #[test]
+    fn strips_byte_order_marks() {
+        let utf8 = read("./resources/test/encoding/bom.pgn", None).unwrap();
+        let utf16 = read("./resources/test/encoding/utf16.pgn", None).unwrap();
+        for sources in [utf8, utf16] {
+            assert!(sources[0].text().starts_with("[Event \"Bundesliga\"]"));
+            assert!(sources[0].text().contains("Müller"));
+        }
+    }
+
+    #[test]
+    fn returns_err_if_file_missing() {
+        assert!(read(&format!("{DIRECTORY}/missing.pgn"), None).is_err());
+    }
+}
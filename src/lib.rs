@@ -1,12 +1,42 @@
+mod annotate;
+mod check;
 mod cli;
+mod collection;
+mod config;
 mod engine;
+mod format;
+mod input;
 mod model;
 mod parse;
+mod search;
+mod stats;
+#[cfg(test)]
+mod test_support;
+mod uci;
 mod ui;
+mod write;
 
+pub use annotate::Annotator;
+pub use check::Report as CheckReport;
 pub use cli::pigin;
+pub use collection::{
+    duplicates as duplicate_games, merge as merge_games, split as split_games,
+    Filter as GameFilter, SplitBy,
+};
+pub use config::load as load_config;
+pub use config::Config;
 pub use engine::execute_moves;
+pub use format::format as format_pgn;
+pub use input::{read as read_input, Source};
 pub use model::Game;
 pub use model::Pgn;
+pub use model::PieceColour;
+pub use parse::fen as parse_fen;
 pub use parse::parse;
-pub use ui::launch;
+pub use search::{Occurrence, PatternQuery, PositionIndex};
+pub use stats::players;
+pub use ui::{launch, render_position};
+pub use write::diagram as write_diagram;
+pub use write::gif as write_gif;
+pub use write::json as write_json;
+pub use write::pgn as write_pgn;
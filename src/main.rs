@@ -1,34 +1,448 @@
-use std::{error::Error, fs};
+use std::{error::Error, fs, path::Path, process, time::Duration};
 
-use pigin::{execute_moves, launch, parse, pigin, Game, Pgn};
+use clap::ArgMatches;
+use pigin::{
+    duplicate_games, execute_moves, format_pgn, launch, load_config, merge_games, parse, parse_fen,
+    pigin, players, read_input, render_position, split_games, write_diagram, write_gif, write_json,
+    write_pgn, Annotator, CheckReport, Config, Game, GameFilter, Occurrence, PatternQuery, Pgn,
+    PieceColour, PositionIndex, Source, SplitBy,
+};
 
 type PgnsResult = Result<Vec<Pgn>, Box<dyn Error>>;
 
 fn main() -> Result<(), Box<dyn Error>> {
     let matches = pigin().get_matches();
-    let file_names: Vec<&String> = matches
-        .get_many("file")
-        .ok_or("'file' argument not provided")?
+
+    match matches.subcommand() {
+        Some(("annotate", matches)) => annotate(matches),
+        Some(("position", matches)) => position(matches),
+        Some(("pattern", matches)) => pattern(matches),
+        Some(("stats", matches)) => stats(matches),
+        Some(("export", matches)) => export(matches),
+        Some(("show", matches)) => show(matches),
+        Some(("diagram", matches)) => diagram(matches),
+        Some(("gif", matches)) => gif(matches),
+        Some(("check", matches)) => check(matches),
+        Some(("fmt", matches)) => fmt(matches),
+        Some(("filter", matches)) => filter(matches),
+        Some(("split", matches)) => split(matches),
+        Some(("merge", matches)) => merge(matches),
+        Some(("duplicates", matches)) => duplicates(matches),
+        _ => view(&matches),
+    }
+}
+
+fn view(matches: &ArgMatches) -> Result<(), Box<dyn Error>> {
+    let mut config = config_from(matches)?;
+    if matches.get_flag("autoplay") {
+        config.autoplay_mut().start_on_launch();
+    }
+
+    launch(games_from(matches)?, config)?;
+    Ok(())
+}
+
+fn annotate(matches: &ArgMatches) -> Result<(), Box<dyn Error>> {
+    let mut config = config_from(matches)?;
+    if let Some(&depth) = matches.get_one::<u32>("depth") {
+        config.annotate_mut().use_depth(depth);
+    }
+    if let Some(&movetime) = matches.get_one::<u64>("movetime") {
+        config.annotate_mut().use_movetime_ms(movetime);
+    }
+
+    let mut games = games_from(matches)?;
+    let mut annotator = Annotator::new(&config)?;
+    let game_count = games.len();
+    let mut output = Vec::new();
+    for (index, game) in games.iter_mut().enumerate() {
+        eprintln!("Annotating game {} of {game_count}", index + 1);
+        annotator.annotate(game)?;
+        output.push(write_pgn(game.pgn())?);
+    }
+
+    let output = output.join("\n");
+    match matches.get_one::<String>("output") {
+        Some(file_name) => fs::write(file_name, output)?,
+        None => print!("{output}"),
+    }
+    Ok(())
+}
+
+fn position(matches: &ArgMatches) -> Result<(), Box<dyn Error>> {
+    let fen = matches
+        .get_one::<String>("fen")
+        .ok_or("'fen' argument not provided")?;
+    let board = parse_fen(fen)?;
+    let games = games_from(matches)?;
+
+    let index = PositionIndex::new(&games);
+    print_occurrences(
+        &games,
+        &index.find(&games, &board),
+        "No games reach this position",
+    );
+    Ok(())
+}
+
+fn pattern(matches: &ArgMatches) -> Result<(), Box<dyn Error>> {
+    let query = matches
+        .get_one::<String>("pattern")
+        .ok_or("'pattern' argument not provided")?;
+    let query = PatternQuery::parse(query)?;
+    let games = games_from(matches)?;
+
+    let occurrences = query.find(&games);
+    print_occurrences(&games, &occurrences, "No games match this pattern");
+    Ok(())
+}
+
+fn stats(matches: &ArgMatches) -> Result<(), Box<dyn Error>> {
+    let games = games_from(matches)?;
+    let filter = matches
+        .get_one::<String>("player")
+        .map(|player| player.to_lowercase());
+
+    let reports: Vec<String> = players(&games)
+        .iter()
+        .filter(|player| {
+            filter
+                .as_ref()
+                .map_or(true, |filter| player.name().to_lowercase().contains(filter))
+        })
+        .map(ToString::to_string)
         .collect();
+    if reports.is_empty() {
+        println!("No players found");
+    } else {
+        println!("{}", reports.join("\n\n"));
+    }
+    Ok(())
+}
 
-    let pgns = file_names
-        .into_iter()
-        .map(|file_name| pgns_from(file_name))
-        .reduce(reduce)
-        .unwrap_or_else(|| Ok(Vec::new()))?;
+fn export(matches: &ArgMatches) -> Result<(), Box<dyn Error>> {
+    let games = games_from(matches)?;
+    let pgns: Vec<&Pgn> = games.iter().map(Game::pgn).collect();
+
+    // JSON is the only format so far, which clap has already checked for
+    let output = write_json(&pgns)?;
+    match matches.get_one::<String>("output") {
+        Some(file_name) => fs::write(file_name, output)?,
+        None => println!("{output}"),
+    }
+    Ok(())
+}
+
+fn show(matches: &ArgMatches) -> Result<(), Box<dyn Error>> {
+    let games = games_from(matches)?;
+    let (number, game) = game_chosen(matches, &games)?;
+    let ply = ply_chosen(matches, game);
+    let perspective = perspective_from(matches);
+
+    println!(
+        "Game {number}: {}",
+        render_position(game, ply, perspective, matches.get_flag("unicode"))?
+    );
+    Ok(())
+}
+
+fn diagram(matches: &ArgMatches) -> Result<(), Box<dyn Error>> {
+    let games = games_from(matches)?;
+    let (_, game) = game_chosen(matches, &games)?;
+    let svg = write_diagram(game, ply_chosen(matches, game), perspective_from(matches))?;
+
+    match matches.get_one::<String>("output") {
+        Some(file_name) => fs::write(file_name, svg)?,
+        None => print!("{svg}"),
+    }
+    Ok(())
+}
+
+fn gif(matches: &ArgMatches) -> Result<(), Box<dyn Error>> {
+    let games = games_from(matches)?;
+    let (number, game) = game_chosen(matches, &games)?;
+    let delay = matches.get_one::<u64>("delay").copied().unwrap_or(1000);
+    let gif = write_gif(
+        game,
+        perspective_from(matches),
+        Duration::from_millis(delay),
+    )?;
+
+    let file_name = matches
+        .get_one::<String>("output")
+        .cloned()
+        .unwrap_or_else(|| format!("game{number}.gif"));
+    fs::write(&file_name, gif)?;
+    println!("Animation written to {file_name}");
+    Ok(())
+}
+
+// Exits with an error status if there are errors, for use in scripts
+fn check(matches: &ArgMatches) -> Result<(), Box<dyn Error>> {
+    let mut report = CheckReport::new();
+    for source in sources_from(matches)? {
+        report.add(source.name(), source.text());
+    }
+
+    match matches.get_one::<String>("format").map(String::as_str) {
+        Some("json") => println!("{}", report.json()?),
+        _ => println!("{report}"),
+    }
+    if report.errors() > 0 || (matches.get_flag("strict") && report.warnings() > 0) {
+        process::exit(1);
+    }
+    Ok(())
+}
+
+// Nothing is written until every file has been formatted, so no file is left half done by a game
+// which can't be
+fn fmt(matches: &ArgMatches) -> Result<(), Box<dyn Error>> {
+    let in_place = matches.get_flag("in-place");
+    let sources = sources_from(matches)?;
+    if let Some(source) = sources
+        .iter()
+        .find(|source| in_place && source.path().is_none())
+    {
+        return Err(format!("{} can't be formatted in place", source.name()).into());
+    }
+
+    let formatted = sources
+        .iter()
+        .map(|source| {
+            format_pgn(source.text())
+                .map_err(|e| format!("Failed to format {}: {e}", source.name()))
+        })
+        .collect::<Result<Vec<String>, String>>()?;
+    for (index, (source, formatted)) in sources.iter().zip(formatted).enumerate() {
+        match source.path() {
+            Some(path) if in_place => fs::write(path, formatted)?,
+            _ => {
+                if index > 0 {
+                    println!();
+                }
+                print!("{formatted}");
+            }
+        }
+    }
+    Ok(())
+}
+
+fn filter(matches: &ArgMatches) -> Result<(), Box<dyn Error>> {
+    let mut filter = GameFilter::new();
+    if let Some(player) = matches.get_one::<String>("player") {
+        filter.player(player);
+    }
+    filter.dates(
+        matches.get_one::<String>("from").map(String::as_str),
+        matches.get_one::<String>("to").map(String::as_str),
+    );
+    if let Some(result) = matches.get_one::<String>("result") {
+        filter.result(result)?;
+    }
+    filter.ratings(
+        matches.get_one::<u32>("min-rating").copied(),
+        matches.get_one::<u32>("max-rating").copied(),
+    );
+    if let Some(eco) = matches.get_one::<String>("eco") {
+        filter.eco(eco)?;
+    }
+    if let Some(query) = matches.get_one::<String>("query") {
+        filter.query(query)?;
+    }
+
+    let pgns = pgns_from_files(matches)?;
+    let kept: Vec<&Pgn> = pgns.iter().filter(|pgn| filter.matches(pgn)).collect();
+    write_games(&kept, matches.get_one::<String>("output"))?;
+    if let Some(file_name) = matches.get_one::<String>("output") {
+        println!("Kept {} of {} games in {file_name}", kept.len(), pgns.len());
+    }
+    Ok(())
+}
+
+fn split(matches: &ArgMatches) -> Result<(), Box<dyn Error>> {
+    let by = match matches.get_one::<String>("by").map(String::as_str) {
+        Some("round") => SplitBy::Round,
+        Some("player") => SplitBy::Player,
+        _ => SplitBy::Event,
+    };
+    let directory = Path::new(
+        matches
+            .get_one::<String>("directory")
+            .ok_or("'directory' argument not provided")?,
+    );
+    fs::create_dir_all(directory)?;
+
+    let pgns = pgns_from_files(matches)?;
+    let files = split_games(&pgns, by);
+    for (file_name, pgns) in &files {
+        write_games(pgns, Some(&directory.join(file_name)))?;
+    }
+    println!(
+        "Split {} games into {} files in {}",
+        pgns.len(),
+        files.len(),
+        directory.display()
+    );
+    Ok(())
+}
+
+fn merge(matches: &ArgMatches) -> Result<(), Box<dyn Error>> {
+    let collections = sources_from(matches)?
+        .iter()
+        .map(pgns_from)
+        .collect::<Result<Vec<Vec<Pgn>>, Box<dyn Error>>>()?;
+    let total: usize = collections.iter().map(Vec::len).sum();
+
+    let merged = merge_games(collections, matches.get_flag("dedupe"));
+    let pgns: Vec<&Pgn> = merged.iter().collect();
+    write_games(&pgns, matches.get_one::<String>("output"))?;
+    if let Some(file_name) = matches.get_one::<String>("output") {
+        println!(
+            "Merged {} games into {file_name}, dropping {} copies",
+            merged.len(),
+            total - merged.len()
+        );
+    }
+    Ok(())
+}
+
+// Each group is headed by the game it's a copy of, followed by where each copy is
+fn duplicates(matches: &ArgMatches) -> Result<(), Box<dyn Error>> {
+    let mut pgns = Vec::new();
+    let mut places = Vec::new();
+    for source in sources_from(matches)? {
+        let source_pgns = pgns_from(&source)?;
+        places.extend(
+            (1..=source_pgns.len()).map(|number| format!("{} game {number}", source.name())),
+        );
+        pgns.extend(source_pgns);
+    }
+
+    let groups = duplicate_games(&pgns);
+    for group in &groups {
+        let tags = pgns[group.kept()].tags();
+        println!(
+            "{} - {}, {}",
+            tags.get_or_default("White", "?"),
+            tags.get_or_default("Black", "?"),
+            tags.get_or_default("Date", "????.??.??")
+        );
+        for &index in group.games() {
+            let kept = if index == group.kept() {
+                " (richest)"
+            } else {
+                ""
+            };
+            println!("  {}{kept}", places[index]);
+        }
+    }
+    println!(
+        "Games with copies: {}, out of {} read",
+        groups.len(),
+        pgns.len()
+    );
+    Ok(())
+}
 
+// Games are written one after another, separated by a blank line
+fn write_games(pgns: &[&Pgn], file_name: Option<impl AsRef<Path>>) -> Result<(), Box<dyn Error>> {
     let games = pgns
+        .iter()
+        .map(|pgn| write_pgn(pgn))
+        .collect::<Result<Vec<String>, _>>()?;
+    match file_name {
+        Some(file_name) => fs::write(file_name, games.join("\n"))?,
+        None => print!("{}", games.join("\n")),
+    }
+    Ok(())
+}
+
+// The game picked by its number counting from 1, with that number
+fn game_chosen<'a>(
+    matches: &ArgMatches,
+    games: &'a [Game],
+) -> Result<(usize, &'a Game), Box<dyn Error>> {
+    let number = matches.get_one::<usize>("game").copied().unwrap_or(1);
+    let game = number
+        .checked_sub(1)
+        .and_then(|index| games.get(index))
+        .ok_or_else(|| format!("There is no game {number}, only {} loaded", games.len()))?;
+    Ok((number, game))
+}
+
+fn ply_chosen(matches: &ArgMatches, game: &Game) -> usize {
+    matches
+        .get_one::<usize>("ply")
+        .copied()
+        .unwrap_or(game.pgn().ply().len())
+}
+
+fn perspective_from(matches: &ArgMatches) -> PieceColour {
+    match matches.get_one::<String>("perspective").map(String::as_str) {
+        Some("black") => PieceColour::Black,
+        _ => PieceColour::White,
+    }
+}
+
+fn print_occurrences(games: &[Game], occurrences: &[Occurrence], not_found: &str) {
+    if occurrences.is_empty() {
+        println!("{not_found}");
+    }
+    for occurrence in occurrences {
+        let pgn = games[occurrence.game()].pgn();
+        println!(
+            "Game {}: {} - {}, {}",
+            occurrence.game() + 1,
+            pgn.tags().get_or_default("White", "Unknown"),
+            pgn.tags().get_or_default("Black", "Unknown"),
+            occurrence.describe(pgn)
+        );
+    }
+}
+
+fn config_from(matches: &ArgMatches) -> Result<Config, Box<dyn Error>> {
+    let mut config = load_config(matches.get_one::<String>("config").map(String::as_str))?;
+    if let Some(engine) = matches.get_one::<String>("engine") {
+        config.engine_mut().use_path(engine);
+    }
+    Ok(config)
+}
+
+fn games_from(matches: &ArgMatches) -> Result<Vec<Game>, Box<dyn Error>> {
+    pgns_from_files(matches)?
         .into_iter()
         .map(game_from)
-        .collect::<Result<Vec<Game>, Box<dyn Error>>>()?;
+        .collect()
+}
 
-    launch(games)?;
-    Ok(())
+fn pgns_from_files(matches: &ArgMatches) -> PgnsResult {
+    sources_from(matches)?
+        .iter()
+        .map(pgns_from)
+        .reduce(reduce)
+        .unwrap_or_else(|| Ok(Vec::new()))
+}
+
+// Every file, archive or standard input named by the file arguments, each of which has to name at
+// least one
+fn sources_from(matches: &ArgMatches) -> Result<Vec<Source>, Box<dyn Error>> {
+    let encoding = matches.get_one::<String>("encoding").map(String::as_str);
+    let mut sources = Vec::new();
+    for argument in matches
+        .get_many::<String>("file")
+        .ok_or("'file' argument not provided")?
+    {
+        let read = read_input(argument, encoding)?;
+        if read.is_empty() {
+            return Err(format!("No games found in {argument}").into());
+        }
+        sources.extend(read);
+    }
+    Ok(sources)
 }
 
-fn pgns_from(file_name: &str) -> PgnsResult {
-    let file = fs::read_to_string(file_name)?;
-    parse(&file).map_err(|err| err.into())
+fn pgns_from(source: &Source) -> PgnsResult {
+    parse(source.text()).map_err(|err| format!("{}: {err}", source.name()).into())
 }
 
 fn game_from(pgn: Pgn) -> Result<Game, Box<dyn Error>> {
@@ -3,6 +3,7 @@ use std::collections::HashMap;
 use super::{Piece, PieceColour, Position};
 
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum AvailableCastle {
     WhiteKingside,
     WhiteQueenside,
@@ -11,7 +12,9 @@ pub enum AvailableCastle {
 }
 
 #[derive(Debug, PartialEq, Eq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Board {
+    #[cfg_attr(feature = "serde", serde(with = "grid"))]
     grid: HashMap<Position, Piece>,
     active_colour: PieceColour,
     available_castles: Vec<AvailableCastle>,
@@ -50,6 +53,12 @@ impl Board {
         self.grid.get(&position)
     }
 
+    pub fn pieces(&self) -> impl Iterator<Item = (Position, Piece)> + '_ {
+        self.grid
+            .iter()
+            .map(|(&position, &piece)| (position, piece))
+    }
+
     // TODO: review implementation
     pub fn search(&self, piece: Piece) -> Vec<Position> {
         self.grid
@@ -160,3 +169,59 @@ impl Builder {
         }
     }
 }
+
+// Map keys have to be strings in formats such as JSON, so the grid is written as a list of
+// squares and their pieces, in rank then file order
+#[cfg(feature = "serde")]
+mod grid {
+    use std::collections::HashMap;
+
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    use crate::model::{Piece, Position};
+
+    pub fn serialize<S>(grid: &HashMap<Position, Piece>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut squares: Vec<(&Position, &Piece)> = grid.iter().collect();
+        squares.sort_by_key(|(position, _)| (position.row(), position.col()));
+        serializer.collect_seq(squares)
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<HashMap<Position, Piece>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        Vec::<(Position, Piece)>::deserialize(deserializer)
+            .map(|squares| squares.into_iter().collect())
+    }
+}
+
+#[cfg(all(test, feature = "serde"))]
+mod tests {
+    use crate::{model::Pgn, parse};
+
+    use super::*;
+
+    #[test]
+    fn round_trips_board_through_json() {
+        let board = parse::fen("4k3/8/8/3pP3/8/8/8/R3K2R w KQ d6 0 30").unwrap();
+        let json = serde_json::to_value(&board).unwrap();
+
+        assert_eq!(json["grid"].as_array().unwrap().len(), 6);
+        assert_eq!(serde_json::from_value::<Board>(json).unwrap(), board);
+    }
+
+    #[test]
+    fn round_trips_game_through_json() {
+        let pgn = parse::parse(
+            "[White \"One\"]\n[Result \"1-0\"]\n\n{Start} 1. e4 $1 {Best} (1. d4 d5) e5 1-0\n",
+        )
+        .unwrap()
+        .remove(0);
+        let json = serde_json::to_string(&pgn).unwrap();
+
+        assert_eq!(serde_json::from_str::<Pgn>(&json).unwrap(), pgn);
+    }
+}
@@ -17,4 +17,8 @@ impl Game {
     pub fn pgn(&self) -> &Pgn {
         &self.pgn
     }
+
+    pub fn pgn_mut(&mut self) -> &mut Pgn {
+        &mut self.pgn
+    }
 }
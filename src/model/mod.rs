@@ -7,7 +7,7 @@ mod position;
 
 pub use board::{AvailableCastle, Board};
 pub use game::Game;
-pub use pgn::{Fen, GameResult, Pgn, Tags};
+pub use pgn::{Fen, GameResult, Pgn, Tags, DEFAULT_FEN, SEVEN_TAG_ROSTER};
 pub use piece::{Piece, PieceColour, PieceType};
 pub use ply::{Check, MoveQualifier, Movement, Ply, PlyMovement};
 pub use position::{Position, COLUMNS, MAX_POSITION, MIN_POSITION, ROWS};
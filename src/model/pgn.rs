@@ -1,8 +1,12 @@
-use std::collections::HashMap;
-
 use super::{board::Board, PieceColour, Ply};
 
+// The tags every game in export format carries, in the order they're written
+pub const SEVEN_TAG_ROSTER: [&str; 7] =
+    ["Event", "Site", "Date", "Round", "White", "Black", "Result"];
+pub const DEFAULT_FEN: &str = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1";
+
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum GameResult {
     BlackWin,
     WhiteWin,
@@ -11,6 +15,7 @@ pub enum GameResult {
 }
 
 #[derive(Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Fen {
     starting_board: Board,
     active_colour: PieceColour,
@@ -40,15 +45,28 @@ impl Fen {
 }
 
 #[derive(Debug, PartialEq, Eq)]
-pub struct Tags(HashMap<String, String>);
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Tags(Vec<(String, String)>);
 
+// Tags are kept in the order they were read. A tag given again replaces the earlier value where it
+// was
 impl Tags {
-    pub fn new(tags: HashMap<String, String>) -> Self {
-        Self(tags)
+    pub fn new(tags: Vec<(String, String)>) -> Self {
+        tags.into_iter().collect()
     }
 
     pub fn get(&self, key: &str) -> Option<&String> {
-        self.0.get(key)
+        self.0
+            .iter()
+            .find(|(tag, _)| tag == key)
+            .map(|(_, value)| value)
+    }
+
+    pub fn insert(&mut self, key: String, value: String) {
+        match self.0.iter_mut().find(|(tag, _)| *tag == key) {
+            Some((_, existing)) => *existing = value,
+            None => self.0.push((key, value)),
+        }
     }
 
     pub fn get_or_default(&self, key: &str, default: &str) -> String {
@@ -57,20 +75,33 @@ impl Tags {
     }
 
     pub fn remove(&mut self, key: &str) -> Option<String> {
-        self.0.remove(key)
+        let index = self.0.iter().position(|(tag, _)| tag == key)?;
+        Some(self.0.remove(index).1)
     }
 
-    pub fn inner(&self) -> &HashMap<String, String> {
+    pub fn inner(&self) -> &[(String, String)] {
         &self.0
     }
 }
 
+impl FromIterator<(String, String)> for Tags {
+    fn from_iter<I: IntoIterator<Item = (String, String)>>(tags: I) -> Self {
+        let mut collected = Self(Vec::new());
+        for (key, value) in tags {
+            collected.insert(key, value);
+        }
+        collected
+    }
+}
+
 #[derive(Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Pgn {
     tags: Tags,
     fen: Fen,
     result: GameResult,
     ply: Vec<Ply>,
+    comment: Option<String>,
 }
 
 impl Pgn {
@@ -80,6 +111,7 @@ impl Pgn {
             fen,
             result,
             ply: ply_list,
+            comment: None,
         }
     }
 
@@ -98,4 +130,17 @@ impl Pgn {
     pub fn ply(&self) -> &[Ply] {
         &self.ply
     }
+
+    pub fn ply_mut(&mut self) -> &mut [Ply] {
+        &mut self.ply
+    }
+
+    // For a game without moves, as otherwise comments are kept with the moves
+    pub fn comment(&self) -> Option<&String> {
+        self.comment.as_ref()
+    }
+
+    pub fn set_comment(&mut self, comment: Option<String>) {
+        self.comment = comment;
+    }
 }
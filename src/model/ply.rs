@@ -3,7 +3,8 @@ use crate::model::position::Position;
 
 use super::{Piece, PieceColour};
 
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, PartialEq, Eq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Movement {
     piece: Piece,
     position: Position,
@@ -23,20 +24,23 @@ impl Movement {
     }
 }
 
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, PartialEq, Eq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum MoveQualifier {
     Row(i8),
     Col(i8),
     Position(Position),
 }
 
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, PartialEq, Eq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Check {
     Check,
     Checkmate,
 }
 
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, PartialEq, Eq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum PlyMovement {
     KingsideCastle {
         colour: PieceColour,
@@ -61,11 +65,27 @@ pub enum PlyMovement {
     },
 }
 
-#[derive(Debug, PartialEq, Eq)]
+impl PlyMovement {
+    pub fn colour(&self) -> PieceColour {
+        match self {
+            PlyMovement::KingsideCastle { colour, .. }
+            | PlyMovement::QueensideCastle { colour, .. } => *colour,
+            PlyMovement::Move { movement, .. } | PlyMovement::Promotion { movement, .. } => {
+                *movement.piece().colour()
+            }
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Ply {
     move_number: i16,
     movement: PlyMovement,
     comment: Option<String>,
+    comment_before: Option<String>,
+    nags: Vec<u8>,
+    variations: Vec<Vec<Ply>>,
 }
 
 impl Ply {
@@ -74,6 +94,9 @@ impl Ply {
             move_number,
             movement: ply,
             comment,
+            comment_before: None,
+            nags: Vec::new(),
+            variations: Vec::new(),
         }
     }
 
@@ -85,7 +108,39 @@ impl Ply {
         &self.movement
     }
 
+    // On the position after this ply
     pub fn comment(&self) -> Option<&String> {
         self.comment.as_ref()
     }
+
+    pub fn set_comment(&mut self, comment: Option<String>) {
+        self.comment = comment;
+    }
+
+    // On the position before this ply, such as one introducing the game or a variation
+    pub fn comment_before(&self) -> Option<&String> {
+        self.comment_before.as_ref()
+    }
+
+    pub fn set_comment_before(&mut self, comment: Option<String>) {
+        self.comment_before = comment;
+    }
+
+    // Numeric Annotation Glyphs, e.g. 2 for a mistake, written as $2
+    pub fn nags(&self) -> &[u8] {
+        &self.nags
+    }
+
+    pub fn add_nag(&mut self, nag: u8) {
+        self.nags.push(nag);
+    }
+
+    // Each variation is a line played instead of this ply
+    pub fn variations(&self) -> &[Vec<Ply>] {
+        &self.variations
+    }
+
+    pub fn add_variation(&mut self, variation: Vec<Ply>) {
+        self.variations.push(variation);
+    }
 }
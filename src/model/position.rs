@@ -22,6 +22,7 @@ impl std::fmt::Display for InvalidPositionError {
 impl std::error::Error for InvalidPositionError {}
 
 #[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Position {
     row: i8,
     col: i8,
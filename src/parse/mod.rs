@@ -7,15 +7,166 @@ mod position;
 mod result;
 mod tag;
 
+use std::ops::Range;
+
 use nom::{combinator::all_consuming, multi::many1};
 
-use crate::model::Pgn;
+use crate::model::{Board, GameResult, Pgn, PieceColour, PlyMovement};
 
 use self::error::PgnParseError;
 
+// Left at the start of text by some editors, even in UTF-8
+const BYTE_ORDER_MARK: char = '\u{feff}';
+
 pub fn parse(input: &str) -> Result<Vec<Pgn>, PgnParseError> {
+    let input = input.strip_prefix(BYTE_ORDER_MARK).unwrap_or(input);
     let (_, pgns) = all_consuming(many1(pgn::parse))(input)
         .map_err(|e| PgnParseError::new(format!("Failed to parse games: {e}")))?;
 
     Ok(pgns)
 }
+
+// Each game with the part of the input it was read from, which starts at its first tag rather
+// than any blank lines before it. A game which can't be read is reported with where reading it
+// stopped, and reading carries on from the next tag section
+pub fn games(input: &str) -> Vec<(Range<usize>, Result<Pgn, PgnParseError>)> {
+    let mut games = Vec::new();
+    let mut start = 0;
+    loop {
+        let rest =
+            input[start..].trim_start_matches(|c: char| c.is_whitespace() || c == BYTE_ORDER_MARK);
+        if rest.is_empty() {
+            break;
+        }
+        start = input.len() - rest.len();
+        // A game can stop short of an unreadable move, leaving the rest of its movetext behind
+        let (end, pgn) = match pgn::parse(rest) {
+            Ok((remaining, pgn)) if remaining.trim().is_empty() || remaining.starts_with('[') => {
+                (input.len() - remaining.len(), Ok(pgn))
+            }
+            Ok((remaining, _)) => (next_game(input, start), Err(remaining)),
+            Err(nom::Err::Error(e) | nom::Err::Failure(e)) => {
+                (next_game(input, start), Err(e.input))
+            }
+            Err(nom::Err::Incomplete(_)) => (input.len(), Err(rest)),
+        };
+
+        let pgn = pgn.map_err(|stopped| {
+            let offset = input.len() - stopped.len();
+            let line = input[..offset].matches('\n').count() + 1;
+            match stopped.lines().next().map(str::trim) {
+                Some(near) if !near.is_empty() => PgnParseError::new(format!(
+                    "Failed to read the game at line {line}, near '{near}'"
+                )),
+                _ => PgnParseError::new(format!("The game stops short at line {line}")),
+            }
+        });
+        games.push((start..end, pgn));
+        start = end;
+    }
+    games
+}
+
+// Where the tag section after the game starting at the offset begins, being the first line opening
+// with a tag after a blank line, or the end of the input if there isn't one
+fn next_game(input: &str, start: usize) -> usize {
+    let mut offset = start;
+    let mut blank = false;
+    for line in input[start..].split_inclusive('\n') {
+        if blank && line.starts_with('[') {
+            return offset;
+        }
+        blank = line.trim().is_empty();
+        offset += line.len();
+    }
+    input.len()
+}
+
+pub fn fen(input: &str) -> Result<Board, PgnParseError> {
+    let (_, fen) = fen::parse(input.trim())
+        .map_err(|e| PgnParseError::new(format!("Failed to parse FEN '{input}': {e}")))?;
+
+    Ok(fen.starting_board().clone())
+}
+
+// A single move in SAN, as typed rather than read from movetext
+pub fn san(input: &str, colour: PieceColour) -> Result<PlyMovement, PgnParseError> {
+    let input = format!("{} ", input.trim());
+    let (_, movement) = all_consuming(|input| ply::parse(input, colour))(&input)
+        .map_err(|_| PgnParseError::new(format!("'{}' is not a valid move", input.trim())))?;
+
+    Ok(movement)
+}
+
+// A tag pair on a line of its own, e.g. [Event "Casual"]
+pub fn tag(input: &str) -> Result<(String, String), PgnParseError> {
+    let input = format!("{}\n", input.trim());
+    let (_, (key, value)) = all_consuming(tag::parse_tag)(&input)
+        .map_err(|_| PgnParseError::new(format!("'{}' is not a valid tag", input.trim())))?;
+
+    Ok((key.to_string(), value))
+}
+
+pub fn result(input: &str) -> Result<GameResult, PgnParseError> {
+    let (_, result) = all_consuming(result::parse)(input.trim())
+        .map_err(|_| PgnParseError::new(format!("'{}' is not a valid result", input.trim())))?;
+
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod games_tests {
+        use super::*;
+
+        const GAMES: &str = "[Result \"1-0\"]\n\n1. e4 e5 1-0\n\n\
+            [Result \"*\"]\n\n1. e4 Kz9 2. d4 *\n\n\
+            [Result \"0-1\"]\n\n1. d4 0-1\n";
+
+        #[test]
+        fn reads_each_game_with_its_text() {
+            let games = games(GAMES);
+            assert_eq!(games.len(), 3);
+            assert_eq!(
+                &GAMES[games[0].0.clone()],
+                "[Result \"1-0\"]\n\n1. e4 e5 1-0\n\n"
+            );
+            assert_eq!(games[2].1.as_ref().unwrap().ply().len(), 1);
+        }
+
+        #[test]
+        fn carries_on_after_unreadable_game() {
+            let games = games(GAMES);
+            assert_eq!(
+                games[1].1.as_ref().unwrap_err().message(),
+                "Failed to read the game at line 7, near 'Kz9 2. d4 *'"
+            );
+            assert!(GAMES[games[2].0.clone()].starts_with("[Result \"0-1\"]"));
+        }
+
+        #[test]
+        fn ignores_trailing_whitespace() {
+            assert!(games("\n\n").is_empty());
+            assert_eq!(games(&format!("{GAMES}\n\n")).len(), 3);
+        }
+
+        #[test]
+        fn skips_leading_blank_lines() {
+            let input = format!("\n\n{GAMES}");
+            let games = games(&input);
+            assert_eq!(games.len(), 3);
+            assert!(games[0].1.is_ok());
+            assert!(input[games[0].0.clone()].starts_with("[Result \"1-0\"]"));
+        }
+
+        #[test]
+        fn skips_byte_order_mark() {
+            let input = format!("\u{feff}{GAMES}");
+            let games = games(&input);
+            assert!(input[games[0].0.clone()].starts_with("[Result \"1-0\"]"));
+            assert_eq!(parse(&input[..games[0].0.end]).unwrap().len(), 1);
+        }
+    }
+}
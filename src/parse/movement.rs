@@ -2,10 +2,10 @@ use crate::model::{PieceColour, Ply};
 use nom::branch::alt;
 use nom::bytes::complete::tag;
 use nom::bytes::complete::take_until;
-use nom::character::complete::{char, line_ending, space1};
-use nom::combinator::{map, opt};
+use nom::character::complete::{char, line_ending, multispace0};
+use nom::combinator::{map, opt, rest};
 use nom::multi::many0;
-use nom::sequence::delimited;
+use nom::sequence::{delimited, preceded};
 use nom::{
     character::complete::{digit1, space0},
     combinator::map_res,
@@ -20,60 +20,152 @@ pub fn parse(input: &str) -> IResult<&str, Vec<Ply>> {
     alt((result_only_parser, parse_moves))(input)
 }
 
+// The comments of a game with no moves, which can only be on the game as a whole
+pub fn parse_without_moves(input: &str) -> IResult<&str, Option<String>> {
+    map(
+        tuple((comments, result::parse, comments)),
+        |(before, _, after)| match (before, after) {
+            (Some(before), Some(after)) => Some(format!("{before} {after}")),
+            (before, after) => before.or(after),
+        },
+    )(input)
+}
+
+// Comments before the first move, whether or not its move number comes first, are on the position
+// the game or variation starts from
 fn parse_moves(input: &str) -> IResult<&str, Vec<Ply>> {
+    let (remaining, before) = comments(input)?;
     let (remaining, mut first_move) =
-        alt((parse_move, map(parse_partial_move, |ply| vec![ply])))(input)?;
+        alt((parse_move, map(parse_partial_move, |ply| vec![ply])))(remaining)?;
 
     let (remaining, mut other_moves) = map(many0(parse_move), |list| {
         list.into_iter().flatten().collect()
     })(remaining)?;
+
+    if let (Some(before), Some(first)) = (before, first_move.first_mut()) {
+        let comment = match first.comment_before() {
+            Some(existing) => format!("{before} {existing}"),
+            None => before,
+        };
+        first.set_comment_before(Some(comment));
+    }
     first_move.append(&mut other_moves);
     Ok((remaining, first_move))
 }
 
 fn parse_move(input: &str) -> IResult<&str, Vec<Ply>> {
     let (remaining, move_number) = white_move_number(input)?;
-    let (remaining, white_ply) = ply::parse(remaining, PieceColour::White)?;
-    let (remaining, white_comment) = opt(comment)(remaining)?;
+    let (remaining, white_ply) = annotated_ply(remaining, move_number, PieceColour::White)?;
 
-    let (remaining, maybe_result) = opt(result::parse)(remaining)?;
+    let (remaining, maybe_ending) = opt(ending)(remaining)?;
 
-    if maybe_result.is_some() {
-        return Ok((
-            remaining,
-            vec![Ply::new(move_number, white_ply, white_comment)],
-        ));
+    if let Some(ending) = maybe_ending {
+        return Ok((remaining, vec![annotate(white_ply, ending)]));
     }
 
-    let (remaining, maybe_black_move_number) = opt(black_move_number)(remaining)?;
-
-    let (remaining, black_ply) = ply::parse(remaining, PieceColour::Black)?;
-    let (remaining, black_comment) = opt(comment)(remaining)?;
+    // A variation may end on White's move
+    let Ok((remaining, black_ply)) = black_reply(remaining, move_number) else {
+        return Ok((remaining, vec![white_ply]));
+    };
 
-    let (remaining, _) = opt(result::parse)(remaining)?;
+    let (remaining, ending) = opt(ending)(remaining)?;
 
     Ok((
         remaining,
-        vec![
-            Ply::new(move_number, white_ply, white_comment),
-            Ply::new(
-                maybe_black_move_number.unwrap_or(move_number),
-                black_ply,
-                black_comment,
-            ),
-        ],
+        vec![white_ply, annotate(black_ply, ending.unwrap_or_default())],
     ))
 }
 
+fn black_reply(input: &str, move_number: i16) -> IResult<&str, Ply> {
+    let (remaining, maybe_black_move_number) = opt(black_move_number)(input)?;
+
+    annotated_ply(
+        remaining,
+        maybe_black_move_number.unwrap_or(move_number),
+        PieceColour::Black,
+    )
+}
+
 fn parse_partial_move(input: &str) -> IResult<&str, Ply> {
     let (remaining, move_number) = black_move_number(input)?;
 
-    let (remaining, ply) = ply::parse(remaining, PieceColour::Black)?;
-    let (remaining, comment) = opt(comment)(remaining)?;
+    let (remaining, ply) = annotated_ply(remaining, move_number, PieceColour::Black)?;
 
-    let (remaining, _) = opt(result::parse)(remaining)?;
+    let (remaining, ending) = opt(ending)(remaining)?;
+
+    Ok((remaining, annotate(ply, ending.unwrap_or_default())))
+}
+
+// A ply with any comments between its move number and its move, which are on the position before
+// it, and whatever follows it
+fn annotated_ply(input: &str, move_number: i16, colour: PieceColour) -> IResult<&str, Ply> {
+    let (remaining, before) = comments(input)?;
+    let (remaining, ply) = ply::parse(remaining, colour)?;
+    let (remaining, annotations) = annotations(remaining)?;
+
+    let mut ply = annotate(Ply::new(move_number, ply, None), annotations);
+    ply.set_comment_before(before);
+    Ok((remaining, ply))
+}
+
+// The result, with any comments after it, which are kept with the last move
+fn ending(input: &str) -> IResult<&str, Vec<Annotation>> {
+    preceded(
+        result::parse,
+        many0(map(preceded(multispace0, comment), Annotation::Comment)),
+    )(input)
+}
+
+// Several comments in a row, joined together
+fn comments(input: &str) -> IResult<&str, Option<String>> {
+    map(many0(preceded(multispace0, comment)), |comments| {
+        (!comments.is_empty()).then(|| comments.join(" "))
+    })(input)
+}
+
+enum Annotation {
+    Comment(String),
+    Nag(u8),
+    Variation(Vec<Ply>),
+}
+
+// Whatever follows a ply before the next move. Several comments on one ply are joined together
+fn annotations(input: &str) -> IResult<&str, Vec<Annotation>> {
+    many0(alt((
+        map(comment, Annotation::Comment),
+        map(nag, Annotation::Nag),
+        map(variation, Annotation::Variation),
+    )))(input)
+}
+
+fn annotate(mut ply: Ply, annotations: Vec<Annotation>) -> Ply {
+    for annotation in annotations {
+        match annotation {
+            Annotation::Comment(comment) => {
+                let comment = match ply.comment() {
+                    Some(existing) => format!("{existing} {comment}"),
+                    None => comment,
+                };
+                ply.set_comment(Some(comment));
+            }
+            Annotation::Nag(nag) => ply.add_nag(nag),
+            Annotation::Variation(variation) => ply.add_variation(variation),
+        }
+    }
+    ply
+}
+
+fn nag(input: &str) -> IResult<&str, u8> {
+    let parser = delimited(char('$'), digit1, multispace0);
+    map_res(parser, |s: &str| s.parse::<u8>())(input)
+}
 
-    Ok((remaining, Ply::new(move_number, ply, comment)))
+fn variation(input: &str) -> IResult<&str, Vec<Ply>> {
+    delimited(
+        tuple((char('('), multispace0)),
+        parse_moves,
+        tuple((char(')'), multispace0)),
+    )(input)
 }
 
 fn white_move_number(input: &str) -> IResult<&str, i16> {
@@ -93,13 +185,17 @@ fn comment(input: &str) -> IResult<&str, String> {
 fn parenthesis_comment(input: &str) -> IResult<&str, String> {
     let parser = terminated(
         delimited(char('{'), take_until("}"), char('}')),
-        alt((space1, line_ending)),
+        multispace0,
     );
     map(parser, |s: &str| s.replace('\n', " "))(input)
 }
 
+// Runs to the end of the line, or of the input if that comes first
 fn semicolon_comment(input: &str) -> IResult<&str, String> {
-    let parser = delimited(char(';'), take_until("\n"), line_ending);
+    let parser = preceded(
+        char(';'),
+        alt((terminated(take_until("\n"), line_ending), rest)),
+    );
     map(parser, |s: &str| s.trim().to_string())(input)
 }
 
@@ -232,6 +328,61 @@ mod tests {
         }
     }
 
+    mod comment_placement_tests {
+        use super::*;
+
+        fn comments(ply: &Ply) -> (Option<&str>, Option<&str>) {
+            (
+                ply.comment_before().map(String::as_str),
+                ply.comment().map(String::as_str),
+            )
+        }
+
+        #[test]
+        fn parses_comments_before_first_move() {
+            let (_, ply) = parse("{Intro} ; More\n1. e4 ({Instead} 1. d4) e5 *").unwrap();
+            assert_eq!(comments(&ply[0]), (Some("Intro More"), None));
+            assert_eq!(
+                comments(&ply[0].variations()[0][0]),
+                (Some("Instead"), None)
+            );
+        }
+
+        #[test]
+        fn parses_consecutive_comments() {
+            let (_, ply) = parse("1. e4 {One}  {Two}\n; Three\ne5 *").unwrap();
+            assert_eq!(comments(&ply[0]), (None, Some("One Two Three")));
+        }
+
+        #[test]
+        fn parses_comments_after_move_numbers() {
+            let (_, ply) = parse("1. {White} e4 1... {Black} e5 2.{Again} Nf3 *").unwrap();
+            assert_eq!(comments(&ply[0]), (Some("White"), None));
+            assert_eq!(comments(&ply[1]), (Some("Black"), None));
+            assert_eq!(comments(&ply[2]), (Some("Again"), None));
+        }
+
+        #[test]
+        fn parses_comments_after_result() {
+            let (remaining, ply) = parse("1. e4 {Good} 1-0 {Resigns} ; Early\n\n[Event").unwrap();
+            assert_eq!(remaining, "\n[Event");
+            assert_eq!(comments(&ply[0]), (None, Some("Good Resigns Early")));
+
+            let (_, ply) = parse("1. e4 e5 * {Unfinished}").unwrap();
+            assert_eq!(comments(&ply[1]), (None, Some("Unfinished")));
+        }
+
+        #[test]
+        fn parses_comments_without_moves() {
+            assert_eq!(
+                parse_without_moves("{No moves} 1-0 {At all}").unwrap(),
+                ("", Some(String::from("No moves At all")))
+            );
+            assert_eq!(parse_without_moves("* rest").unwrap(), (" rest", None));
+            assert!(parse_without_moves("1. e4 *").is_err());
+        }
+    }
+
     mod parse_move_tests {
         use crate::model::{
             Check, MoveQualifier, Movement, Piece, PieceColour, PieceType, PlyMovement, Position,
@@ -343,6 +494,24 @@ mod tests {
             assert_eq!(result, ("3. f7 Qb2", expected_ply))
         }
 
+        #[test]
+        fn parses_move_with_nags_and_variations() {
+            let (remaining, ply) =
+                parse_move("1. e4 $1 {Best} (1. d4 d5 {Solid}) 1... c5 $2 (1... e5) 2. Nf3")
+                    .unwrap();
+
+            assert_eq!(remaining, "2. Nf3");
+            assert_eq!(ply[0].nags(), &[1]);
+            assert_eq!(ply[0].comment().map(String::as_str), Some("Best"));
+            assert_eq!(ply[0].variations()[0].len(), 2);
+            assert_eq!(
+                ply[0].variations()[0][1].comment().map(String::as_str),
+                Some("Solid")
+            );
+            assert_eq!(ply[1].nags(), &[2]);
+            assert_eq!(ply[1].variations()[0].len(), 1);
+        }
+
         #[test]
         fn parses_move_with_result_after_white_move() {
             let result = parse_move("2. e4 1-0 something").unwrap();
@@ -1,3 +1,5 @@
+use nom::branch::alt;
+use nom::combinator::map;
 use nom::error::ParseError;
 use nom::error::{Error, ErrorKind};
 use nom::multi::many0;
@@ -9,14 +11,12 @@ use super::fen;
 use super::movement;
 use super::result;
 use super::tag;
-use crate::model::Pgn;
-
-static DEFAULT_FEN: &str = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1";
+use crate::model::{Pgn, Ply, DEFAULT_FEN};
 
 // TODO: consider how to improve error handling here
 pub fn parse(input: &str) -> IResult<&str, Pgn> {
-    let (remaining, (mut tags, ply)) =
-        terminated(pair(tag::parse, movement::parse), many0(line_ending))(input)?;
+    let (remaining, (mut tags, (ply, comment))) =
+        terminated(pair(tag::parse, movetext), many0(line_ending))(input)?;
 
     let fen = &tags
         .remove("FEN")
@@ -32,12 +32,23 @@ pub fn parse(input: &str) -> IResult<&str, Pgn> {
     let (_, result) = all_consuming(result::parse)(result)
         .map_err(|_| nom::Err::Error(Error::from_error_kind(input, ErrorKind::Tag)))?;
 
-    Ok((remaining, Pgn::new(tags, fen, result, ply)))
+    let mut pgn = Pgn::new(tags, fen, result, ply);
+    pgn.set_comment(comment);
+    Ok((remaining, pgn))
+}
+
+fn movetext(input: &str) -> IResult<&str, (Vec<Ply>, Option<String>)> {
+    alt((
+        map(movement::parse_without_moves, |comment| {
+            (Vec::new(), comment)
+        }),
+        map(movement::parse, |ply| (ply, None)),
+    ))(input)
 }
 
 #[cfg(test)]
 mod tests {
-    use std::{collections::HashMap, fs};
+    use std::fs;
 
     use crate::model::{
         AvailableCastle, Board, Fen, GameResult, Movement, Piece, PieceColour, PieceType, Ply,
@@ -63,9 +74,10 @@ mod tests {
     }
 
     fn expected() -> Pgn {
-        let mut tags: HashMap<String, String> = HashMap::new();
-        tags.insert("White".to_string(), "Player, One".to_string());
-        tags.insert("Black".to_string(), "Player, Two".to_string());
+        let tags = vec![
+            ("White".to_string(), "Player, One".to_string()),
+            ("Black".to_string(), "Player, Two".to_string()),
+        ];
 
         let ply_list = vec![
             Ply::new(
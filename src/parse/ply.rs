@@ -2,7 +2,7 @@ use nom::{
     branch::alt,
     bytes::complete::tag,
     character::complete::{line_ending, one_of, space1},
-    combinator::{map, map_res, opt},
+    combinator::{map, map_res, opt, peek},
     sequence::{pair, terminated, tuple},
     IResult,
 };
@@ -125,8 +125,9 @@ fn piece_type(input: &str) -> IResult<&str, PieceType> {
     })(input)
 }
 
+// The last move of a variation may be followed directly by its closing bracket
 fn ply_terminator(input: &str) -> IResult<&str, &str> {
-    alt((space1, line_ending))(input)
+    alt((space1, line_ending, peek(tag(")"))))(input)
 }
 
 fn check(input: &str) -> IResult<&str, Check> {
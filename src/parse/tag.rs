@@ -1,65 +1,124 @@
-use std::collections::HashMap;
-
 use nom::character::complete::char;
 use nom::{
-    bytes::complete::take_until,
-    character::complete::line_ending,
+    bytes::complete::take_while1,
+    character::complete::{line_ending, space0},
+    error::{Error, ErrorKind},
     multi::many_till,
-    sequence::{delimited, separated_pair, terminated},
+    sequence::{preceded, terminated, tuple},
     IResult,
 };
 
 use crate::model::Tags;
 
 pub fn parse(input: &str) -> IResult<&str, Tags> {
-    let (remaining, (tags, _)) = many_till(parse_tag, line_ending)(input)?;
-    let tags: HashMap<String, String> = tags
+    let (remaining, (tags, _)) = many_till(parse_tag, preceded(space0, line_ending))(input)?;
+    let tags = tags
         .into_iter()
-        .map(|pair: (&str, &str)| (pair.0.to_string(), pair.1.to_string()))
+        .map(|(key, value)| (key.to_string(), value))
         .collect();
 
     Ok((remaining, Tags::new(tags)))
 }
 
-fn parse_tag(input: &str) -> IResult<&str, (&str, &str)> {
-    fn parse_key_value(input: &str) -> IResult<&str, (&str, &str)> {
-        separated_pair(take_until(" "), char(' '), parse_value)(input)
-    }
-    terminated(
-        delimited(char('['), parse_key_value, char(']')),
-        line_ending,
-    )(input)
+// Spaces and tabs are allowed around the name and value as well as around the brackets
+pub fn parse_tag(input: &str) -> IResult<&str, (&str, String)> {
+    let (remaining, (_, _, _, key, _, value, _, _)) = terminated(
+        tuple((
+            space0,
+            char('['),
+            space0,
+            parse_key,
+            space0,
+            parse_value,
+            space0,
+            char(']'),
+        )),
+        preceded(space0, line_ending),
+    )(input)?;
+    Ok((remaining, (key, value)))
+}
+
+fn parse_key(input: &str) -> IResult<&str, &str> {
+    take_while1(|c: char| c.is_alphanumeric() || "_+#=:-".contains(c))(input)
 }
 
-fn parse_value(input: &str) -> IResult<&str, &str> {
-    delimited(char('"'), take_until("\""), char('"'))(input)
+// Within a value a quote or backslash is escaped with a backslash. Any other backslash is kept as it
+// is, as some databases don't escape them in file paths. A value can't run over a line
+fn parse_value(input: &str) -> IResult<&str, String> {
+    let (rest, _) = char('"')(input)?;
+    let mut value = String::new();
+    let mut chars = rest.char_indices().peekable();
+    while let Some((index, c)) = chars.next() {
+        match c {
+            '"' => return Ok((&rest[index + 1..], value)),
+            '\\' => match chars.peek() {
+                Some(&(_, escaped @ ('"' | '\\'))) => {
+                    value.push(escaped);
+                    chars.next();
+                }
+                _ => value.push(c),
+            },
+            '\n' | '\r' => break,
+            c => value.push(c),
+        }
+    }
+    Err(nom::Err::Error(Error::new(input, ErrorKind::Char)))
 }
 
 #[cfg(test)]
 mod tests {
-    use std::collections::HashMap;
-
     use super::*;
 
     #[test]
     fn parses_tags() {
-        let result = parse("[Tag1 \"Value 1\"]\n[Tag2 \"Value 2\"]\n\r\n1. e4").unwrap();
-        let mut expected = HashMap::new();
-        expected.insert("Tag1".to_string(), "Value 1".to_string());
-        expected.insert("Tag2".to_string(), "Value 2".to_string());
+        let result = parse("[Tag2 \"Value 2\"]\n[Tag1 \"Value 1\"]\n\r\n1. e4").unwrap();
+        let expected = vec![
+            ("Tag2".to_string(), "Value 2".to_string()),
+            ("Tag1".to_string(), "Value 1".to_string()),
+        ];
 
         assert_eq!(result, ("1. e4", Tags::new(expected)))
     }
 
+    #[test]
+    fn replaces_repeated_tag() {
+        let (_, tags) = parse("[A \"1\"]\n[B \"2\"]\n[A \"3\"]\n\n").unwrap();
+        assert_eq!(
+            tags.inner(),
+            &[
+                ("A".to_string(), "3".to_string()),
+                ("B".to_string(), "2".to_string())
+            ]
+        );
+    }
+
     #[test]
     fn parses_tag() {
         let result = parse_tag("[Tag \"Value\"]\n1.e4").unwrap();
-        assert_eq!(result, ("1.e4", ("Tag", "Value")))
+        assert_eq!(result, ("1.e4", ("Tag", "Value".to_string())))
+    }
+
+    #[test]
+    fn tolerates_whitespace() {
+        let result = parse_tag("  [ Tag\t \"Value\" ]  \r\n").unwrap();
+        assert_eq!(result, ("", ("Tag", "Value".to_string())));
+        assert!(parse("[Tag \"Value\"]\n  \n1. e4").is_ok());
     }
 
     #[test]
     fn parses_value() {
         let result = parse_value(r#""Value""#).unwrap();
-        assert_eq!(result, ("", "Value"))
+        assert_eq!(result, ("", "Value".to_string()))
+    }
+
+    #[test]
+    fn unescapes_value() {
+        let result = parse_value(r#""The \"Immortal\" \\ C:\Games" rest"#).unwrap();
+        assert_eq!(
+            result,
+            (" rest", r#"The "Immortal" \ C:\Games"#.to_string())
+        );
+        assert!(parse_value("\"Open\n\"").is_err());
+        assert!(parse_value(r#""Escaped end\""#).is_err());
     }
 }
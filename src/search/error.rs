@@ -0,0 +1,18 @@
+use std::{error::Error, fmt::Display};
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct SearchError(String);
+
+impl SearchError {
+    pub fn new(message: impl Into<String>) -> Self {
+        SearchError(message.into())
+    }
+}
+
+impl Error for SearchError {}
+
+impl Display for SearchError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
@@ -0,0 +1,212 @@
+use std::collections::HashSet;
+
+use crate::model::{Board, Game, GameResult, Pgn, PlyMovement};
+
+use super::position::{hash, PositionIndex};
+
+// A running total of the ratings given, ignoring games without one
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+struct Average {
+    total: u64,
+    count: u64,
+}
+
+impl Average {
+    fn add(&mut self, rating: Option<&String>) {
+        if let Some(rating) = rating.and_then(|rating| rating.trim().parse::<u64>().ok()) {
+            self.total += rating;
+            self.count += 1;
+        }
+    }
+
+    fn value(self) -> Option<u64> {
+        (self.count > 0).then(|| (self.total + self.count / 2) / self.count)
+    }
+}
+
+// How often a move was played from a position and how those games ended
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct MoveStats {
+    movement: PlyMovement,
+    games: usize,
+    white_wins: usize,
+    draws: usize,
+    black_wins: usize,
+    white_elo: Average,
+    black_elo: Average,
+}
+
+impl MoveStats {
+    fn new(movement: PlyMovement) -> Self {
+        MoveStats {
+            movement,
+            games: 0,
+            white_wins: 0,
+            draws: 0,
+            black_wins: 0,
+            white_elo: Average::default(),
+            black_elo: Average::default(),
+        }
+    }
+
+    fn add(&mut self, pgn: &Pgn) {
+        self.games += 1;
+        match pgn.result() {
+            GameResult::WhiteWin => self.white_wins += 1,
+            GameResult::Draw => self.draws += 1,
+            GameResult::BlackWin => self.black_wins += 1,
+            GameResult::Ongoing => {}
+        }
+        self.white_elo.add(pgn.tags().get("WhiteElo"));
+        self.black_elo.add(pgn.tags().get("BlackElo"));
+    }
+
+    pub fn movement(&self) -> &PlyMovement {
+        &self.movement
+    }
+
+    pub fn games(&self) -> usize {
+        self.games
+    }
+
+    // Percentages of all the games, so unfinished games mean they can add up to less than 100
+    pub fn white_percentage(&self) -> f64 {
+        self.percentage(self.white_wins)
+    }
+
+    pub fn draw_percentage(&self) -> f64 {
+        self.percentage(self.draws)
+    }
+
+    pub fn black_percentage(&self) -> f64 {
+        self.percentage(self.black_wins)
+    }
+
+    pub fn average_white_elo(&self) -> Option<u64> {
+        self.white_elo.value()
+    }
+
+    pub fn average_black_elo(&self) -> Option<u64> {
+        self.black_elo.value()
+    }
+
+    fn percentage(&self, count: usize) -> f64 {
+        count as f64 * 100.0 / self.games as f64
+    }
+}
+
+// Every move played from the board across the games, most played first. Games reaching the board
+// more than once only count once for each move played from it
+pub fn explore(games: &[Game], index: &PositionIndex, board: &Board) -> Vec<MoveStats> {
+    let mut moves: Vec<(u64, MoveStats)> = Vec::new();
+    let mut counted = HashSet::new();
+
+    for occurrence in index.find(games, board) {
+        let game = &games[occurrence.game()];
+        let (Some(ply), Some(next)) = (
+            game.pgn().ply().get(occurrence.board()),
+            game.boards().get(occurrence.board() + 1),
+        ) else {
+            continue;
+        };
+
+        // The same move can be written differently from one game to the next, so moves are told
+        // apart by the position they lead to
+        let key = hash(next);
+        if !counted.insert((occurrence.game(), key)) {
+            continue;
+        }
+        let index = match moves.iter().position(|(k, _)| *k == key) {
+            Some(index) => index,
+            None => {
+                moves.push((key, MoveStats::new(ply.movement().clone())));
+                moves.len() - 1
+            }
+        };
+        moves[index].1.add(game.pgn());
+    }
+
+    let mut moves: Vec<MoveStats> = moves.into_iter().map(|(_, stats)| stats).collect();
+    moves.sort_by(|a, b| b.games.cmp(&a.games));
+    moves
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_support;
+
+    use super::*;
+
+    fn game(tags: &str, movetext: &str) -> Game {
+        test_support::game(&format!("{tags}\n\n{movetext}\n"))
+    }
+
+    fn games() -> Vec<Game> {
+        vec![
+            game(
+                "[Result \"1-0\"]\n[WhiteElo \"2700\"]\n[BlackElo \"2600\"]",
+                "1. e4 e5 2. Nf3 Nc6 1-0",
+            ),
+            game(
+                "[Result \"1/2-1/2\"]\n[WhiteElo \"2500\"]",
+                "1. e4 c5 1/2-1/2",
+            ),
+            game("[Result \"0-1\"]", "1. d4 d5 0-1"),
+            game("[Result \"0-1\"]", "1. Nf3 e5 2. e4 Nf6 0-1"),
+        ]
+    }
+
+    #[test]
+    fn counts_moves_from_position() {
+        let games = games();
+        let index = PositionIndex::new(&games);
+        let moves = explore(&games, &index, &games[0].boards()[0]);
+
+        let played: Vec<(String, usize)> = moves
+            .iter()
+            .map(|stats| (stats.movement().to_string(), stats.games()))
+            .collect();
+        assert_eq!(
+            played,
+            vec![
+                (String::from("e4"), 2),
+                (String::from("d4"), 1),
+                (String::from("Nf3"), 1)
+            ]
+        );
+    }
+
+    #[test]
+    fn summarises_results_and_ratings() {
+        let games = games();
+        let index = PositionIndex::new(&games);
+        let e4 = &explore(&games, &index, &games[0].boards()[0])[0];
+
+        assert_eq!(e4.white_percentage(), 50.0);
+        assert_eq!(e4.draw_percentage(), 50.0);
+        assert_eq!(e4.black_percentage(), 0.0);
+        assert_eq!(e4.average_white_elo(), Some(2600));
+        assert_eq!(e4.average_black_elo(), Some(2600));
+    }
+
+    #[test]
+    fn includes_transpositions() {
+        let games = games();
+        let index = PositionIndex::new(&games);
+        let moves = explore(&games, &index, &games[0].boards()[3]);
+
+        let played: Vec<String> = moves
+            .iter()
+            .map(|stats| stats.movement().to_string())
+            .collect();
+        assert_eq!(played, vec![String::from("Nc6"), String::from("Nf6")]);
+        assert_eq!(moves[1].black_percentage(), 100.0);
+    }
+
+    #[test]
+    fn ignores_final_position() {
+        let games = games();
+        let index = PositionIndex::new(&games);
+        assert!(explore(&games, &index, &games[2].boards()[2]).is_empty());
+    }
+}
@@ -0,0 +1,11 @@
+mod error;
+mod explorer;
+mod pattern;
+mod position;
+mod tags;
+
+pub use error::SearchError;
+pub use explorer::{explore, MoveStats};
+pub use pattern::PatternQuery;
+pub use position::{Occurrence, PositionIndex};
+pub use tags::TagQuery;
@@ -0,0 +1,359 @@
+use nom::{
+    branch::alt,
+    bytes::complete::tag_no_case,
+    character::complete::{char, digit1, multispace0, multispace1, one_of},
+    combinator::{all_consuming, map, map_res, opt},
+    multi::{many1, separated_list1},
+    sequence::{delimited, pair, preceded, tuple},
+    IResult,
+};
+
+use crate::model::{Board, Game, Piece, PieceColour, PieceType, Position, COLUMNS, ROWS};
+
+use super::{error::SearchError, position::Occurrence};
+
+// Kings are always on the board so aren't counted
+const COUNTED: [PieceType; 5] = [
+    PieceType::Queen,
+    PieceType::Rook,
+    PieceType::Bishop,
+    PieceType::Knight,
+    PieceType::Pawn,
+];
+
+// The pieces one side has besides its king. With a wildcard the side may have more than these
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+struct Material {
+    counts: [usize; COUNTED.len()],
+    more: bool,
+}
+
+impl Material {
+    fn on(board: &Board, colour: PieceColour) -> Self {
+        let mut counts = [0; COUNTED.len()];
+        for (_, piece) in board
+            .pieces()
+            .filter(|(_, piece)| *piece.colour() == colour)
+        {
+            if let Some(index) = COUNTED.iter().position(|t| t == piece.piece_type()) {
+                counts[index] += 1;
+            }
+        }
+        Material {
+            counts,
+            more: false,
+        }
+    }
+
+    fn matches(&self, actual: &Material) -> bool {
+        self.counts
+            .iter()
+            .zip(actual.counts)
+            .all(|(&wanted, actual)| {
+                if self.more {
+                    actual >= wanted
+                } else {
+                    actual == wanted
+                }
+            })
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+enum Content {
+    Piece(Piece),
+    AnyPiece,
+    Empty,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum Term {
+    // Either side may have either set of material
+    Material(Material, Material),
+    OppositeBishops,
+    // A square, or any square on a file or rank when either is a wildcard, holding the content
+    Square {
+        content: Content,
+        col: Option<i8>,
+        row: Option<i8>,
+    },
+}
+
+impl Term {
+    fn matches(&self, board: &Board) -> bool {
+        match self {
+            Term::Material(a, b) => {
+                let white = Material::on(board, PieceColour::White);
+                let black = Material::on(board, PieceColour::Black);
+                (a.matches(&white) && b.matches(&black)) || (b.matches(&white) && a.matches(&black))
+            }
+            Term::OppositeBishops => {
+                let square_colour = |colour| match board
+                    .search(Piece::new(colour, PieceType::Bishop))
+                    .as_slice()
+                {
+                    [bishop] => Some((bishop.row() + bishop.col()) % 2),
+                    _ => None,
+                };
+                match (
+                    square_colour(PieceColour::White),
+                    square_colour(PieceColour::Black),
+                ) {
+                    (Some(white), Some(black)) => white != black,
+                    _ => false,
+                }
+            }
+            Term::Square { content, col, row } => (0..8)
+                .flat_map(|r| (0..8).map(move |c| Position::new(r, c)))
+                .filter(|position| col.map_or(true, |col| position.col() == col))
+                .filter(|position| row.map_or(true, |row| position.row() == row))
+                .any(|position| match (content, board.occupant(position)) {
+                    (Content::Piece(piece), Some(occupant)) => piece == occupant,
+                    (Content::AnyPiece, Some(_)) | (Content::Empty, None) => true,
+                    _ => false,
+                }),
+        }
+    }
+}
+
+// Terms which must all hold on a board: material signatures such as 'R+P vs R', which match
+// either way round, 'opposite-bishops', and squares such as 'Kg1', 'pe?', '?d5' or '.e4' using
+// FEN letters for pieces
+#[derive(Debug, PartialEq, Eq)]
+pub struct PatternQuery(Vec<Term>);
+
+impl PatternQuery {
+    pub fn parse(input: &str) -> Result<Self, SearchError> {
+        let (_, terms) = all_consuming(delimited(
+            multispace0,
+            separated_list1(multispace1, term),
+            multispace0,
+        ))(input)
+        .map_err(|_| SearchError::new(format!("'{input}' is not a valid pattern")))?;
+
+        Ok(PatternQuery(terms))
+    }
+
+    pub fn matches(&self, board: &Board) -> bool {
+        self.0.iter().all(|term| term.matches(board))
+    }
+
+    // The first board of each game the pattern holds on
+    pub fn find(&self, games: &[Game]) -> Vec<Occurrence> {
+        games
+            .iter()
+            .enumerate()
+            .filter_map(|(game, g)| {
+                g.boards()
+                    .iter()
+                    .position(|board| self.matches(board))
+                    .map(|board| Occurrence::new(game, board))
+            })
+            .collect()
+    }
+}
+
+fn term(input: &str) -> IResult<&str, Term> {
+    alt((
+        map(
+            tuple((
+                material,
+                multispace1,
+                tag_no_case("vs"),
+                multispace1,
+                material,
+            )),
+            |(a, _, _, _, b)| Term::Material(a, b),
+        ),
+        map(
+            alt((tag_no_case("opposite-bishops"), tag_no_case("ocb"))),
+            |_| Term::OppositeBishops,
+        ),
+        square,
+    ))(input)
+}
+
+// e.g. 'R+P', 'KRP', '2N', 'Q+*' or a lone 'K'
+fn material(input: &str) -> IResult<&str, Material> {
+    let pieces = preceded(
+        opt(char('K')),
+        many1(preceded(opt(char('+')), material_piece)),
+    );
+    let lone_king = map(char('K'), |_| Vec::new());
+
+    map(alt((pieces, lone_king)), |pieces| {
+        let mut material = Material::default();
+        for piece in pieces {
+            match piece {
+                Some((count, index)) => material.counts[index] += count,
+                None => material.more = true,
+            }
+        }
+        material
+    })(input)
+}
+
+// A count and index into COUNTED, or None for a wildcard
+fn material_piece(input: &str) -> IResult<&str, Option<(usize, usize)>> {
+    let count = map_res(digit1, |digits: &str| digits.parse::<usize>());
+    let piece = map(one_of("QRBNP"), |c| "QRBNP".find(c).unwrap_or_default());
+
+    alt((
+        map(char('*'), |_| None),
+        map(pair(opt(count), piece), |(count, index)| {
+            Some((count.unwrap_or(1), index))
+        }),
+    ))(input)
+}
+
+fn square(input: &str) -> IResult<&str, Term> {
+    let content = map_res(one_of("KQRBNPkqrbnp?."), |c: char| {
+        let colour = if c.is_ascii_uppercase() {
+            PieceColour::White
+        } else {
+            PieceColour::Black
+        };
+        let piece_type = match c.to_ascii_uppercase() {
+            '?' => return Ok(Content::AnyPiece),
+            '.' => return Ok(Content::Empty),
+            'K' => PieceType::King,
+            'Q' => PieceType::Queen,
+            'R' => PieceType::Rook,
+            'B' => PieceType::Bishop,
+            'N' => PieceType::Knight,
+            'P' => PieceType::Pawn,
+            _ => return Err(SearchError::new(format!("'{c}' is not a piece"))),
+        };
+        Ok(Content::Piece(Piece::new(colour, piece_type)))
+    });
+    let col = map(one_of("abcdefgh?"), |c| index(COLUMNS, c));
+    let row = map(one_of("12345678?"), |c| index(ROWS, c));
+
+    map(tuple((content, col, row)), |(content, col, row)| {
+        Term::Square { content, col, row }
+    })(input)
+}
+
+fn index(characters: &str, c: char) -> Option<i8> {
+    characters
+        .find(c)
+        .and_then(|index| i8::try_from(index).ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{parse, test_support::game_of};
+
+    use super::*;
+
+    fn board(fen: &str) -> Board {
+        parse::fen(fen).unwrap()
+    }
+
+    mod parse_tests {
+        use super::*;
+
+        #[test]
+        fn returns_err_if_empty() {
+            assert!(PatternQuery::parse(" ").is_err())
+        }
+
+        #[test]
+        fn returns_err_if_not_pattern() {
+            assert!(PatternQuery::parse("R+P versus R").is_err());
+            assert!(PatternQuery::parse("Xe4").is_err());
+        }
+
+        #[test]
+        fn parses_material() {
+            let query = PatternQuery::parse("KR+2P vs R+*").unwrap();
+            assert_eq!(
+                query,
+                PatternQuery(vec![Term::Material(
+                    Material {
+                        counts: [0, 1, 0, 0, 2],
+                        more: false
+                    },
+                    Material {
+                        counts: [0, 1, 0, 0, 0],
+                        more: true
+                    }
+                )])
+            );
+        }
+
+        #[test]
+        fn parses_squares() {
+            let query = PatternQuery::parse("Kg1 pe? .d5").unwrap();
+            assert_eq!(
+                query,
+                PatternQuery(vec![
+                    Term::Square {
+                        content: Content::Piece(Piece::new(PieceColour::White, PieceType::King)),
+                        col: Some(6),
+                        row: Some(0),
+                    },
+                    Term::Square {
+                        content: Content::Piece(Piece::new(PieceColour::Black, PieceType::Pawn)),
+                        col: Some(4),
+                        row: None,
+                    },
+                    Term::Square {
+                        content: Content::Empty,
+                        col: Some(3),
+                        row: Some(4),
+                    },
+                ])
+            );
+        }
+    }
+
+    mod matches_tests {
+        use super::*;
+
+        #[test]
+        fn matches_material_either_way_round() {
+            let query = PatternQuery::parse("R+P vs R").unwrap();
+            assert!(query.matches(&board("8/8/4k3/4p3/8/8/r7/4K2R w - - 0 1")));
+            assert!(query.matches(&board("8/8/4k3/8/4P3/8/r7/4K2R w - - 0 1")));
+            assert!(!query.matches(&board("8/8/4k3/4p3/4P3/8/r7/4K2R w - - 0 1")));
+        }
+
+        #[test]
+        fn matches_material_wildcards() {
+            let query = PatternQuery::parse("Q+* vs K").unwrap();
+            assert!(query.matches(&board("8/8/4k3/8/8/8/8/3QK2R w - - 0 1")));
+            assert!(!query.matches(&board("8/8/4k3/8/8/8/8/4K2R w - - 0 1")));
+        }
+
+        #[test]
+        fn matches_opposite_bishops() {
+            let query = PatternQuery::parse("opposite-bishops").unwrap();
+            assert!(query.matches(&board("4kb2/8/8/8/8/8/8/4KB2 w - - 0 1")));
+            assert!(!query.matches(&board("2b1k3/8/8/8/8/8/8/4KB2 w - - 0 1")));
+        }
+
+        #[test]
+        fn matches_squares_with_wildcards() {
+            let start = board("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1");
+            assert!(PatternQuery::parse("Ke1 p?7 ?d8 .e4")
+                .unwrap()
+                .matches(&start));
+            assert!(!PatternQuery::parse("Ke1 ?e4").unwrap().matches(&start));
+        }
+    }
+
+    mod find_tests {
+        use super::*;
+
+        #[test]
+        fn finds_first_matching_board() {
+            let games = [game_of("1. e4 d5 2. exd5 Qxd5")];
+
+            let occurrences = PatternQuery::parse("Q+2R+2B+2N+7P vs Q+2R+2B+2N+7P")
+                .unwrap()
+                .find(&games);
+            assert_eq!(occurrences, vec![Occurrence::new(0, 4)]);
+        }
+    }
+}
@@ -0,0 +1,240 @@
+use std::collections::HashMap;
+
+use crate::model::{AvailableCastle, Board, Game, Pgn, PieceColour, PieceType, Position};
+
+const SQUARES: usize = 64;
+const PIECE_KINDS: usize = 12;
+const SIDE_TO_MOVE: usize = PIECE_KINDS * SQUARES;
+const CASTLES: usize = SIDE_TO_MOVE + 1;
+const KEY_COUNT: usize = CASTLES + 4;
+
+// Zobrist keys: a random number for each piece on each square, for Black to move and for each
+// castling right. XORing together those that apply gives the same hash for a position however it
+// was reached
+static KEYS: [u64; KEY_COUNT] = keys();
+
+const fn keys() -> [u64; KEY_COUNT] {
+    // SplitMix64, so that hashes are the same from one run to the next
+    let mut keys = [0; KEY_COUNT];
+    let mut state: u64 = 0x9e37_79b9_7f4a_7c15;
+    let mut index = 0;
+    while index < KEY_COUNT {
+        state = state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut key = state;
+        key = (key ^ (key >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        key = (key ^ (key >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        keys[index] = key ^ (key >> 31);
+        index += 1;
+    }
+    keys
+}
+
+// Positions match on the pieces, the side to move and castling rights, but not on en passant or
+// the move clocks
+pub fn hash(board: &Board) -> u64 {
+    let mut hash = board.pieces().fold(0, |hash, (position, piece)| {
+        hash ^ KEYS[piece_index(*piece.colour(), *piece.piece_type()) * SQUARES + square(position)]
+    });
+
+    if *board.active_colour() == PieceColour::Black {
+        hash ^= KEYS[SIDE_TO_MOVE];
+    }
+    for castle in board.available_castles() {
+        hash ^= KEYS[CASTLES + castle_index(*castle)];
+    }
+    hash
+}
+
+// Whether two boards are the same position in the sense hash uses, for telling apart the positions
+// which happen to share a hash
+fn same_position(a: &Board, b: &Board) -> bool {
+    a.active_colour() == b.active_colour()
+        && a.available_castles().len() == b.available_castles().len()
+        && a.available_castles()
+            .iter()
+            .all(|castle| b.available_castles().contains(castle))
+        && a.pieces().count() == b.pieces().count()
+        && a.pieces()
+            .all(|(position, piece)| b.occupant(position) == Some(&piece))
+}
+
+fn piece_index(colour: PieceColour, piece_type: PieceType) -> usize {
+    let offset = match colour {
+        PieceColour::White => 0,
+        PieceColour::Black => 6,
+    };
+    offset
+        + match piece_type {
+            PieceType::Pawn => 0,
+            PieceType::Knight => 1,
+            PieceType::Bishop => 2,
+            PieceType::Rook => 3,
+            PieceType::Queen => 4,
+            PieceType::King => 5,
+        }
+}
+
+fn square(position: Position) -> usize {
+    usize::try_from(position.row() * 8 + position.col()).unwrap_or_default()
+}
+
+fn castle_index(castle: AvailableCastle) -> usize {
+    match castle {
+        AvailableCastle::WhiteKingside => 0,
+        AvailableCastle::WhiteQueenside => 1,
+        AvailableCastle::BlackKingside => 2,
+        AvailableCastle::BlackQueenside => 3,
+    }
+}
+
+// A game reaching a position, with the index of the board in Game::boards
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Occurrence {
+    game: usize,
+    board: usize,
+}
+
+impl Occurrence {
+    pub fn new(game: usize, board: usize) -> Self {
+        Occurrence { game, board }
+    }
+
+    pub fn game(&self) -> usize {
+        self.game
+    }
+
+    pub fn board(&self) -> usize {
+        self.board
+    }
+
+    // e.g. "after 12... Nf6", or "at the start" for the starting position
+    pub fn describe(&self, pgn: &Pgn) -> String {
+        match self
+            .board
+            .checked_sub(1)
+            .and_then(|index| pgn.ply().get(index))
+        {
+            Some(ply) => match ply.movement().colour() {
+                PieceColour::White => format!("after {}. {}", ply.move_number(), ply.movement()),
+                PieceColour::Black => format!("after {}... {}", ply.move_number(), ply.movement()),
+            },
+            None => String::from("at the start"),
+        }
+    }
+}
+
+// Every board of every game by hash, built once when games are loaded
+pub struct PositionIndex(HashMap<u64, Vec<Occurrence>>);
+
+impl PositionIndex {
+    pub fn new(games: &[Game]) -> Self {
+        let mut index: HashMap<u64, Vec<Occurrence>> = HashMap::new();
+        for (game, boards) in games.iter().map(Game::boards).enumerate() {
+            for (board, position) in boards.iter().enumerate() {
+                index
+                    .entry(hash(position))
+                    .or_default()
+                    .push(Occurrence { game, board });
+            }
+        }
+        PositionIndex(index)
+    }
+
+    // In the order the games were loaded, then the order they were reached in. The games must be
+    // those the index was built from
+    pub fn find(&self, games: &[Game], board: &Board) -> Vec<Occurrence> {
+        self.0
+            .get(&hash(board))
+            .map_or(&[][..], Vec::as_slice)
+            .iter()
+            .filter(|occurrence| {
+                games
+                    .get(occurrence.game)
+                    .and_then(|game| game.boards().get(occurrence.board))
+                    .is_some_and(|found| same_position(found, board))
+            })
+            .copied()
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{parse, test_support::game_of};
+
+    use super::*;
+
+    mod hash_tests {
+        use super::*;
+
+        #[test]
+        fn ignores_move_order() {
+            let a = game_of("1. e4 e5 2. Nf3 Nc6");
+            let b = game_of("1. Nf3 Nc6 2. e4 e5");
+            assert_eq!(hash(&a.boards()[4]), hash(&b.boards()[4]));
+        }
+
+        #[test]
+        fn distinguishes_side_to_move() {
+            let a = parse::fen("4k3/8/8/8/8/8/8/4K3 w - - 0 1").unwrap();
+            let b = parse::fen("4k3/8/8/8/8/8/8/4K3 b - - 0 1").unwrap();
+            assert_ne!(hash(&a), hash(&b));
+        }
+
+        #[test]
+        fn distinguishes_castling_rights() {
+            let a = parse::fen("r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1").unwrap();
+            let b = parse::fen("r3k2r/8/8/8/8/8/8/R3K2R w Kkq - 0 1").unwrap();
+            assert_ne!(hash(&a), hash(&b));
+        }
+
+        #[test]
+        fn ignores_clocks_and_en_passant() {
+            let a = parse::fen("4k3/8/8/8/4P3/8/8/4K3 b - e3 0 1").unwrap();
+            let b = parse::fen("4k3/8/8/8/4P3/8/8/4K3 b - - 12 40").unwrap();
+            assert_eq!(hash(&a), hash(&b));
+        }
+    }
+
+    mod index_tests {
+        use super::*;
+
+        #[test]
+        fn finds_transpositions() {
+            let games = [
+                game_of("1. e4 e5 2. Nf3 Nc6"),
+                game_of("1. d4 d5"),
+                game_of("1. Nf3 Nc6 2. e4 e5"),
+            ];
+            let index = PositionIndex::new(&games);
+
+            let occurrences = index.find(&games, &games[0].boards()[4]);
+            assert_eq!(
+                occurrences,
+                vec![
+                    Occurrence { game: 0, board: 4 },
+                    Occurrence { game: 2, board: 4 }
+                ]
+            );
+            assert_eq!(occurrences[1].describe(games[2].pgn()), "after 2... e5");
+        }
+
+        #[test]
+        fn returns_nothing_if_not_found() {
+            let games = [game_of("1. e4 e5")];
+            let board = parse::fen("4k3/8/8/8/8/8/8/4K3 w - - 0 1").unwrap();
+            assert!(PositionIndex::new(&games).find(&games, &board).is_empty());
+        }
+
+        #[test]
+        fn ignores_positions_sharing_a_hash() {
+            let games = [game_of("1. e4 e5")];
+            let board = parse::fen("4k3/8/8/8/8/8/8/4K3 w - - 0 1").unwrap();
+            let mut index = PositionIndex::new(&games);
+            index
+                .0
+                .insert(hash(&board), vec![Occurrence { game: 0, board: 1 }]);
+            assert!(index.find(&games, &board).is_empty());
+        }
+    }
+}
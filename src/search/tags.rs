@@ -0,0 +1,268 @@
+use std::cmp::Ordering;
+
+use nom::{
+    branch::alt,
+    bytes::complete::{is_not, tag, take_while1},
+    character::complete::{char, multispace0, multispace1},
+    combinator::{all_consuming, map, opt},
+    multi::separated_list0,
+    sequence::{delimited, tuple},
+    IResult,
+};
+
+use crate::{model::Pgn, write};
+
+use super::error::SearchError;
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+enum Comparison {
+    Contains,
+    NotContains,
+    Equals,
+    GreaterOrEqual,
+    LessOrEqual,
+    Greater,
+    Less,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum Term {
+    Text(String),
+    Tag {
+        key: String,
+        comparison: Comparison,
+        value: String,
+    },
+}
+
+// A list of terms which must all match: free text is looked for in every tag value, whereas
+// 'Key<op>Value' terms compare against a single tag
+#[derive(Debug, PartialEq, Eq, Default)]
+pub struct TagQuery(Vec<Term>);
+
+impl TagQuery {
+    pub fn parse(input: &str) -> Result<Self, SearchError> {
+        let (_, terms) = all_consuming(delimited(
+            multispace0,
+            separated_list0(multispace1, term),
+            multispace0,
+        ))(input)
+        .map_err(|_| SearchError::new(format!("'{input}' is not a valid search")))?;
+
+        Ok(TagQuery(terms))
+    }
+
+    pub fn matches(&self, pgn: &Pgn) -> bool {
+        self.0.iter().all(|term| match term {
+            Term::Text(text) => {
+                let text = text.to_lowercase();
+                tag_values(pgn).any(|value| value.to_lowercase().contains(&text))
+            }
+            Term::Tag {
+                key,
+                comparison,
+                value,
+            } => match tag_value(pgn, key) {
+                Some(tag_value) => compare(&tag_value, *comparison, value),
+                None => *comparison == Comparison::NotContains,
+            },
+        })
+    }
+}
+
+fn tag_values(pgn: &Pgn) -> impl Iterator<Item = String> + '_ {
+    pgn.tags()
+        .inner()
+        .iter()
+        .map(|(_, value)| value.clone())
+        .chain(std::iter::once(write::result(pgn.result()).to_string()))
+}
+
+fn tag_value(pgn: &Pgn, key: &str) -> Option<String> {
+    if key.eq_ignore_ascii_case("Result") {
+        return Some(write::result(pgn.result()).to_string());
+    }
+
+    pgn.tags()
+        .inner()
+        .iter()
+        .find(|(tag, _)| tag.eq_ignore_ascii_case(key))
+        .map(|(_, value)| value.clone())
+}
+
+fn compare(tag_value: &str, comparison: Comparison, value: &str) -> bool {
+    let tag_lower = tag_value.to_lowercase();
+    let value_lower = value.to_lowercase();
+
+    match comparison {
+        Comparison::Contains => tag_lower.contains(&value_lower),
+        Comparison::NotContains => !tag_lower.contains(&value_lower),
+        Comparison::Equals => tag_lower == value_lower,
+        Comparison::GreaterOrEqual => order(&tag_lower, &value_lower).is_some_and(Ordering::is_ge),
+        Comparison::LessOrEqual => order(&tag_lower, &value_lower).is_some_and(Ordering::is_le),
+        Comparison::Greater => order(&tag_lower, &value_lower).is_some_and(Ordering::is_gt),
+        Comparison::Less => order(&tag_lower, &value_lower).is_some_and(Ordering::is_lt),
+    }
+}
+
+// Numbers are compared by value. Anything else is compared on as many characters as the search
+// value has, so that 'Date<=2023' includes every date in 2023
+fn order(tag_value: &str, value: &str) -> Option<Ordering> {
+    if let (Ok(tag_number), Ok(number)) = (tag_value.parse::<f64>(), value.parse::<f64>()) {
+        return tag_number.partial_cmp(&number);
+    }
+
+    let prefix: String = tag_value.chars().take(value.chars().count()).collect();
+    Some(prefix.as_str().cmp(value))
+}
+
+fn term(input: &str) -> IResult<&str, Term> {
+    let tag_term = map(
+        tuple((key, comparison, value)),
+        |(key, comparison, value)| Term::Tag {
+            key: key.to_string(),
+            comparison,
+            value,
+        },
+    );
+    let text_term = map(alt((quoted, is_not(" \t\r\n\"=<>!"))), |text: &str| {
+        Term::Text(text.to_string())
+    });
+    alt((tag_term, text_term))(input)
+}
+
+fn key(input: &str) -> IResult<&str, &str> {
+    take_while1(|c: char| c.is_alphanumeric() || c == '_')(input)
+}
+
+fn comparison(input: &str) -> IResult<&str, Comparison> {
+    alt((
+        map(tag("=="), |_| Comparison::Equals),
+        map(tag(">="), |_| Comparison::GreaterOrEqual),
+        map(tag("<="), |_| Comparison::LessOrEqual),
+        map(tag("!="), |_| Comparison::NotContains),
+        map(tag("="), |_| Comparison::Contains),
+        map(tag(">"), |_| Comparison::Greater),
+        map(tag("<"), |_| Comparison::Less),
+    ))(input)
+}
+
+fn value(input: &str) -> IResult<&str, String> {
+    map(alt((quoted, is_not(" \t\r\n\""))), ToString::to_string)(input)
+}
+
+fn quoted(input: &str) -> IResult<&str, &str> {
+    map(
+        delimited(char('"'), opt(is_not("\"")), char('"')),
+        Option::unwrap_or_default,
+    )(input)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::model::{Board, Fen, GameResult, PieceColour, Tags};
+
+    use super::*;
+
+    mod parse_tests {
+        use super::*;
+
+        #[test]
+        fn parses_empty_query() {
+            assert_eq!(TagQuery::parse("  "), Ok(TagQuery::default()))
+        }
+
+        #[test]
+        fn returns_err_if_value_missing() {
+            assert!(TagQuery::parse("White=").is_err())
+        }
+
+        #[test]
+        fn parses_terms() {
+            let query = TagQuery::parse(r#"carlsen Date>=2023 Event="Tata Steel""#).unwrap();
+            assert_eq!(
+                query,
+                TagQuery(vec![
+                    Term::Text("carlsen".to_string()),
+                    Term::Tag {
+                        key: "Date".to_string(),
+                        comparison: Comparison::GreaterOrEqual,
+                        value: "2023".to_string()
+                    },
+                    Term::Tag {
+                        key: "Event".to_string(),
+                        comparison: Comparison::Contains,
+                        value: "Tata Steel".to_string()
+                    },
+                ])
+            )
+        }
+    }
+
+    mod matches_tests {
+        use super::*;
+
+        #[test]
+        fn matches_everything_if_empty() {
+            assert!(TagQuery::default().matches(&pgn()))
+        }
+
+        #[test]
+        fn matches_text_in_any_tag() {
+            assert!(TagQuery::parse("wijk").unwrap().matches(&pgn()));
+            assert!(!TagQuery::parse("nakamura").unwrap().matches(&pgn()));
+        }
+
+        #[test]
+        fn matches_tag_case_insensitively() {
+            assert!(TagQuery::parse("white=CARLSEN").unwrap().matches(&pgn()));
+            assert!(!TagQuery::parse("Black=Carlsen").unwrap().matches(&pgn()));
+        }
+
+        #[test]
+        fn matches_result() {
+            assert!(TagQuery::parse("Result=1-0").unwrap().matches(&pgn()));
+            assert!(!TagQuery::parse("Result=0-1").unwrap().matches(&pgn()));
+        }
+
+        #[test]
+        fn compares_dates_by_prefix() {
+            assert!(TagQuery::parse("Date>=2023").unwrap().matches(&pgn()));
+            assert!(TagQuery::parse("Date<=2023").unwrap().matches(&pgn()));
+            assert!(!TagQuery::parse("Date>2023").unwrap().matches(&pgn()));
+            assert!(TagQuery::parse("Date<2023.02").unwrap().matches(&pgn()));
+        }
+
+        #[test]
+        fn compares_numbers_by_value() {
+            assert!(TagQuery::parse("WhiteElo>=850").unwrap().matches(&pgn()));
+            assert!(!TagQuery::parse("WhiteElo<850").unwrap().matches(&pgn()));
+        }
+
+        #[test]
+        fn requires_all_terms_to_match() {
+            assert!(!TagQuery::parse("Carlsen Result=0-1")
+                .unwrap()
+                .matches(&pgn()));
+        }
+
+        #[test]
+        fn matches_missing_tag_only_if_negated() {
+            assert!(TagQuery::parse("ECO!=B12").unwrap().matches(&pgn()));
+            assert!(!TagQuery::parse("ECO=B12").unwrap().matches(&pgn()));
+        }
+
+        fn pgn() -> Pgn {
+            let tags = vec![
+                ("White".to_string(), "Carlsen, Magnus".to_string()),
+                ("Black".to_string(), "Giri, Anish".to_string()),
+                ("Event".to_string(), "Tata Steel".to_string()),
+                ("Site".to_string(), "Wijk aan Zee".to_string()),
+                ("Date".to_string(), "2023.01.15".to_string()),
+                ("WhiteElo".to_string(), "2859".to_string()),
+            ];
+            let fen = Fen::new(Board::builder().build(), PieceColour::White, 1);
+            Pgn::new(Tags::new(tags), fen, GameResult::WhiteWin, Vec::new())
+        }
+    }
+}
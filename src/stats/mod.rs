@@ -0,0 +1,3 @@
+mod player;
+
+pub use player::{players, PlayerStats};
@@ -0,0 +1,371 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt::Display,
+};
+
+use crate::model::{Game, GameResult, PieceColour, Tags};
+
+// Opponents are grouped into bands this many points wide
+const BAND_WIDTH: u32 = 200;
+const OPENINGS_SHOWN: usize = 5;
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+enum Outcome {
+    Win,
+    Draw,
+    Loss,
+}
+
+impl Outcome {
+    // None for a game still in progress
+    fn of(result: GameResult, colour: PieceColour) -> Option<Outcome> {
+        match (result, colour) {
+            (GameResult::WhiteWin, PieceColour::White)
+            | (GameResult::BlackWin, PieceColour::Black) => Some(Outcome::Win),
+            (GameResult::WhiteWin, PieceColour::Black)
+            | (GameResult::BlackWin, PieceColour::White) => Some(Outcome::Loss),
+            (GameResult::Draw, _) => Some(Outcome::Draw),
+            (GameResult::Ongoing, _) => None,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub struct Score {
+    wins: usize,
+    draws: usize,
+    losses: usize,
+}
+
+impl Score {
+    fn add(&mut self, outcome: Outcome) {
+        match outcome {
+            Outcome::Win => self.wins += 1,
+            Outcome::Draw => self.draws += 1,
+            Outcome::Loss => self.losses += 1,
+        }
+    }
+
+    pub fn games(&self) -> usize {
+        self.wins + self.draws + self.losses
+    }
+
+    pub fn points(&self) -> f64 {
+        self.wins as f64 + self.draws as f64 / 2.0
+    }
+}
+
+// e.g. "+3 =2 -1, 4/6"
+impl Display for Score {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "+{} ={} -{}, {}/{}",
+            self.wins,
+            self.draws,
+            self.losses,
+            self.points(),
+            self.games()
+        )
+    }
+}
+
+// Everything known about one player from the games they appear in
+#[derive(Debug, PartialEq, Clone)]
+pub struct PlayerStats {
+    name: String,
+    games: usize,
+    moves: usize,
+    as_white: Score,
+    as_black: Score,
+    // Finished games against a rated opponent, for the performance rating
+    rated: Score,
+    opponent_ratings: u64,
+    openings: HashMap<String, usize>,
+    by_band: BTreeMap<u32, Score>,
+    unrated: Score,
+}
+
+impl PlayerStats {
+    fn new(name: &str) -> Self {
+        PlayerStats {
+            name: name.to_string(),
+            games: 0,
+            moves: 0,
+            as_white: Score::default(),
+            as_black: Score::default(),
+            rated: Score::default(),
+            opponent_ratings: 0,
+            openings: HashMap::new(),
+            by_band: BTreeMap::new(),
+            unrated: Score::default(),
+        }
+    }
+
+    fn add(&mut self, game: &Game, colour: PieceColour) {
+        let pgn = game.pgn();
+        self.games += 1;
+        self.moves += pgn.ply().len().div_ceil(2);
+        if let Some(opening) = opening(pgn.tags()) {
+            *self.openings.entry(opening).or_default() += 1;
+        }
+
+        let Some(outcome) = Outcome::of(pgn.result(), colour) else {
+            return;
+        };
+        let (score, opponent_elo) = match colour {
+            PieceColour::White => (&mut self.as_white, "BlackElo"),
+            PieceColour::Black => (&mut self.as_black, "WhiteElo"),
+        };
+        score.add(outcome);
+
+        match rating(pgn.tags(), opponent_elo) {
+            Some(opponent) => {
+                self.rated.add(outcome);
+                self.opponent_ratings += u64::from(opponent);
+                self.by_band
+                    .entry(opponent / BAND_WIDTH * BAND_WIDTH)
+                    .or_default()
+                    .add(outcome);
+            }
+            None => self.unrated.add(outcome),
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn games(&self) -> usize {
+        self.games
+    }
+
+    pub fn as_white(&self) -> Score {
+        self.as_white
+    }
+
+    pub fn as_black(&self) -> Score {
+        self.as_black
+    }
+
+    // In moves rather than plies
+    pub fn average_length(&self) -> f64 {
+        self.moves as f64 / self.games as f64
+    }
+
+    // The average opponent's rating plus 400 points for each win more than losses, per game
+    pub fn performance(&self) -> Option<i64> {
+        let games = self.rated.games();
+        if games == 0 {
+            return None;
+        }
+        let games = games as f64;
+        let average = self.opponent_ratings as f64 / games;
+        let margin = self.rated.wins as f64 - self.rated.losses as f64;
+        Some((average + 400.0 * margin / games).round() as i64)
+    }
+
+    // Most played first, then alphabetically
+    pub fn openings(&self) -> Vec<(&str, usize)> {
+        let mut openings: Vec<(&str, usize)> = self
+            .openings
+            .iter()
+            .map(|(opening, &count)| (opening.as_str(), count))
+            .collect();
+        openings.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(b.0)));
+        openings
+    }
+
+    // The lowest rating in each band with the score against it, lowest band first
+    pub fn by_band(&self) -> impl Iterator<Item = (u32, Score)> + '_ {
+        self.by_band.iter().map(|(&band, &score)| (band, score))
+    }
+
+    pub fn unrated(&self) -> Score {
+        self.unrated
+    }
+
+    // The report for the player, one line at a time
+    pub fn lines(&self) -> Vec<String> {
+        let mut lines = vec![
+            self.name.clone(),
+            format!(
+                "  Games: {}, averaging {:.0} moves",
+                self.games,
+                self.average_length()
+            ),
+            format!("  As White: {}", self.as_white),
+            format!("  As Black: {}", self.as_black),
+        ];
+        if let Some(performance) = self.performance() {
+            lines.push(format!("  Performance: {performance}"));
+        }
+
+        let openings = self.openings();
+        if !openings.is_empty() {
+            lines.push(String::from("  Openings:"));
+            lines.extend(
+                openings
+                    .iter()
+                    .take(OPENINGS_SHOWN)
+                    .map(|(opening, count)| format!("    {opening}: {count}")),
+            );
+        }
+
+        if self.by_band.is_empty() && self.unrated.games() == 0 {
+            return lines;
+        }
+        lines.push(String::from("  By opponent rating:"));
+        lines.extend(
+            self.by_band()
+                .map(|(band, score)| format!("    {band}-{}: {score}", band + BAND_WIDTH - 1)),
+        );
+        if self.unrated.games() > 0 {
+            lines.push(format!("    Unrated: {}", self.unrated));
+        }
+        lines
+    }
+}
+
+impl Display for PlayerStats {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.lines().join("\n"))
+    }
+}
+
+// Every named player in the games, those with the most games first
+pub fn players(games: &[Game]) -> Vec<PlayerStats> {
+    let mut players: HashMap<String, PlayerStats> = HashMap::new();
+    for game in games {
+        for (tag, colour) in [("White", PieceColour::White), ("Black", PieceColour::Black)] {
+            let Some(name) = game
+                .pgn()
+                .tags()
+                .get(tag)
+                .map(|name| name.trim())
+                .filter(|name| !name.is_empty() && *name != "?")
+            else {
+                continue;
+            };
+            players
+                .entry(name.to_string())
+                .or_insert_with(|| PlayerStats::new(name))
+                .add(game, colour);
+        }
+    }
+
+    let mut players: Vec<PlayerStats> = players.into_values().collect();
+    players.sort_by(|a, b| b.games.cmp(&a.games).then(a.name.cmp(&b.name)));
+    players
+}
+
+fn rating(tags: &Tags, tag: &str) -> Option<u32> {
+    tags.get(tag)
+        .and_then(|rating| rating.trim().parse().ok())
+        .filter(|&rating| rating > 0)
+}
+
+// Openings are told apart by their ECO code where there is one, as names are written many ways
+fn opening(tags: &Tags) -> Option<String> {
+    ["ECO", "Opening"].iter().find_map(|&tag| {
+        tags.get(tag)
+            .map(|value| value.trim())
+            .filter(|value| !value.is_empty() && *value != "?")
+            .map(ToString::to_string)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_support;
+
+    use super::*;
+
+    fn game(tags: &[(&str, &str)], result: &str) -> Game {
+        let tags: Vec<String> = tags
+            .iter()
+            .map(|(key, value)| format!("[{key} \"{value}\"]"))
+            .collect();
+        test_support::game(&format!(
+            "{}\n[Result \"{result}\"]\n\n1. e4 e5 2. Nf3 {result}\n",
+            tags.join("\n")
+        ))
+    }
+
+    fn games() -> Vec<Game> {
+        vec![
+            game(
+                &[
+                    ("White", "Carlsen"),
+                    ("Black", "Nakamura"),
+                    ("BlackElo", "2750"),
+                    ("ECO", "C65"),
+                ],
+                "1-0",
+            ),
+            game(
+                &[
+                    ("White", "Nakamura"),
+                    ("Black", "Carlsen"),
+                    ("WhiteElo", "2790"),
+                    ("ECO", "C65"),
+                    ("Opening", "Ruy Lopez"),
+                ],
+                "1/2-1/2",
+            ),
+            game(&[("White", "Carlsen"), ("Black", "?")], "0-1"),
+            game(&[("White", "Anand"), ("Black", "Carlsen")], "*"),
+        ]
+    }
+
+    fn carlsen() -> PlayerStats {
+        players(&games())
+            .into_iter()
+            .find(|player| player.name() == "Carlsen")
+            .unwrap()
+    }
+
+    #[test]
+    fn lists_players_by_games() {
+        let names: Vec<String> = players(&games())
+            .iter()
+            .map(|player| player.name().to_string())
+            .collect();
+        assert_eq!(names, vec!["Carlsen", "Nakamura", "Anand"]);
+    }
+
+    #[test]
+    fn scores_each_colour() {
+        let carlsen = carlsen();
+        assert_eq!(carlsen.games(), 4);
+        assert_eq!(carlsen.as_white().to_string(), "+1 =0 -1, 1/2");
+        assert_eq!(carlsen.as_black().to_string(), "+0 =1 -0, 0.5/1");
+        assert_eq!(carlsen.average_length(), 2.0);
+    }
+
+    #[test]
+    fn calculates_performance() {
+        // Opponents averaging 2770, with one win and one draw
+        assert_eq!(carlsen().performance(), Some(2970));
+        assert_eq!(
+            players(&[game(&[("White", "Anand")], "1-0")])[0].performance(),
+            None
+        );
+    }
+
+    #[test]
+    fn counts_openings() {
+        assert_eq!(carlsen().openings(), vec![("C65", 2)]);
+    }
+
+    #[test]
+    fn groups_opponents_by_rating() {
+        let carlsen = carlsen();
+        let bands: Vec<(u32, String)> = carlsen
+            .by_band()
+            .map(|(band, score)| (band, score.to_string()))
+            .collect();
+        assert_eq!(bands, vec![(2600, String::from("+1 =1 -0, 1.5/2"))]);
+        assert_eq!(carlsen.unrated().to_string(), "+0 =0 -1, 0/1");
+    }
+}
@@ -0,0 +1,14 @@
+// Fixtures shared by the tests of several modules
+use crate::{execute_moves, model::Game, parse};
+
+// The first game in the text, with every board it reaches
+pub fn game(text: &str) -> Game {
+    let pgn = parse::parse(text).unwrap().remove(0);
+    let boards = execute_moves(pgn.fen().starting_board(), pgn.ply()).unwrap();
+    Game::new(pgn, boards)
+}
+
+// A game of just the given moves
+pub fn game_of(movetext: &str) -> Game {
+    game(&format!("[Result \"*\"]\n\n{movetext} *\n"))
+}
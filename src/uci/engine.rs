@@ -0,0 +1,275 @@
+use std::{
+    collections::BTreeMap,
+    io::{BufRead, BufReader, Write},
+    process::{Child, ChildStdin, Command, Stdio},
+    sync::mpsc::{self, Receiver},
+    thread,
+    time::{Duration, Instant},
+};
+
+use super::{error::UciError, info::Info};
+
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Limit {
+    Depth(u32),
+    Time(Duration),
+}
+
+// A UCI engine running as a child process. Its output is read on a separate thread so that it can
+// be polled without blocking
+pub struct Engine {
+    name: String,
+    child: Child,
+    stdin: ChildStdin,
+    lines: Receiver<String>,
+    searching: bool,
+    // Searches which have been stopped but whose bestmove hasn't arrived yet, so any info lines
+    // read in the meantime are about an old position
+    stale_searches: usize,
+}
+
+impl Engine {
+    pub fn start(path: &str, options: &BTreeMap<String, String>) -> Result<Self, UciError> {
+        let mut child = Command::new(path)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()
+            .map_err(|e| UciError::new(format!("Failed to start engine '{path}': {e}")))?;
+
+        let stdin = child
+            .stdin
+            .take()
+            .ok_or_else(|| UciError::new("Failed to open engine input"))?;
+        let stdout = child
+            .stdout
+            .take()
+            .ok_or_else(|| UciError::new("Failed to open engine output"))?;
+
+        let (sender, lines) = mpsc::channel();
+        thread::spawn(move || {
+            for line in BufReader::new(stdout).lines().map_while(Result::ok) {
+                if sender.send(line).is_err() {
+                    break;
+                }
+            }
+        });
+
+        let mut engine = Engine {
+            name: path.to_string(),
+            child,
+            stdin,
+            lines,
+            searching: false,
+            stale_searches: 0,
+        };
+
+        engine.send("uci")?;
+        for line in engine.wait_for("uciok")? {
+            if let Some(name) = line.strip_prefix("id name ") {
+                engine.name = name.trim().to_string();
+            }
+        }
+        for (name, value) in options {
+            engine.send(&format!("setoption name {name} value {value}"))?;
+        }
+        engine.send("isready")?;
+        engine.wait_for("readyok")?;
+
+        Ok(engine)
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn searching(&self) -> bool {
+        self.searching
+    }
+
+    // Starts searching the position until stopped, with progress read through poll
+    pub fn go_infinite(&mut self, fen: &str) -> Result<(), UciError> {
+        self.stop()?;
+        self.send(&format!("position fen {fen}"))?;
+        self.send("go infinite")?;
+        self.searching = true;
+        Ok(())
+    }
+
+    pub fn stop(&mut self) -> Result<(), UciError> {
+        if self.searching {
+            self.send("stop")?;
+            self.stale_searches += 1;
+            self.searching = false;
+        }
+        Ok(())
+    }
+
+    // Reads whatever the engine has written since the last poll, returning the latest main line
+    pub fn poll(&mut self) -> Result<Option<Info>, UciError> {
+        let mut latest = None;
+        loop {
+            match self.lines.try_recv() {
+                Ok(line) => {
+                    if let Some(info) = self.read(&line) {
+                        latest = Some(info);
+                    }
+                }
+                Err(mpsc::TryRecvError::Empty) => return Ok(latest),
+                Err(mpsc::TryRecvError::Disconnected) => {
+                    return Err(UciError::new(format!("Engine '{}' has exited", self.name)))
+                }
+            }
+        }
+    }
+
+    // Searches the position to the given limit, waiting for the engine to finish. Returns the
+    // last main line reported along with the best move
+    pub fn search(&mut self, fen: &str, limit: Limit) -> Result<(Info, String), UciError> {
+        self.stop()?;
+        self.send(&format!("position fen {fen}"))?;
+        let timeout = match limit {
+            Limit::Depth(depth) => {
+                self.send(&format!("go depth {depth}"))?;
+                None
+            }
+            Limit::Time(time) => {
+                self.send(&format!("go movetime {}", time.as_millis()))?;
+                Some(time + HANDSHAKE_TIMEOUT)
+            }
+        };
+        self.searching = true;
+
+        let started = Instant::now();
+        let mut latest = Info::default();
+        loop {
+            let line = match timeout {
+                None => self.lines.recv().ok(),
+                Some(timeout) => self
+                    .lines
+                    .recv_timeout(timeout.saturating_sub(started.elapsed()))
+                    .ok(),
+            }
+            .ok_or_else(|| UciError::new(format!("Engine '{}' stopped responding", self.name)))?;
+
+            match line.strip_prefix("bestmove") {
+                Some(_) if self.stale_searches > 0 => self.stale_searches -= 1,
+                Some(best_move) => {
+                    self.searching = false;
+                    let best_move = best_move.split_whitespace().next().unwrap_or_default();
+                    return Ok((latest, best_move.to_string()));
+                }
+                None => {
+                    if let Some(info) = self.read(&line) {
+                        latest = info;
+                    }
+                }
+            }
+        }
+    }
+
+    // Returns the info from a line if it's a main line from the current search
+    fn read(&mut self, line: &str) -> Option<Info> {
+        if line.starts_with("bestmove") {
+            if self.stale_searches > 0 {
+                self.stale_searches -= 1;
+            } else {
+                self.searching = false;
+            }
+            return None;
+        }
+
+        if self.stale_searches > 0 {
+            return None;
+        }
+        Info::parse(line).filter(Info::is_main_line)
+    }
+
+    fn send(&mut self, command: &str) -> Result<(), UciError> {
+        writeln!(self.stdin, "{command}")
+            .and_then(|()| self.stdin.flush())
+            .map_err(|e| UciError::new(format!("Failed to write to engine '{}': {e}", self.name)))
+    }
+
+    fn wait_for(&mut self, token: &str) -> Result<Vec<String>, UciError> {
+        let started = Instant::now();
+        let mut lines = Vec::new();
+        loop {
+            let line = self
+                .lines
+                .recv_timeout(HANDSHAKE_TIMEOUT.saturating_sub(started.elapsed()))
+                .map_err(|_| {
+                    UciError::new(format!(
+                        "Engine '{}' did not respond with '{token}'",
+                        self.name
+                    ))
+                })?;
+            if line.trim() == token {
+                return Ok(lines);
+            }
+            lines.push(line);
+        }
+    }
+}
+
+impl Drop for Engine {
+    fn drop(&mut self) {
+        let _ = self.send("quit");
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::uci::Score;
+
+    use super::*;
+
+    const STAND_IN: &str = "./resources/test/uci/stand_in_engine.sh";
+    const START: &str = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1";
+
+    #[test]
+    fn returns_err_if_engine_missing() {
+        assert!(Engine::start("./does-not-exist", &BTreeMap::new()).is_err())
+    }
+
+    #[test]
+    fn reads_engine_name() {
+        let engine = Engine::start(STAND_IN, &BTreeMap::new()).unwrap();
+        assert_eq!(engine.name(), "Stand-in")
+    }
+
+    #[test]
+    fn searches_to_limit() {
+        let mut engine = Engine::start(STAND_IN, &BTreeMap::new()).unwrap();
+        let (info, best_move) = engine.search(START, Limit::Depth(2)).unwrap();
+
+        assert_eq!(best_move, "e2e4");
+        assert_eq!(info.depth(), Some(2));
+        assert_eq!(info.score(), Some(Score::Centipawns(35)));
+        assert!(!engine.searching());
+    }
+
+    #[test]
+    fn streams_infinite_search() {
+        let mut engine = Engine::start(STAND_IN, &BTreeMap::new()).unwrap();
+        engine.go_infinite(START).unwrap();
+
+        let started = Instant::now();
+        let mut latest = None;
+        while latest.as_ref().and_then(Info::depth) != Some(2)
+            && started.elapsed() < HANDSHAKE_TIMEOUT
+        {
+            latest = engine.poll().unwrap().or(latest);
+            thread::sleep(Duration::from_millis(10));
+        }
+        assert_eq!(latest.unwrap().pv(), &["e2e4", "e7e5"]);
+
+        assert!(engine.searching());
+        engine.stop().unwrap();
+        assert!(!engine.searching());
+    }
+}
@@ -0,0 +1,163 @@
+use std::fmt::Display;
+
+use crate::model::PieceColour;
+
+// Keywords in an info line followed by a single value which isn't needed
+const SKIPPED: &[&str] = &[
+    "seldepth",
+    "time",
+    "nodes",
+    "nps",
+    "hashfull",
+    "tbhits",
+    "sbhits",
+    "cpuload",
+    "currmove",
+    "currmovenumber",
+];
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Score {
+    Centipawns(i32),
+    Mate(i32),
+}
+
+impl Score {
+    // Engines score from the point of view of the side to move
+    pub fn for_white(self, side_to_move: PieceColour) -> Score {
+        match (self, side_to_move) {
+            (score, PieceColour::White) => score,
+            (Score::Centipawns(centipawns), PieceColour::Black) => Score::Centipawns(-centipawns),
+            (Score::Mate(moves), PieceColour::Black) => Score::Mate(-moves),
+        }
+    }
+}
+
+impl Display for Score {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Score::Centipawns(centipawns) => write!(f, "{:+.2}", f64::from(*centipawns) / 100.0),
+            Score::Mate(moves) => write!(f, "#{moves}"),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
+pub struct Info {
+    depth: Option<u32>,
+    score: Option<Score>,
+    multipv: Option<u32>,
+    pv: Vec<String>,
+}
+
+impl Info {
+    // Returns None for anything other than an info line, or one only carrying a string
+    pub fn parse(line: &str) -> Option<Info> {
+        let mut tokens = line.split_whitespace();
+        if tokens.next() != Some("info") {
+            return None;
+        }
+
+        let mut info = Info::default();
+        while let Some(token) = tokens.next() {
+            match token {
+                "depth" => info.depth = tokens.next().and_then(|depth| depth.parse().ok()),
+                "multipv" => info.multipv = tokens.next().and_then(|index| index.parse().ok()),
+                "score" => {
+                    let kind = tokens.next();
+                    let value = tokens.next().and_then(|value| value.parse().ok());
+                    info.score = match (kind, value) {
+                        (Some("cp"), Some(value)) => Some(Score::Centipawns(value)),
+                        (Some("mate"), Some(value)) => Some(Score::Mate(value)),
+                        _ => None,
+                    };
+                }
+                "lowerbound" | "upperbound" => {}
+                "pv" => info.pv = tokens.by_ref().map(ToString::to_string).collect(),
+                "string" | "refutation" | "currline" => break,
+                token if SKIPPED.contains(&token) => {
+                    tokens.next();
+                }
+                _ => {}
+            }
+        }
+
+        (info != Info::default()).then_some(info)
+    }
+
+    pub fn depth(&self) -> Option<u32> {
+        self.depth
+    }
+
+    pub fn score(&self) -> Option<Score> {
+        self.score
+    }
+
+    pub fn pv(&self) -> &[String] {
+        &self.pv
+    }
+
+    // With MultiPV set, lines other than the first are alternatives to the best move
+    pub fn is_main_line(&self) -> bool {
+        self.score.is_some() && self.multipv.unwrap_or(1) == 1
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod parse_tests {
+        use super::*;
+
+        #[test]
+        fn returns_none_if_not_info() {
+            assert_eq!(Info::parse("bestmove e2e4 ponder e7e5"), None);
+            assert_eq!(Info::parse("info string NNUE enabled"), None);
+        }
+
+        #[test]
+        fn parses_info() {
+            let info = Info::parse(
+                "info depth 20 seldepth 28 multipv 1 score cp -35 nodes 123 nps 456 time 78 pv e7e5 g1f3",
+            )
+            .unwrap();
+            assert_eq!(info.depth(), Some(20));
+            assert_eq!(info.score(), Some(Score::Centipawns(-35)));
+            assert_eq!(info.pv(), &["e7e5", "g1f3"]);
+            assert!(info.is_main_line());
+        }
+
+        #[test]
+        fn parses_mate() {
+            let info = Info::parse("info depth 5 score mate -3 lowerbound pv h2h3").unwrap();
+            assert_eq!(info.score(), Some(Score::Mate(-3)));
+        }
+
+        #[test]
+        fn recognises_alternative_lines() {
+            let info = Info::parse("info depth 5 multipv 2 score cp 10 pv d2d4").unwrap();
+            assert!(!info.is_main_line());
+        }
+    }
+
+    mod score_tests {
+        use super::*;
+
+        #[test]
+        fn displays_score() {
+            assert_eq!(Score::Centipawns(35).to_string(), "+0.35");
+            assert_eq!(Score::Centipawns(-120).to_string(), "-1.20");
+            assert_eq!(Score::Mate(-2).to_string(), "#-2");
+        }
+
+        #[test]
+        fn converts_to_white_perspective() {
+            assert_eq!(
+                Score::Centipawns(35).for_white(PieceColour::Black),
+                Score::Centipawns(-35)
+            );
+            assert_eq!(Score::Mate(2).for_white(PieceColour::White), Score::Mate(2));
+        }
+    }
+}
@@ -0,0 +1,8 @@
+mod engine;
+mod error;
+mod info;
+mod notation;
+
+pub use engine::{Engine, Limit};
+pub use info::{Info, Score};
+pub use notation::{legal_move, line, long_algebraic};
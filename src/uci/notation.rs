@@ -0,0 +1,105 @@
+use crate::{
+    engine::{self, LegalMove},
+    model::{Board, PieceType, Ply, PlyMovement, Position, COLUMNS, ROWS},
+};
+
+// Finds the legal move written in UCI's long algebraic notation, e.g. "e7e8q"
+pub fn legal_move(board: &Board, uci: &str) -> Option<LegalMove> {
+    let mut chars = uci.chars();
+    let from = square(chars.next()?, chars.next()?)?;
+    let to = square(chars.next()?, chars.next()?)?;
+    let promotes_to = match chars.next() {
+        None => None,
+        Some('q') => Some(PieceType::Queen),
+        Some('r') => Some(PieceType::Rook),
+        Some('b') => Some(PieceType::Bishop),
+        Some('n') => Some(PieceType::Knight),
+        Some(_) => return None,
+    };
+
+    engine::legal_moves(board).into_iter().find(|legal_move| {
+        let promotion = match legal_move.movement() {
+            PlyMovement::Promotion { promotes_to, .. } => Some(*promotes_to),
+            _ => None,
+        };
+        legal_move.from() == from && legal_move.to() == to && promotion == promotes_to
+    })
+}
+
+// Writes the move the way UCI expects, e.g. "e7e8q". Castling is written as the king's move
+pub fn long_algebraic(legal_move: &LegalMove) -> String {
+    let promotion = match legal_move.movement() {
+        PlyMovement::Promotion { promotes_to, .. } => match promotes_to {
+            PieceType::Queen => "q",
+            PieceType::Rook => "r",
+            PieceType::Bishop => "b",
+            PieceType::Knight => "n",
+            PieceType::King | PieceType::Pawn => "",
+        },
+        _ => "",
+    };
+    format!("{}{}{promotion}", legal_move.from(), legal_move.to())
+}
+
+// Converts as much of an engine's principal variation as is legal into ply
+pub fn line(board: &Board, pv: &[String]) -> Vec<Ply> {
+    let mut board = board.clone();
+    let mut ply = Vec::new();
+
+    for uci in pv {
+        let Some(legal_move) = legal_move(&board, uci) else {
+            break;
+        };
+        let move_number = i16::try_from(board.fullmove_clock()).unwrap_or(i16::MAX);
+        board = legal_move.board().clone();
+        ply.push(Ply::new(move_number, legal_move.into_movement(), None));
+    }
+
+    ply
+}
+
+fn square(file: char, rank: char) -> Option<Position> {
+    let col = COLUMNS.find(file)?;
+    let row = ROWS.find(rank)?;
+    Position::try_from(i8::try_from(row).ok()?, i8::try_from(col).ok()?).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::parse;
+
+    use super::*;
+
+    #[test]
+    fn converts_line() {
+        let board = parse::fen("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1").unwrap();
+        let pv = ["e2e4", "e7e5", "g1f3", "e1e8"].map(String::from);
+
+        let ply = line(&board, &pv);
+        assert_eq!(ply.len(), 3);
+        assert_eq!(ply[2].move_number(), 2);
+    }
+
+    #[test]
+    fn finds_promotion() {
+        let board = parse::fen("4k3/P7/8/8/8/8/8/4K3 w - - 0 1").unwrap();
+        let legal_move = legal_move(&board, "a7a8n").unwrap();
+        assert!(matches!(
+            legal_move.movement(),
+            PlyMovement::Promotion {
+                promotes_to: PieceType::Knight,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn writes_long_algebraic() {
+        let board = parse::fen("4k3/P7/8/8/8/8/8/4K2R w K - 0 1").unwrap();
+        assert_eq!(
+            long_algebraic(&legal_move(&board, "a7a8r").unwrap()),
+            "a7a8r"
+        );
+        assert_eq!(long_algebraic(&legal_move(&board, "e1g1").unwrap()), "e1g1");
+    }
+}
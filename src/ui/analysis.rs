@@ -0,0 +1,244 @@
+use crate::{
+    engine::{self, LegalMove},
+    model::{
+        Board, PieceColour, PieceType, Ply, PlyMovement, Position, MAX_POSITION, MIN_POSITION,
+    },
+    parse,
+};
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Direction {
+    Left,
+    Right,
+    Up,
+    Down,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Selection {
+    cursor: Position,
+    selected: Option<Position>,
+}
+
+impl Selection {
+    pub fn cursor(&self) -> Position {
+        self.cursor
+    }
+
+    pub fn selected(&self) -> Option<Position> {
+        self.selected
+    }
+}
+
+// A scratch line branching off from one of a game's boards. Playing a move part way through the
+// line replaces whatever followed it
+pub struct Analysis {
+    game: usize,
+    branch: usize,
+    boards: Vec<Board>,
+    ply: Vec<Ply>,
+    current: usize,
+    selection: Selection,
+}
+
+impl Analysis {
+    pub fn new(game: usize, branch: usize, board: Board) -> Self {
+        let cursor = match board.active_colour() {
+            PieceColour::White => Position::new(MIN_POSITION + 1, 4),
+            PieceColour::Black => Position::new(MAX_POSITION - 1, 4),
+        };
+
+        Analysis {
+            game,
+            branch,
+            boards: vec![board],
+            ply: Vec::new(),
+            current: 0,
+            selection: Selection {
+                cursor,
+                selected: None,
+            },
+        }
+    }
+
+    pub fn game(&self) -> usize {
+        self.game
+    }
+
+    // The index of the game's board the line starts from
+    pub fn branch(&self) -> usize {
+        self.branch
+    }
+
+    pub fn board(&self) -> &Board {
+        &self.boards[self.current]
+    }
+
+    pub fn ply(&self) -> &[Ply] {
+        &self.ply
+    }
+
+    pub fn current(&self) -> usize {
+        self.current
+    }
+
+    // The board before the current one and the move played from it, if any in this line
+    pub fn last_move(&self) -> Option<(&Board, &Ply)> {
+        let previous = self.current.checked_sub(1)?;
+        Some((&self.boards[previous], &self.ply[previous]))
+    }
+
+    pub fn last(&self) -> usize {
+        self.boards.len() - 1
+    }
+
+    pub fn selection(&self) -> &Selection {
+        &self.selection
+    }
+
+    pub fn go_to(&mut self, index: usize) {
+        self.current = index.min(self.last());
+        self.selection.selected = None;
+    }
+
+    pub fn play_san(&mut self, input: &str) -> Result<(), String> {
+        let colour = *self.board().active_colour();
+        let movement = parse::san(input, colour).map_err(|e| e.to_string())?;
+        let legal_move =
+            engine::resolve_move(self.board(), &movement).map_err(|e| format!("{input}: {e}"))?;
+        self.play(legal_move);
+        Ok(())
+    }
+
+    pub fn move_cursor(&mut self, direction: Direction, perspective: PieceColour) {
+        // Directions are as seen on screen, so are reversed when viewing from Black's side
+        let (rows, columns) = match direction {
+            Direction::Left => (0, -1),
+            Direction::Right => (0, 1),
+            Direction::Up => (1, 0),
+            Direction::Down => (-1, 0),
+        };
+        let flip = match perspective {
+            PieceColour::White => 1,
+            PieceColour::Black => -1,
+        };
+        let cursor = self.selection.cursor;
+
+        if let Ok(position) =
+            Position::try_from(cursor.row() + rows * flip, cursor.col() + columns * flip)
+        {
+            self.selection.cursor = position;
+        }
+    }
+
+    // Picks up the piece under the cursor, or moves the picked up piece to the cursor. Pawns
+    // picked this way always promote to a queen
+    pub fn pick(&mut self) -> Result<(), String> {
+        let board = self.board();
+        let cursor = self.selection.cursor;
+        let colour = *board.active_colour();
+        let own_piece = board
+            .occupant(cursor)
+            .is_some_and(|piece| *piece.colour() == colour);
+
+        match self.selection.selected {
+            Some(selected) if selected == cursor => {
+                self.selection.selected = None;
+                Ok(())
+            }
+            _ if own_piece => {
+                self.selection.selected = Some(cursor);
+                Ok(())
+            }
+            Some(selected) => {
+                let legal_move = engine::legal_moves(board)
+                    .into_iter()
+                    .filter(|legal_move| legal_move.from() == selected && legal_move.to() == cursor)
+                    .find(|legal_move| match legal_move.movement() {
+                        PlyMovement::Promotion { promotes_to, .. } => {
+                            *promotes_to == PieceType::Queen
+                        }
+                        _ => true,
+                    })
+                    .ok_or_else(|| format!("{selected}-{cursor} is not a legal move"))?;
+                self.play(legal_move);
+                Ok(())
+            }
+            None => Err(format!("There is no {colour:?} piece on {cursor}")),
+        }
+    }
+
+    fn play(&mut self, legal_move: LegalMove) {
+        let board = self.board();
+        let move_number = i16::try_from(board.fullmove_clock()).unwrap_or(i16::MAX);
+
+        self.boards.truncate(self.current + 1);
+        self.ply.truncate(self.current);
+
+        self.boards.push(legal_move.board().clone());
+        self.ply
+            .push(Ply::new(move_number, legal_move.into_movement(), None));
+        self.current += 1;
+        self.selection.selected = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn analysis() -> Analysis {
+        let board = parse::fen("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1").unwrap();
+        Analysis::new(0, 0, board)
+    }
+
+    #[test]
+    fn plays_san() {
+        let mut analysis = analysis();
+        analysis.play_san("e4").unwrap();
+        analysis.play_san("e5").unwrap();
+        analysis.play_san("Nf3").unwrap();
+
+        assert_eq!(analysis.ply().len(), 3);
+        assert_eq!(analysis.board().active_colour(), &PieceColour::Black);
+    }
+
+    #[test]
+    fn returns_err_if_move_illegal() {
+        let mut analysis = analysis();
+        assert!(analysis.play_san("Ke2").is_err());
+        assert!(analysis.play_san("nonsense").is_err());
+        assert!(analysis.ply().is_empty());
+    }
+
+    #[test]
+    fn replaces_rest_of_line() {
+        let mut analysis = analysis();
+        analysis.play_san("e4").unwrap();
+        analysis.play_san("e5").unwrap();
+        analysis.go_to(1);
+        analysis.play_san("c5").unwrap();
+
+        assert_eq!(analysis.ply().len(), 2);
+        assert_eq!(analysis.current(), 2);
+    }
+
+    #[test]
+    fn picks_pieces_with_cursor() {
+        let mut analysis = analysis();
+        analysis.pick().unwrap();
+        analysis.move_cursor(Direction::Up, PieceColour::White);
+        analysis.move_cursor(Direction::Up, PieceColour::White);
+        analysis.pick().unwrap();
+
+        assert_eq!(analysis.ply().len(), 1);
+        assert!(analysis.board().occupant(Position::new(3, 4)).is_some());
+    }
+
+    #[test]
+    fn moves_cursor_from_perspective() {
+        let mut analysis = analysis();
+        analysis.move_cursor(Direction::Left, PieceColour::Black);
+        assert_eq!(analysis.selection().cursor(), Position::new(1, 5));
+    }
+}
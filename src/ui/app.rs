@@ -1,4 +1,6 @@
+use std::fs;
 use std::io::Stdout;
+use std::time::{Duration, Instant};
 
 use ratatui::layout::{Constraint, Layout, Rect};
 use ratatui::style::{Modifier, Style};
@@ -6,43 +8,106 @@ use ratatui::text::{Line, Span};
 use ratatui::widgets::block::Title;
 use ratatui::{prelude::CrosstermBackend, Frame, Terminal};
 
-use crate::model::{Board, Game, PieceColour};
+use crate::config::{Config, KeyBindings, Theme};
+use crate::model::{Board, Game, PieceColour, Ply};
+use crate::parse;
+use crate::search::{Occurrence, PatternQuery, PositionIndex, SearchError, TagQuery};
+use crate::stats::{players, PlayerStats};
+use crate::write;
 
-use super::{command::Command, error::UiError};
+use super::{
+    analysis::Analysis,
+    command::Command,
+    engine::EnginePanel,
+    error::UiError,
+    explorer::ExplorerPane,
+    games::GameList,
+    prompt::{Prompt, PromptKind},
+};
 
-use super::{board, centre, command, fen, games, ply, tags};
+use super::{
+    autoplay::Autoplay, board, centre, command, engine, explorer, games, ply, prompt, stats, tags,
+};
 use crossterm::{
     execute,
     terminal::{disable_raw_mode, LeaveAlternateScreen},
 };
-use ratatui::widgets::{Block, Borders, Clear, ListState, Padding, Paragraph};
+use ratatui::widgets::{Block, Borders, Clear, Padding, Paragraph};
+
+const PLY_JUMP: usize = 10;
+// How often the engine panel is refreshed while the engine is searching
+const ENGINE_REFRESH: Duration = Duration::from_millis(100);
 
 pub struct App {
     terminal: Terminal<CrosstermBackend<Stdout>>,
+    state: State,
+}
+
+struct State {
     games: Vec<Game>,
     current_game: usize,
     current_ply: Vec<usize>,
     max_ply: Vec<usize>,
     perspective: PieceColour,
-    list_state: ListState,
+    game_list: GameList,
     show_metadata: bool,
+    show_stats: bool,
+    show_help: bool,
     display_fen: bool,
+    theme: Theme,
+    keys: KeyBindings,
+    prompt: Option<Prompt>,
+    message: Option<String>,
+    autoplay: Autoplay,
+    query: String,
+    analysis: Option<Analysis>,
+    engine: Option<EnginePanel>,
+    explorer: ExplorerPane,
+    positions: PositionIndex,
+    players: Vec<PlayerStats>,
+    matches: Vec<Occurrence>,
+    current_match: usize,
 }
 
 impl App {
-    pub fn new(terminal: Terminal<CrosstermBackend<Stdout>>, games: Vec<Game>) -> Self {
+    pub fn new(
+        terminal: Terminal<CrosstermBackend<Stdout>>,
+        games: Vec<Game>,
+        config: Config,
+        engine: Option<EnginePanel>,
+    ) -> Self {
         let current_ply = games.iter().map(|_| 0).collect();
         let max_ply = games.iter().map(|g| g.boards().len() - 1).collect();
+        let game_list = GameList::new(games.len(), config.games().columns());
+        let positions = PositionIndex::new(&games);
+        let players = players(&games);
         App {
             terminal,
-            games,
-            current_game: 0,
-            current_ply,
-            max_ply,
-            perspective: PieceColour::White,
-            list_state: ListState::default().with_selected(Some(0)),
-            show_metadata: false,
-            display_fen: false,
+            state: State {
+                games,
+                current_game: 0,
+                current_ply,
+                max_ply,
+                perspective: PieceColour::White,
+                game_list,
+                show_metadata: false,
+                show_stats: false,
+                show_help: false,
+                display_fen: false,
+                theme: config.theme().clone(),
+                keys: config.keys().clone(),
+                prompt: None,
+                message: None,
+                autoplay: Autoplay::new(config.autoplay(), Instant::now()),
+                query: String::new(),
+                analysis: None,
+                engine,
+                explorer: ExplorerPane::new(),
+                positions,
+                players,
+                matches: Vec::new(),
+                current_match: 0,
+            },
         }
     }
 
@@ -64,81 +129,443 @@ impl App {
 
     fn run(&mut self) -> Result<(), UiError> {
         loop {
-            if let Some(command) = command::read()? {
+            self.state.update_engine();
+            self.state.update_explorer();
+            self.terminal
+                .draw(|frame| render(frame, &mut self.state))
+                .map_err(|e| UiError::new(format!("Failed to draw frame: {e}")))?;
+
+            let prompting = self.state.prompt.is_some();
+            let timeout = match (
+                self.state.autoplay.timeout(Instant::now()),
+                self.state
+                    .engine
+                    .as_ref()
+                    .filter(|engine| engine.searching()),
+            ) {
+                (Some(timeout), Some(_)) => Some(timeout.min(ENGINE_REFRESH)),
+                (None, Some(_)) => Some(ENGINE_REFRESH),
+                (timeout, None) => timeout,
+            };
+            if let Some(command) = command::read(&self.state.keys, prompting, timeout)? {
                 match command {
-                    Command::PlyForwards => {
-                        let current_ply = self.current_ply[self.current_game];
-                        if current_ply < self.max_ply[self.current_game] {
-                            self.current_ply[self.current_game] += 1;
-                        }
-                    }
-                    Command::PlyBackwards => {
-                        let current_ply = self.current_ply[self.current_game];
-                        if current_ply > 0 {
-                            self.current_ply[self.current_game] -= 1;
-                        }
-                    }
-                    Command::GameForwards => {
-                        if self.current_game < self.games.len() - 1 {
-                            self.current_game += 1;
-                            self.list_state.select(Some(self.current_game));
-                        }
-                    }
-                    Command::GameBackwards => {
-                        if self.current_game > 0 {
-                            self.current_game -= 1;
-                            self.list_state.select(Some(self.current_game));
-                        }
-                    }
-                    Command::FlipPerspective => match self.perspective {
-                        PieceColour::White => {
-                            self.perspective = PieceColour::Black;
-                        }
-                        PieceColour::Black => {
-                            self.perspective = PieceColour::White;
+                    Command::Quit => break,
+                    command => self.state.update(command),
+                }
+            }
+
+            let now = Instant::now();
+            if self.state.autoplay.due(now) {
+                self.state.advance();
+                self.state.autoplay.advanced(now);
+            }
+        }
+        Ok(())
+    }
+}
+
+impl State {
+    fn update(&mut self, command: Command) {
+        self.message = None;
+        let current_ply = self.displayed_ply();
+        match command {
+            Command::PlyForwards => self.go_to_ply(current_ply + 1),
+            Command::PlyBackwards => self.go_to_ply(current_ply.saturating_sub(1)),
+            Command::FirstPly => self.go_to_ply(0),
+            Command::LastPly => self.go_to_ply(usize::MAX),
+            Command::JumpForwards => self.go_to_ply(current_ply + PLY_JUMP),
+            Command::JumpBackwards => self.go_to_ply(current_ply.saturating_sub(PLY_JUMP)),
+            Command::GoToMove => {
+                self.prompt = Some(Prompt::new(PromptKind::GoToMove, String::new()));
+            }
+            Command::Search => {
+                self.prompt = Some(Prompt::new(PromptKind::Search, self.query.clone()));
+            }
+            Command::FindPosition => {
+                let board = self.displayed_board().clone();
+                if let Err(message) = self.find_position(&board) {
+                    self.message = Some(message);
+                }
+            }
+            Command::EnterPosition => {
+                self.prompt = Some(Prompt::new(PromptKind::Position, String::new()));
+            }
+            Command::FindPattern => {
+                self.prompt = Some(Prompt::new(PromptKind::Pattern, String::new()));
+            }
+            Command::NextMatch => self.step_match(1),
+            Command::PreviousMatch => self.step_match(self.matches.len().saturating_sub(1)),
+            Command::ToggleAutoplay => self.autoplay.toggle(Instant::now()),
+            Command::AutoplayFaster => self.autoplay.faster(),
+            Command::AutoplaySlower => self.autoplay.slower(),
+            Command::PromptInput(c) => {
+                if let Some(prompt) = self.prompt.as_mut() {
+                    prompt.push(c);
+                }
+                self.search_incrementally();
+            }
+            Command::PromptDelete => {
+                if let Some(prompt) = self.prompt.as_mut() {
+                    prompt.pop();
+                }
+                self.search_incrementally();
+            }
+            Command::PromptSubmit => {
+                if let Some(prompt) = self.prompt.take() {
+                    let result = match prompt.kind() {
+                        PromptKind::GoToMove => {
+                            let ply = match &self.analysis {
+                                Some(analysis) => analysis.ply(),
+                                None => self.games[self.current_game].pgn().ply(),
+                            };
+                            prompt::board_index(ply, prompt.input())
+                                .map(|index| self.go_to_ply(index))
                         }
-                    },
-                    Command::ToggleMetadata => {
-                        self.show_metadata = !self.show_metadata;
-                    }
-                    Command::DisplayFen => {
-                        self.display_fen = !self.display_fen;
+                        PromptKind::Move => self.analyse().play_san(prompt.input()),
+                        PromptKind::Position => parse::fen(prompt.input())
+                            .map_err(|e| e.to_string())
+                            .and_then(|board| self.find_position(&board)),
+                        PromptKind::Pattern => PatternQuery::parse(prompt.input())
+                            .map_err(|e| e.to_string())
+                            .and_then(|query| self.find_pattern(&query)),
+                        PromptKind::Search => match self.filter_games(prompt.input()) {
+                            Ok(()) => {
+                                self.query = prompt.input().to_string();
+                                Ok(())
+                            }
+                            Err(e) => {
+                                let query = self.query.clone();
+                                let _ = self.filter_games(&query);
+                                Err(e.to_string())
+                            }
+                        },
+                    };
+                    if let Err(message) = result {
+                        self.message = Some(message);
                     }
-                    Command::Quit => break,
                 }
             }
+            Command::PromptCancel => {
+                if let Some(PromptKind::Search) = self.prompt.take().map(|prompt| prompt.kind()) {
+                    let query = self.query.clone();
+                    let _ = self.filter_games(&query);
+                }
+            }
+            Command::GameForwards => {
+                if let Some(game) = self.game_list.next(self.current_game, false) {
+                    self.select_game(game);
+                }
+            }
+            Command::GameBackwards => {
+                if let Some(game) = self.game_list.previous(self.current_game) {
+                    self.select_game(game);
+                }
+            }
+            Command::SortGames => {
+                self.game_list.cycle_sort(&self.games);
+                self.select_game(self.current_game);
+            }
+            Command::ReverseSort => {
+                self.game_list.reverse_sort(&self.games);
+                self.select_game(self.current_game);
+            }
+            Command::Analyse => {
+                if self.analysis.take().is_none() {
+                    self.analyse();
+                }
+            }
+            Command::EnterMove => {
+                self.analyse();
+                self.prompt = Some(Prompt::new(PromptKind::Move, String::new()));
+            }
+            Command::MoveCursor(direction) => {
+                let perspective = self.perspective;
+                self.analyse().move_cursor(direction, perspective);
+            }
+            Command::PickSquare => {
+                if let Err(message) = self.analyse().pick() {
+                    self.message = Some(message);
+                }
+            }
+            Command::SaveVariation => {
+                if let Err(message) = self.save_variation() {
+                    self.message = Some(message);
+                }
+            }
+            Command::ToggleEngine => match self.engine.as_mut() {
+                Some(engine) => engine.toggle(),
+                None => {
+                    self.message = Some(String::from(
+                        "No engine configured, set one with --engine or in the config file",
+                    ));
+                }
+            },
+            Command::ToggleExplorer => self.explorer.toggle(),
+            Command::PreviousExplorerMove => self.explorer.step(-1),
+            Command::NextExplorerMove => self.explorer.step(1),
+            Command::PlayExplorerMove => {
+                if let Err(message) = self.play_explorer_move() {
+                    self.message = Some(message);
+                }
+            }
+            Command::FlipPerspective => match self.perspective {
+                PieceColour::White => {
+                    self.perspective = PieceColour::Black;
+                }
+                PieceColour::Black => {
+                    self.perspective = PieceColour::White;
+                }
+            },
+            Command::ToggleMetadata => {
+                self.show_metadata = !self.show_metadata;
+            }
+            Command::ToggleStats => {
+                self.show_stats = !self.show_stats;
+            }
+            Command::ToggleHelp => {
+                self.show_help = !self.show_help;
+            }
+            Command::DisplayFen => {
+                self.display_fen = !self.display_fen;
+            }
+            Command::SaveDiagram => {
+                self.message = Some(match self.save_diagram() {
+                    Ok(file_name) => format!("Diagram written to {file_name}"),
+                    Err(message) => message,
+                });
+            }
+            Command::Quit => {}
+        }
+    }
 
-            let current_ply = self.current_ply[self.current_game];
+    // Steps autoplay forward, moving on to the next game at the end of this one if configured to
+    fn advance(&mut self) {
+        if self.analysis.is_some() {
+            self.autoplay.pause();
+            return;
+        }
 
-            self.terminal
-                .draw(|frame| {
-                    render(
-                        frame,
-                        self.current_game,
-                        current_ply,
-                        self.perspective,
-                        &self.games,
-                        &mut self.list_state,
-                        self.show_metadata,
-                        self.display_fen,
-                    );
-                })
-                .map_err(|e| UiError::new(format!("Failed to draw frame: {e}")))?;
+        let current_ply = self.current_ply[self.current_game];
+        if current_ply < self.max_ply[self.current_game] {
+            self.go_to_ply(current_ply + 1);
+        } else if let Some(game) = self
+            .game_list
+            .next(self.current_game, true)
+            .filter(|_| self.autoplay.next_game())
+        {
+            self.select_game(game);
+            self.go_to_ply(0);
+        } else {
+            self.autoplay.pause();
+        }
+    }
+
+    // Searching is incremental, but a half-typed query which doesn't parse yet or matches nothing is
+    // ignored
+    fn search_incrementally(&mut self) {
+        if let Some(prompt) = self
+            .prompt
+            .as_ref()
+            .filter(|p| p.kind() == PromptKind::Search)
+        {
+            let input = prompt.input().to_string();
+            let _ = self.filter_games(&input);
+        }
+    }
+
+    // A query matching no games leaves the list as it was, rather than hiding the game on screen
+    fn filter_games(&mut self, input: &str) -> Result<(), SearchError> {
+        let query = TagQuery::parse(input)?;
+        let visible_games: Vec<usize> = (0..self.games.len())
+            .filter(|&game| query.matches(self.games[game].pgn()))
+            .collect();
+        if visible_games.is_empty() {
+            return Err(SearchError::new("No games match this search"));
+        }
+        self.game_list.filter(&self.games, visible_games);
+
+        match self.game_list.first() {
+            Some(first) if !self.game_list.contains(self.current_game) => {
+                self.select_game(first);
+            }
+            _ => self.select_game(self.current_game),
         }
         Ok(())
     }
+
+    fn select_game(&mut self, game: usize) {
+        if game != self.current_game {
+            self.analysis = None;
+        }
+        self.current_game = game;
+        self.game_list.select(game);
+    }
+
+    // Starts analysing from the board currently shown, unless already analysing
+    fn analyse(&mut self) -> &mut Analysis {
+        self.autoplay.pause();
+        let game = self.current_game;
+        let branch = self.current_ply[game];
+        let board = &self.games[game].boards()[branch];
+        self.analysis
+            .get_or_insert_with(|| Analysis::new(game, branch, board.clone()))
+    }
+
+    // Lines can only be saved in place of an existing move, so not from the final position
+    fn save_variation(&mut self) -> Result<(), String> {
+        let analysis = self
+            .analysis
+            .take_if(|analysis| !analysis.ply().is_empty())
+            .ok_or("Play some moves before saving them as a variation")?;
+
+        let game = &mut self.games[analysis.game()];
+        let ply = game
+            .pgn_mut()
+            .ply_mut()
+            .get_mut(analysis.branch())
+            .ok_or("Variations can't be saved after the final move")?;
+        ply.add_variation(analysis.ply().to_vec());
+
+        self.current_ply[analysis.game()] = analysis.branch();
+        Ok(())
+    }
+
+    // Lists every game reaching the board, showing the first of them
+    fn find_position(&mut self, board: &Board) -> Result<(), String> {
+        let matches = self.positions.find(&self.games, board);
+        if matches.is_empty() {
+            return Err(String::from("No games reach this position"));
+        }
+        self.matches = matches;
+        self.show_match(0);
+        Ok(())
+    }
+
+    // Lists the first board of each game the pattern holds on, showing the first of them
+    fn find_pattern(&mut self, query: &PatternQuery) -> Result<(), String> {
+        let matches = query.find(&self.games);
+        if matches.is_empty() {
+            return Err(String::from("No games match this pattern"));
+        }
+        self.matches = matches;
+        self.show_match(0);
+        Ok(())
+    }
+
+    // Steps through the matches, wrapping around at either end
+    fn step_match(&mut self, step: usize) {
+        if self.matches.is_empty() {
+            self.message = Some(String::from("Find a position to step through its matches"));
+            return;
+        }
+        self.show_match((self.current_match + step) % self.matches.len());
+    }
+
+    fn show_match(&mut self, index: usize) {
+        let occurrence = self.matches[index];
+        self.current_match = index;
+        self.analysis = None;
+        self.select_game(occurrence.game());
+        self.current_ply[occurrence.game()] = occurrence.board();
+        self.message = Some(format!(
+            "Match {} of {}: game {}, {}",
+            index + 1,
+            self.matches.len(),
+            occurrence.game() + 1,
+            occurrence.describe(self.games[occurrence.game()].pgn())
+        ));
+    }
+
+    fn displayed_board(&self) -> &Board {
+        match &self.analysis {
+            Some(analysis) => analysis.board(),
+            None => &self.games[self.current_game].boards()[self.current_ply[self.current_game]],
+        }
+    }
+
+    fn update_engine(&mut self) {
+        let Some(engine) = self.engine.as_mut() else {
+            return;
+        };
+        // displayed_board would borrow all of self, so the fields are borrowed individually here
+        let board = match &self.analysis {
+            Some(analysis) => analysis.board(),
+            None => &self.games[self.current_game].boards()[self.current_ply[self.current_game]],
+        };
+        engine.update(board);
+        engine.poll();
+    }
+
+    fn update_explorer(&mut self) {
+        let board = match &self.analysis {
+            Some(analysis) => analysis.board(),
+            None => &self.games[self.current_game].boards()[self.current_ply[self.current_game]],
+        };
+        self.explorer.update(&self.games, &self.positions, board);
+    }
+
+    // Walks the tree by playing the selected move in an analysis line
+    fn play_explorer_move(&mut self) -> Result<(), String> {
+        let san = self
+            .explorer
+            .selected()
+            .filter(|_| self.explorer.visible())
+            .map(|stats| stats.movement().to_string())
+            .ok_or("Open the explorer and select a move to play it")?;
+        self.analyse().play_san(&san)
+    }
+
+    // The board before the displayed one and the move played from it, for highlighting the move
+    fn displayed_move(&self) -> Option<(&Board, &Ply)> {
+        let game = &self.games[self.current_game];
+        let index = match &self.analysis {
+            Some(analysis) if analysis.current() > 0 => return analysis.last_move(),
+            Some(analysis) => analysis.branch(),
+            None => self.current_ply[self.current_game],
+        };
+        let previous = index.checked_sub(1)?;
+        Some((&game.boards()[previous], &game.pgn().ply()[previous]))
+    }
+
+    // Writes the displayed board to an SVG file in the working directory, returning its name
+    fn save_diagram(&self) -> Result<String, String> {
+        let file_name = match &self.analysis {
+            Some(analysis) => format!(
+                "game{}-ply{}-analysis.svg",
+                self.current_game + 1,
+                analysis.branch() + analysis.current()
+            ),
+            None => format!(
+                "game{}-ply{}.svg",
+                self.current_game + 1,
+                self.current_ply[self.current_game]
+            ),
+        };
+        let highlights = write::Highlights::new(self.displayed_move());
+        let svg = write::svg(self.displayed_board(), self.perspective, &highlights);
+        fs::write(&file_name, svg)
+            .map_err(|e| format!("Failed to write diagram to {file_name}: {e}"))?;
+        Ok(file_name)
+    }
+
+    fn displayed_ply(&self) -> usize {
+        match &self.analysis {
+            Some(analysis) => analysis.current(),
+            None => self.current_ply[self.current_game],
+        }
+    }
+
+    fn go_to_ply(&mut self, ply: usize) {
+        match self.analysis.as_mut() {
+            Some(analysis) => analysis.go_to(ply),
+            None => {
+                self.current_ply[self.current_game] = ply.min(self.max_ply[self.current_game]);
+            }
+        }
+    }
 }
 
-fn render(
-    frame: &mut Frame,
-    current_game: usize,
-    current_ply: usize,
-    perspective: PieceColour,
-    games: &[Game],
-    list_state: &mut ListState,
-    show_metadata: bool,
-    display_fen: bool,
-) {
+fn render(frame: &mut Frame, state: &mut State) {
     let regions = Layout::default()
         .direction(ratatui::layout::Direction::Vertical)
         .constraints(vec![
@@ -160,31 +587,120 @@ fn render(
 
     let bottom_region = ui_regions[1];
 
-    title(frame, regions[0]);
+    let (board_region, engine_region) = match &state.engine {
+        Some(_) => {
+            let regions = Layout::default()
+                .direction(ratatui::layout::Direction::Vertical)
+                .constraints(vec![Constraint::Min(0), Constraint::Length(5)])
+                .split(top_region[1]);
+            (regions[0], Some(regions[1]))
+        }
+        None => (top_region[1], None),
+    };
 
-    let pgn = games[current_game].pgn();
+    title(frame, &state.autoplay, regions[0]);
 
-    ply::render(frame, pgn.ply(), current_ply, pgn.result(), top_region[0]);
+    let game = &state.games[state.current_game];
+    let pgn = game.pgn();
 
-    let current_board = &games[current_game].boards()[current_ply];
-    if display_fen {
+    let current_board = match &state.analysis {
+        Some(analysis) => {
+            let branch = &game.boards()[analysis.branch()];
+            let move_number = match branch.active_colour() {
+                PieceColour::White => format!("{}.", branch.fullmove_clock()),
+                PieceColour::Black => format!("{}...", branch.fullmove_clock()),
+            };
+            ply::render(
+                frame,
+                analysis.ply(),
+                analysis.current(),
+                None,
+                Some(format!(" Analysis from {move_number} ")),
+                state.theme.moves(),
+                top_region[0],
+            );
+            analysis.board()
+        }
+        None => {
+            let current_ply = state.current_ply[state.current_game];
+            ply::render(
+                frame,
+                pgn.ply(),
+                current_ply,
+                Some(pgn.result()),
+                None,
+                state.theme.moves(),
+                top_region[0],
+            );
+            &game.boards()[current_ply]
+        }
+    };
+
+    if state.display_fen {
         fen_string(frame, regions[1], current_board);
     }
-    board::render(frame, current_board, perspective, top_region[1]);
+    board::render(
+        frame,
+        current_board,
+        state.perspective,
+        &state.theme,
+        state.analysis.as_ref().map(Analysis::selection),
+        board_region,
+    );
+    if let (Some(panel), Some(area)) = (&state.engine, engine_region) {
+        engine::render(frame, panel, area);
+    }
 
-    games::render(frame, games, bottom_region, list_state, show_metadata);
+    let (games_region, explorer_region) = if state.explorer.visible() {
+        let regions = Layout::default()
+            .direction(ratatui::layout::Direction::Horizontal)
+            .constraints(vec![Constraint::Percentage(60), Constraint::Percentage(40)])
+            .split(bottom_region);
+        (regions[0], Some(regions[1]))
+    } else {
+        (bottom_region, None)
+    };
 
-    if show_metadata {
+    games::render(
+        frame,
+        &state.games,
+        &mut state.game_list,
+        games_region,
+        state.show_metadata,
+        state.theme.pieces(),
+    );
+
+    if let Some(area) = explorer_region {
+        explorer::render(frame, &mut state.explorer, area);
+    }
+
+    if state.show_metadata {
         tags::render(frame, pgn.tags(), pgn.result(), bottom_region);
     }
 
-    command::render(frame, regions[2]);
+    if state.show_stats {
+        stats::render(frame, &state.players, pgn.tags(), regions[1]);
+    }
+
+    if state.show_help {
+        command::render_help(frame, &state.keys, state.analysis.is_some(), regions[1]);
+    }
+
+    match (&state.prompt, &state.message) {
+        (Some(input), _) => prompt::render(frame, input, regions[2]),
+        (None, Some(message)) => prompt::render_message(frame, message, regions[2]),
+        (None, None) => command::render(frame, &state.keys, state.analysis.is_some(), regions[2]),
+    }
 }
 
-fn title(frame: &mut Frame, area: Rect) {
+fn title(frame: &mut Frame, autoplay: &Autoplay, area: Rect) {
     let title: Vec<Span> = [
         Span::styled("pigin", Style::default().add_modifier(Modifier::ITALIC)),
         Span::from(" "),
+        Span::styled(
+            autoplay.label(),
+            Style::default().add_modifier(Modifier::DIM),
+        ),
     ]
     .into_iter()
     .collect();
@@ -198,7 +714,7 @@ fn title(frame: &mut Frame, area: Rect) {
 }
 
 fn fen_string(frame: &mut Frame, area: Rect, board: &Board) {
-    let fen_string = fen::from_board(board)
+    let fen_string = write::fen(board)
         .unwrap_or_else(|_| String::from("Failed to generate FEN string for board"));
 
     let fen_area = centre::centered_rect(80, 20, area);
@@ -0,0 +1,104 @@
+use std::time::{Duration, Instant};
+
+use crate::config::AutoplayConfig;
+
+const MIN_INTERVAL: Duration = Duration::from_millis(250);
+const MAX_INTERVAL: Duration = Duration::from_secs(60);
+const INTERVAL_STEP: Duration = Duration::from_millis(250);
+
+pub struct Autoplay {
+    playing: bool,
+    interval: Duration,
+    next_game: bool,
+    last_advance: Instant,
+}
+
+impl Autoplay {
+    pub fn new(config: &AutoplayConfig, now: Instant) -> Self {
+        Autoplay {
+            playing: config.start(),
+            interval: Duration::from_millis(config.interval_ms()).clamp(MIN_INTERVAL, MAX_INTERVAL),
+            next_game: config.next_game(),
+            last_advance: now,
+        }
+    }
+
+    pub fn next_game(&self) -> bool {
+        self.next_game
+    }
+
+    pub fn toggle(&mut self, now: Instant) {
+        self.playing = !self.playing;
+        self.last_advance = now;
+    }
+
+    pub fn pause(&mut self) {
+        self.playing = false;
+    }
+
+    pub fn faster(&mut self) {
+        self.interval = self
+            .interval
+            .saturating_sub(INTERVAL_STEP)
+            .max(MIN_INTERVAL);
+    }
+
+    pub fn slower(&mut self) {
+        self.interval = (self.interval + INTERVAL_STEP).min(MAX_INTERVAL);
+    }
+
+    // How long to wait for input before the next advance, or None to wait indefinitely
+    pub fn timeout(&self, now: Instant) -> Option<Duration> {
+        self.playing
+            .then(|| (self.last_advance + self.interval).saturating_duration_since(now))
+    }
+
+    pub fn due(&self, now: Instant) -> bool {
+        self.playing && now.duration_since(self.last_advance) >= self.interval
+    }
+
+    pub fn advanced(&mut self, now: Instant) {
+        self.last_advance = now;
+    }
+
+    pub fn label(&self) -> String {
+        let status = if self.playing { "▶" } else { "⏸" };
+        format!("{status} {:.2}s", self.interval.as_secs_f32())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn waits_indefinitely_if_paused() {
+        let autoplay = Autoplay::new(&AutoplayConfig::default(), Instant::now());
+        assert_eq!(autoplay.timeout(Instant::now()), None)
+    }
+
+    #[test]
+    fn becomes_due_after_interval() {
+        let start = Instant::now();
+        let mut autoplay = Autoplay::new(&AutoplayConfig::default(), start);
+        autoplay.toggle(start);
+
+        assert!(!autoplay.due(start + Duration::from_millis(1000)));
+        assert!(autoplay.due(start + Duration::from_millis(1500)));
+        assert_eq!(
+            autoplay.timeout(start + Duration::from_millis(1000)),
+            Some(Duration::from_millis(500))
+        );
+    }
+
+    #[test]
+    fn limits_speed() {
+        let mut autoplay = Autoplay::new(&AutoplayConfig::default(), Instant::now());
+        for _ in 0..10 {
+            autoplay.faster();
+        }
+        assert_eq!(autoplay.interval, MIN_INTERVAL);
+        autoplay.slower();
+        assert_eq!(autoplay.interval, MIN_INTERVAL + INTERVAL_STEP);
+    }
+}
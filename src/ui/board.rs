@@ -1,35 +1,47 @@
 use ratatui::{
     layout::{Alignment, Rect},
-    style::{Color, Style},
+    style::Style,
     text::{Line, Span},
     widgets::{Block, Padding, Paragraph},
     Frame,
 };
 
-use crate::model::{Board, PieceColour, Position, MAX_POSITION};
+use crate::{
+    config::Theme,
+    model::{
+        Board, Piece, PieceColour, PieceType, Position, COLUMNS, MAX_POSITION, MIN_POSITION, ROWS,
+    },
+};
 
-pub fn render(frame: &mut Frame, board: &Board, perspective: PieceColour, area: Rect) {
-    let positions = |i: i8| {
-        let row = match perspective {
-            PieceColour::White => i,
-            PieceColour::Black => MAX_POSITION - i,
-        };
+use super::analysis::Selection;
 
-        (0..=MAX_POSITION).map(move |column| Position::new(row, column))
-    };
+const BOARD_HEIGHT: u16 = 9;
 
-    let board_text: Vec<Line> = (0..=MAX_POSITION)
-        .map(positions)
-        .map(|positions| {
-            Line::from(
-                positions
-                    .map(|position| square(position, board))
-                    .collect::<Vec<Span>>(),
-            )
+pub fn render(
+    frame: &mut Frame,
+    board: &Board,
+    perspective: PieceColour,
+    theme: &Theme,
+    selection: Option<&Selection>,
+    area: Rect,
+) {
+    let coordinates_style = Style::default().fg(theme.board().coordinates());
+
+    let mut board_text: Vec<Line> = rows(perspective)
+        .map(|row| {
+            let mut spans = vec![Span::styled(rank_label(row), coordinates_style)];
+            spans.extend(
+                columns(perspective)
+                    .map(|column| square(Position::new(row, column), board, theme, selection)),
+            );
+            Line::from(spans)
         })
         .collect();
 
-    let vertical_padding = (area.height - 8) / 2;
+    let file_labels: String = columns(perspective).map(file_label).collect();
+    board_text.push(Line::styled(format!("   {file_labels}"), coordinates_style));
+
+    let vertical_padding = area.height.saturating_sub(BOARD_HEIGHT) / 2;
 
     let paragraph = Paragraph::new(board_text)
         .alignment(Alignment::Center)
@@ -38,20 +50,124 @@ pub fn render(frame: &mut Frame, board: &Board, perspective: PieceColour, area:
     frame.render_widget(paragraph, area);
 }
 
-fn square(position: Position, board: &Board) -> Span {
-    let maybe_piece = board.occupant(position);
-    let text = maybe_piece.map_or("   ".to_string(), |piece| format!(" {piece} "));
+pub fn glyph(piece: &Piece, unicode: bool) -> String {
+    if !unicode {
+        return piece.to_string();
+    }
+
+    match (piece.colour(), piece.piece_type()) {
+        (PieceColour::Black, PieceType::Pawn) => "♟",
+        (PieceColour::Black, PieceType::Knight) => "♞",
+        (PieceColour::Black, PieceType::Bishop) => "♝",
+        (PieceColour::Black, PieceType::Rook) => "♜",
+        (PieceColour::Black, PieceType::Queen) => "♛",
+        (PieceColour::Black, PieceType::King) => "♚",
+        (PieceColour::White, PieceType::Pawn) => "♙",
+        (PieceColour::White, PieceType::Knight) => "♘",
+        (PieceColour::White, PieceType::Bishop) => "♗",
+        (PieceColour::White, PieceType::Rook) => "♖",
+        (PieceColour::White, PieceType::Queen) => "♕",
+        (PieceColour::White, PieceType::King) => "♔",
+    }
+    .to_string()
+}
+
+// Rows are listed top to bottom, so White's perspective starts from the eighth rank
+pub fn rows(perspective: PieceColour) -> Box<dyn Iterator<Item = i8>> {
+    match perspective {
+        PieceColour::White => Box::new((MIN_POSITION..=MAX_POSITION).rev()),
+        PieceColour::Black => Box::new(MIN_POSITION..=MAX_POSITION),
+    }
+}
+
+pub fn columns(perspective: PieceColour) -> Box<dyn Iterator<Item = i8>> {
+    match perspective {
+        PieceColour::White => Box::new(MIN_POSITION..=MAX_POSITION),
+        PieceColour::Black => Box::new((MIN_POSITION..=MAX_POSITION).rev()),
+    }
+}
+
+pub fn rank_label(row: i8) -> String {
+    let rank = usize::try_from(row)
+        .ok()
+        .and_then(|row| ROWS.chars().nth(row))
+        .unwrap_or(' ');
+    format!(" {rank} ")
+}
+
+pub fn file_label(column: i8) -> String {
+    let file = usize::try_from(column)
+        .ok()
+        .and_then(|column| COLUMNS.chars().nth(column))
+        .unwrap_or(' ');
+    format!(" {file} ")
+}
 
-    let colour = maybe_piece.map_or(Color::Black, |piece| match piece.colour() {
-        PieceColour::White => Color::White,
-        PieceColour::Black => Color::DarkGray,
+fn square<'a>(
+    position: Position,
+    board: &Board,
+    theme: &Theme,
+    selection: Option<&Selection>,
+) -> Span<'a> {
+    let maybe_piece = board.occupant(position);
+    let text = maybe_piece.map_or("   ".to_string(), |piece| {
+        format!(" {} ", glyph(piece, theme.pieces().unicode()))
     });
 
-    let style = Style::default().fg(colour);
+    let style = match maybe_piece.map(Piece::colour) {
+        Some(PieceColour::White) => Style::default().fg(theme.pieces().white()),
+        Some(PieceColour::Black) => Style::default().fg(theme.pieces().black()),
+        None => Style::default(),
+    };
 
-    if (position.row() + position.col()) % 2 == 0 {
-        Span::styled(text, style.bg(Color::LightBlue))
+    let background = if selection.is_some_and(|s| s.selected() == Some(position)) {
+        theme.board().selected()
+    } else if selection.is_some_and(|s| s.cursor() == position) {
+        theme.board().cursor()
+    } else if (position.row() + position.col()) % 2 == 0 {
+        theme.board().dark_square()
     } else {
-        Span::styled(text, style.bg(Color::LightRed))
+        theme.board().light_square()
+    };
+
+    Span::styled(text, style.bg(background))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod glyph_tests {
+        use super::*;
+
+        #[test]
+        fn returns_letter_if_not_unicode() {
+            let piece = Piece::new(PieceColour::Black, PieceType::Knight);
+            assert_eq!(glyph(&piece, false), "N")
+        }
+
+        #[test]
+        fn distinguishes_colours_if_unicode() {
+            let white = Piece::new(PieceColour::White, PieceType::Queen);
+            let black = Piece::new(PieceColour::Black, PieceType::Queen);
+            assert_eq!(glyph(&white, true), "♕");
+            assert_eq!(glyph(&black, true), "♛");
+        }
+    }
+
+    mod perspective_tests {
+        use super::*;
+
+        #[test]
+        fn lists_eighth_rank_first_from_white_perspective() {
+            let labels: String = rows(PieceColour::White).map(rank_label).collect();
+            assert_eq!(labels, " 8  7  6  5  4  3  2  1 ")
+        }
+
+        #[test]
+        fn lists_h_file_first_from_black_perspective() {
+            let labels: String = columns(PieceColour::Black).map(file_label).collect();
+            assert_eq!(labels, " h  g  f  e  d  c  b  a ")
+        }
     }
 }
@@ -1,72 +1,251 @@
+use std::time::Duration;
+
 use crossterm::event::{self, Event, KeyCode, KeyEventKind};
 use ratatui::{
     layout::Rect,
     style::{Color, Style},
-    text::Span,
-    widgets::{block::Title, Block, Borders},
+    text::{Line, Span},
+    widgets::{block::Title, Block, Borders, Clear, Paragraph},
     Frame,
 };
 
-use super::error::UiError;
+use crate::config::{Action, KeyBindings};
+
+use super::{analysis::Direction, centre, error::UiError};
+
+// Only the bindings needed to get around and find the rest are kept in the footer, as the full
+// lists below don't fit on one line
+const FOOTER: &[(&str, &[Action])] = &[
+    (
+        "Navigate",
+        &[
+            Action::PreviousGame,
+            Action::PreviousPly,
+            Action::NextGame,
+            Action::NextPly,
+        ],
+    ),
+    ("Help", &[Action::ToggleHelp]),
+    ("Quit", &[Action::Quit]),
+];
+
+const ANALYSIS_FOOTER: &[(&str, &[Action])] = &[
+    ("Navigate", &[Action::PreviousPly, Action::NextPly]),
+    ("Leave analysis", &[Action::Analyse]),
+    ("Help", &[Action::ToggleHelp]),
+    ("Quit", &[Action::Quit]),
+];
 
-const PREVIOUS_PLY_KEY: char = 'a';
-const NEXT_PLY_KEY: char = 'd';
-const PREVIOUS_GAME_KEY: char = 'w';
-const NEXT_GAME_KEY: char = 's';
-const FLIP_PERSPECTIVE_KEY: char = 'e';
-const METADATA_KEY: char = 'x';
-const FEN_KEY: char = 'f';
-const QUIT_KEY: char = 'q';
+const HELP: &[(&str, &[Action])] = &[
+    (
+        "Navigate",
+        &[
+            Action::PreviousGame,
+            Action::PreviousPly,
+            Action::NextGame,
+            Action::NextPly,
+        ],
+    ),
+    ("Start/end", &[Action::FirstPly, Action::LastPly]),
+    ("Jump", &[Action::JumpBackwards, Action::JumpForwards]),
+    ("Go to move", &[Action::GoToMove]),
+    ("Search", &[Action::Search]),
+    (
+        "Find position",
+        &[Action::FindPosition, Action::EnterPosition],
+    ),
+    ("Find pattern", &[Action::FindPattern]),
+    ("Matches", &[Action::PreviousMatch, Action::NextMatch]),
+    ("Sort", &[Action::SortGames, Action::ReverseSort]),
+    ("Analyse", &[Action::Analyse]),
+    ("Engine", &[Action::ToggleEngine]),
+    ("Explorer", &[Action::ToggleExplorer]),
+    (
+        "Explore",
+        &[
+            Action::PreviousExplorerMove,
+            Action::NextExplorerMove,
+            Action::PlayExplorerMove,
+        ],
+    ),
+    ("Autoplay", &[Action::ToggleAutoplay]),
+    ("Speed", &[Action::AutoplayFaster, Action::AutoplaySlower]),
+    ("Flip", &[Action::FlipPerspective]),
+    ("Toggle metadata", &[Action::ToggleMetadata]),
+    ("Player stats", &[Action::ToggleStats]),
+    ("Display FEN string", &[Action::DisplayFen]),
+    ("Diagram", &[Action::SaveDiagram]),
+    ("Help", &[Action::ToggleHelp]),
+    ("Quit", &[Action::Quit]),
+];
 
-const NAVIGATE_LABEL: &str = " Navigate: w a s d ";
-const FLIP_LABEL: &str = " Flip: e ";
-const METADATA_LABEL: &str = " Toggle metadata: x ";
-const FEN_LABEL: &str = " Display FEN string: f ";
-const QUIT_LABEL: &str = " Quit: q ";
+const ANALYSIS_HELP: &[(&str, &[Action])] = &[
+    ("Navigate", &[Action::PreviousPly, Action::NextPly]),
+    ("Move", &[Action::EnterMove]),
+    (
+        "Cursor",
+        &[
+            Action::CursorLeft,
+            Action::CursorDown,
+            Action::CursorUp,
+            Action::CursorRight,
+        ],
+    ),
+    ("Pick", &[Action::PickSquare]),
+    ("Save as variation", &[Action::SaveVariation]),
+    ("Find position", &[Action::FindPosition]),
+    ("Engine", &[Action::ToggleEngine]),
+    ("Explorer", &[Action::ToggleExplorer]),
+    (
+        "Explore",
+        &[
+            Action::PreviousExplorerMove,
+            Action::NextExplorerMove,
+            Action::PlayExplorerMove,
+        ],
+    ),
+    ("Flip", &[Action::FlipPerspective]),
+    ("Diagram", &[Action::SaveDiagram]),
+    ("Leave analysis", &[Action::Analyse]),
+    ("Help", &[Action::ToggleHelp]),
+    ("Quit", &[Action::Quit]),
+];
+
+const FOOTER_COLOURS: &[Color] = &[Color::LightGreen, Color::LightBlue];
 
 pub enum Command {
     PlyForwards,
     PlyBackwards,
+    FirstPly,
+    LastPly,
+    JumpForwards,
+    JumpBackwards,
+    GoToMove,
+    Search,
+    FindPosition,
+    EnterPosition,
+    FindPattern,
+    NextMatch,
+    PreviousMatch,
+    ToggleAutoplay,
+    AutoplayFaster,
+    AutoplaySlower,
+    PromptInput(char),
+    PromptDelete,
+    PromptSubmit,
+    PromptCancel,
     GameForwards,
     GameBackwards,
+    SortGames,
+    ReverseSort,
+    Analyse,
+    EnterMove,
+    MoveCursor(Direction),
+    PickSquare,
+    SaveVariation,
+    ToggleEngine,
+    ToggleExplorer,
+    PreviousExplorerMove,
+    NextExplorerMove,
+    PlayExplorerMove,
     FlipPerspective,
     ToggleMetadata,
+    ToggleStats,
+    ToggleHelp,
     DisplayFen,
+    SaveDiagram,
     Quit,
 }
 
-pub fn read() -> Result<Option<Command>, UiError> {
+impl From<Action> for Command {
+    fn from(action: Action) -> Self {
+        match action {
+            Action::PreviousPly => Command::PlyBackwards,
+            Action::NextPly => Command::PlyForwards,
+            Action::FirstPly => Command::FirstPly,
+            Action::LastPly => Command::LastPly,
+            Action::JumpBackwards => Command::JumpBackwards,
+            Action::JumpForwards => Command::JumpForwards,
+            Action::GoToMove => Command::GoToMove,
+            Action::Search => Command::Search,
+            Action::FindPosition => Command::FindPosition,
+            Action::EnterPosition => Command::EnterPosition,
+            Action::FindPattern => Command::FindPattern,
+            Action::NextMatch => Command::NextMatch,
+            Action::PreviousMatch => Command::PreviousMatch,
+            Action::ToggleAutoplay => Command::ToggleAutoplay,
+            Action::AutoplayFaster => Command::AutoplayFaster,
+            Action::AutoplaySlower => Command::AutoplaySlower,
+            Action::PreviousGame => Command::GameBackwards,
+            Action::NextGame => Command::GameForwards,
+            Action::SortGames => Command::SortGames,
+            Action::ReverseSort => Command::ReverseSort,
+            Action::Analyse => Command::Analyse,
+            Action::EnterMove => Command::EnterMove,
+            Action::CursorLeft => Command::MoveCursor(Direction::Left),
+            Action::CursorRight => Command::MoveCursor(Direction::Right),
+            Action::CursorUp => Command::MoveCursor(Direction::Up),
+            Action::CursorDown => Command::MoveCursor(Direction::Down),
+            Action::PickSquare => Command::PickSquare,
+            Action::SaveVariation => Command::SaveVariation,
+            Action::ToggleEngine => Command::ToggleEngine,
+            Action::ToggleExplorer => Command::ToggleExplorer,
+            Action::PreviousExplorerMove => Command::PreviousExplorerMove,
+            Action::NextExplorerMove => Command::NextExplorerMove,
+            Action::PlayExplorerMove => Command::PlayExplorerMove,
+            Action::FlipPerspective => Command::FlipPerspective,
+            Action::ToggleMetadata => Command::ToggleMetadata,
+            Action::ToggleStats => Command::ToggleStats,
+            Action::ToggleHelp => Command::ToggleHelp,
+            Action::DisplayFen => Command::DisplayFen,
+            Action::SaveDiagram => Command::SaveDiagram,
+            Action::Quit => Command::Quit,
+        }
+    }
+}
+
+// While prompting, keys are taken as text rather than looked up in the bindings. Returns None if
+// no event arrives within the timeout, if one is given
+pub fn read(
+    bindings: &KeyBindings,
+    prompting: bool,
+    timeout: Option<Duration>,
+) -> Result<Option<Command>, UiError> {
+    if let Some(timeout) = timeout {
+        let ready = event::poll(timeout)
+            .map_err(|e| UiError::new(format!("Failed to poll for event: {e}")))?;
+        if !ready {
+            return Ok(None);
+        }
+    }
+
     let event = event::read().map_err(|e| UiError::new(format!("Failed to read event: {e}")))?;
     if let Event::Key(key) = event {
-        if key.kind == KeyEventKind::Press {
-            match key.code {
-                KeyCode::Char(PREVIOUS_PLY_KEY) => Ok(Some(Command::PlyBackwards)),
-                KeyCode::Char(NEXT_PLY_KEY) => Ok(Some(Command::PlyForwards)),
-                KeyCode::Char(PREVIOUS_GAME_KEY) => Ok(Some(Command::GameBackwards)),
-                KeyCode::Char(NEXT_GAME_KEY) => Ok(Some(Command::GameForwards)),
-                KeyCode::Char(FLIP_PERSPECTIVE_KEY) => Ok(Some(Command::FlipPerspective)),
-                KeyCode::Char(METADATA_KEY) => Ok(Some(Command::ToggleMetadata)),
-                KeyCode::Char(FEN_KEY) => Ok(Some(Command::DisplayFen)),
-                KeyCode::Char(QUIT_KEY) => Ok(Some(Command::Quit)),
-                _ => Ok(None),
-            }
-        } else {
+        if key.kind != KeyEventKind::Press {
             Ok(None)
+        } else if prompting {
+            Ok(match key.code {
+                KeyCode::Char(c) => Some(Command::PromptInput(c)),
+                KeyCode::Backspace => Some(Command::PromptDelete),
+                KeyCode::Enter => Some(Command::PromptSubmit),
+                KeyCode::Esc => Some(Command::PromptCancel),
+                _ => None,
+            })
+        } else {
+            Ok(bindings.action(key.into()).map(Command::from))
         }
     } else {
         Ok(None)
     }
 }
 
-pub fn render(frame: &mut Frame, area: Rect) {
-    let title: Vec<Span> = [
-        command(NAVIGATE_LABEL, Color::LightGreen),
-        command(FLIP_LABEL, Color::LightBlue),
-        command(METADATA_LABEL, Color::LightGreen),
-        command(FEN_LABEL, Color::LightBlue),
-        command(QUIT_LABEL, Color::LightGreen),
-    ]
-    .concat();
+pub fn render(frame: &mut Frame, bindings: &KeyBindings, analysing: bool, area: Rect) {
+    let footer = if analysing { ANALYSIS_FOOTER } else { FOOTER };
+    let title: Vec<Span> = labels(footer, bindings)
+        .into_iter()
+        .zip(FOOTER_COLOURS.iter().cycle())
+        .flat_map(|(label, &colour)| command(label, colour))
+        .collect();
 
     let commands = Block::default()
         .borders(Borders::TOP)
@@ -76,7 +255,47 @@ pub fn render(frame: &mut Frame, area: Rect) {
     frame.render_widget(commands, area);
 }
 
-fn command(label: &str, background_colour: Color) -> [Span; 2] {
+// Every binding available in the current mode, one to a line
+pub fn render_help(frame: &mut Frame, bindings: &KeyBindings, analysing: bool, area: Rect) {
+    let help = if analysing { ANALYSIS_HELP } else { HELP };
+    let lines: Vec<Line> = labels(help, bindings)
+        .into_iter()
+        .map(|label| Line::from(label.trim().to_string()))
+        .collect();
+
+    let area = centre::centered_rect(60, 90, area);
+    let block = Block::default().borders(Borders::ALL).title(" Help ");
+    frame.render_widget(Clear, area);
+    frame.render_widget(Paragraph::new(lines).block(block), area);
+}
+
+fn labels(footer: &[(&str, &[Action])], bindings: &KeyBindings) -> Vec<String> {
+    footer
+        .iter()
+        .filter_map(|(description, actions)| {
+            let keys: Vec<String> = actions
+                .iter()
+                .map(|&action| {
+                    bindings
+                        .keys(action)
+                        .iter()
+                        .map(ToString::to_string)
+                        .collect::<Vec<String>>()
+                        .join("/")
+                })
+                .filter(|keys| !keys.is_empty())
+                .collect();
+
+            if keys.is_empty() {
+                None
+            } else {
+                Some(format!(" {description}: {} ", keys.join(" ")))
+            }
+        })
+        .collect()
+}
+
+fn command<'a>(label: String, background_colour: Color) -> [Span<'a>; 2] {
     [
         Span::styled(
             label,
@@ -85,3 +304,58 @@ fn command(label: &str, background_colour: Color) -> [Span; 2] {
         Span::from(" "),
     ]
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn generates_labels_from_bindings() {
+        let labels = labels(HELP, &KeyBindings::default());
+        assert_eq!(
+            labels,
+            vec![
+                " Navigate: w/↑ a/← s/↓ d/→ ",
+                " Start/end: Home End ",
+                " Jump: PgUp PgDn ",
+                " Go to move: : ",
+                " Search: / ",
+                " Find position: p P ",
+                " Find pattern: # ",
+                " Matches: [ ] ",
+                " Sort: o O ",
+                " Analyse: n ",
+                " Engine: g ",
+                " Explorer: t ",
+                " Explore: i k Tab ",
+                " Autoplay: Space ",
+                " Speed: + - ",
+                " Flip: e ",
+                " Toggle metadata: x ",
+                " Player stats: c ",
+                " Display FEN string: f ",
+                " Diagram: D ",
+                " Help: ? ",
+                " Quit: q ",
+            ]
+        )
+    }
+
+    #[test]
+    fn keeps_footer_short() {
+        let bindings = KeyBindings::default();
+        assert_eq!(
+            labels(FOOTER, &bindings),
+            vec![" Navigate: w/↑ a/← s/↓ d/→ ", " Help: ? ", " Quit: q "]
+        );
+        assert_eq!(
+            labels(ANALYSIS_FOOTER, &bindings),
+            vec![
+                " Navigate: a/← d/→ ",
+                " Leave analysis: n ",
+                " Help: ? ",
+                " Quit: q "
+            ]
+        );
+    }
+}
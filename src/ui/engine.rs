@@ -0,0 +1,111 @@
+use ratatui::{
+    layout::Rect,
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph, Wrap},
+    Frame,
+};
+
+use crate::{
+    model::Board,
+    uci::{self, Engine, Info},
+    write,
+};
+
+use super::ply;
+
+// Analyses whichever board is on screen, restarting the search whenever it changes
+pub struct EnginePanel {
+    engine: Engine,
+    running: bool,
+    board: Option<Board>,
+    info: Option<Info>,
+    error: Option<String>,
+}
+
+impl EnginePanel {
+    pub fn new(engine: Engine) -> Self {
+        EnginePanel {
+            engine,
+            running: true,
+            board: None,
+            info: None,
+            error: None,
+        }
+    }
+
+    pub fn searching(&self) -> bool {
+        self.engine.searching()
+    }
+
+    pub fn toggle(&mut self) {
+        self.running = !self.running;
+        if !self.running {
+            if let Err(e) = self.engine.stop() {
+                self.error = Some(e.to_string());
+            }
+            self.board = None;
+            self.info = None;
+        }
+    }
+
+    pub fn update(&mut self, board: &Board) {
+        if !self.running || self.error.is_some() || self.board.as_ref() == Some(board) {
+            return;
+        }
+
+        self.board = Some(board.clone());
+        self.info = None;
+        let result = write::fen(board)
+            .map_err(|e| e.to_string())
+            .and_then(|fen| self.engine.go_infinite(&fen).map_err(|e| e.to_string()));
+        if let Err(e) = result {
+            self.error = Some(e);
+        }
+    }
+
+    pub fn poll(&mut self) {
+        if self.error.is_some() {
+            return;
+        }
+        match self.engine.poll() {
+            Ok(Some(info)) => self.info = Some(info),
+            Ok(None) => {}
+            Err(e) => self.error = Some(e.to_string()),
+        }
+    }
+}
+
+pub fn render(frame: &mut Frame, panel: &EnginePanel, area: Rect) {
+    let dim = Style::default().add_modifier(Modifier::DIM);
+    let lines = match (&panel.error, &panel.board, &panel.info) {
+        (Some(error), _, _) => vec![Line::from(error.as_str())],
+        (None, _, _) if !panel.running => vec![Line::styled("Paused", dim)],
+        (None, Some(board), Some(info)) => {
+            let score = info.score().map_or_else(
+                || String::from("?"),
+                |score| score.for_white(*board.active_colour()).to_string(),
+            );
+            let depth = info
+                .depth()
+                .map_or_else(|| String::from("?"), |depth| depth.to_string());
+            vec![
+                Line::from(vec![
+                    Span::styled("Depth ", dim),
+                    Span::from(depth),
+                    Span::styled("  Score ", dim),
+                    Span::styled(score, Style::default().add_modifier(Modifier::BOLD)),
+                ]),
+                Line::from(ply::line_text(&uci::line(board, info.pv()))),
+            ]
+        }
+        (None, _, _) => vec![Line::styled("Searching…", dim)],
+    };
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(format!(" {} ", panel.engine.name()));
+    let paragraph = Paragraph::new(lines).block(block).wrap(Wrap { trim: true });
+
+    frame.render_widget(paragraph, area);
+}
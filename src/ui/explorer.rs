@@ -0,0 +1,116 @@
+use ratatui::{
+    layout::{Constraint, Rect},
+    style::{Modifier, Style},
+    widgets::{Block, Borders, Cell, HighlightSpacing, Paragraph, Row, Table, TableState},
+    Frame,
+};
+
+use crate::{
+    model::{Board, Game},
+    search::{self, MoveStats, PositionIndex},
+};
+
+// The moves played from whichever board is on screen, recounted whenever it changes
+pub struct ExplorerPane {
+    visible: bool,
+    board: Option<Board>,
+    moves: Vec<MoveStats>,
+    table_state: TableState,
+}
+
+impl ExplorerPane {
+    pub fn new() -> Self {
+        ExplorerPane {
+            visible: false,
+            board: None,
+            moves: Vec::new(),
+            table_state: TableState::default(),
+        }
+    }
+
+    pub fn visible(&self) -> bool {
+        self.visible
+    }
+
+    pub fn toggle(&mut self) {
+        self.visible = !self.visible;
+    }
+
+    pub fn update(&mut self, games: &[Game], index: &PositionIndex, board: &Board) {
+        if !self.visible || self.board.as_ref() == Some(board) {
+            return;
+        }
+
+        self.board = Some(board.clone());
+        self.moves = search::explore(games, index, board);
+        self.table_state
+            .select((!self.moves.is_empty()).then_some(0));
+    }
+
+    // Moves the selection by the step, stopping at either end
+    pub fn step(&mut self, step: isize) {
+        if let Some(selected) = self.table_state.selected() {
+            let last = self.moves.len().saturating_sub(1);
+            self.table_state
+                .select(Some(selected.saturating_add_signed(step).min(last)));
+        }
+    }
+
+    pub fn selected(&self) -> Option<&MoveStats> {
+        self.table_state
+            .selected()
+            .and_then(|selected| self.moves.get(selected))
+    }
+}
+
+pub fn render(frame: &mut Frame, pane: &mut ExplorerPane, area: Rect) {
+    let block = Block::default()
+        .borders(Borders::TOP.union(Borders::BOTTOM).union(Borders::LEFT))
+        .title(" Explorer ");
+
+    if pane.moves.is_empty() {
+        let paragraph = Paragraph::new("No games continue from this position")
+            .style(Style::default().add_modifier(Modifier::DIM))
+            .block(block);
+        frame.render_widget(paragraph, area);
+        return;
+    }
+
+    let header = Row::new(["Move", "Games", "White / Draw / Black", "Avg Elo"])
+        .style(Style::default().add_modifier(Modifier::BOLD | Modifier::UNDERLINED));
+
+    let rows = pane.moves.iter().map(|stats| {
+        let elo = |elo: Option<u64>| elo.map_or_else(|| String::from("-"), |elo| elo.to_string());
+        Row::new([
+            Cell::from(stats.movement().to_string()),
+            Cell::from(stats.games().to_string()),
+            Cell::from(format!(
+                "{:.0}% / {:.0}% / {:.0}%",
+                stats.white_percentage(),
+                stats.draw_percentage(),
+                stats.black_percentage()
+            )),
+            Cell::from(format!(
+                "{} / {}",
+                elo(stats.average_white_elo()),
+                elo(stats.average_black_elo())
+            )),
+        ])
+    });
+
+    let table = Table::new(
+        rows,
+        [
+            Constraint::Length(8),
+            Constraint::Length(6),
+            Constraint::Length(20),
+            Constraint::Fill(1),
+        ],
+    )
+    .header(header)
+    .block(block)
+    .highlight_spacing(HighlightSpacing::Always)
+    .highlight_symbol(">>");
+
+    frame.render_stateful_widget(table, area, &mut pane.table_state);
+}
@@ -1,27 +1,195 @@
+use std::cmp::Ordering;
+
 use ratatui::{
-    layout::Rect,
-    style::{Color, Modifier, Style, Stylize},
-    text::{Line, Span},
-    widgets::{Block, Borders, HighlightSpacing, List, ListItem, ListState},
+    layout::{Constraint, Rect},
+    style::{Modifier, Style, Stylize},
+    text::Span,
+    widgets::{Block, Borders, Cell, HighlightSpacing, Row, Table, TableState},
     Frame,
 };
 
-use crate::Game;
+use crate::{
+    config::{Column, PiecesTheme},
+    Game,
+};
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+struct Sort {
+    column: Column,
+    descending: bool,
+}
+
+impl Sort {
+    // Cycles through the displayed columns, returning to the order the games were loaded in after
+    // the last one
+    fn next(sort: Option<Sort>, columns: &[Column]) -> Option<Sort> {
+        let position = match sort {
+            Some(sort) => columns
+                .iter()
+                .position(|&column| column == sort.column)
+                .map_or(0, |position| position + 1),
+            None => 0,
+        };
+        columns.get(position).map(|&column| Sort {
+            column,
+            descending: false,
+        })
+    }
+
+    fn reversed(self) -> Sort {
+        Sort {
+            descending: !self.descending,
+            ..self
+        }
+    }
+}
+
+// The games shown in the table, in the order shown, which navigation follows
+pub struct GameList {
+    visible_games: Vec<usize>,
+    columns: Vec<Column>,
+    sort: Option<Sort>,
+    table_state: TableState,
+}
+
+impl GameList {
+    pub fn new(game_count: usize, columns: &[Column]) -> Self {
+        GameList {
+            visible_games: (0..game_count).collect(),
+            columns: columns.to_vec(),
+            sort: None,
+            table_state: TableState::default().with_selected(Some(0)),
+        }
+    }
+
+    pub fn contains(&self, game: usize) -> bool {
+        self.visible_games.contains(&game)
+    }
+
+    pub fn first(&self) -> Option<usize> {
+        self.visible_games.first().copied()
+    }
+
+    pub fn filter(&mut self, games: &[Game], visible_games: Vec<usize>) {
+        self.visible_games = visible_games;
+        sort(games, &mut self.visible_games, self.sort);
+    }
+
+    pub fn cycle_sort(&mut self, games: &[Game]) {
+        self.sort = Sort::next(self.sort, &self.columns);
+        sort(games, &mut self.visible_games, self.sort);
+    }
+
+    pub fn reverse_sort(&mut self, games: &[Game]) {
+        self.sort = self.sort.map(Sort::reversed);
+        sort(games, &mut self.visible_games, self.sort);
+    }
+
+    pub fn select(&mut self, game: usize) {
+        self.table_state.select(self.position(game));
+    }
+
+    // Falls back to the first game if the given one has been filtered out
+    pub fn next(&self, game: usize, wrap: bool) -> Option<usize> {
+        match self.position(game) {
+            Some(position) => self
+                .visible_games
+                .get(position + 1)
+                .or_else(|| wrap.then(|| self.visible_games.first()).flatten())
+                .copied(),
+            None => self.first(),
+        }
+    }
+
+    pub fn previous(&self, game: usize) -> Option<usize> {
+        match self.position(game) {
+            Some(position) => position
+                .checked_sub(1)
+                .and_then(|previous| self.visible_games.get(previous))
+                .copied(),
+            None => self.first(),
+        }
+    }
+
+    fn position(&self, game: usize) -> Option<usize> {
+        self.visible_games
+            .iter()
+            .position(|&visible| visible == game)
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
+enum SortValue {
+    Number(u64),
+    Text(String),
+}
+
+fn sort(games: &[Game], visible_games: &mut [usize], sort: Option<Sort>) {
+    let Some(sort) = sort else {
+        visible_games.sort_unstable();
+        return;
+    };
+
+    // Games missing the value always go last, whichever direction is chosen
+    visible_games.sort_by(|&a, &b| {
+        match (
+            sort_value(&games[a], sort.column),
+            sort_value(&games[b], sort.column),
+        ) {
+            (Some(a), Some(b)) if sort.descending => b.cmp(&a),
+            (Some(a), Some(b)) => a.cmp(&b),
+            (Some(_), None) => Ordering::Less,
+            (None, Some(_)) => Ordering::Greater,
+            (None, None) => Ordering::Equal,
+        }
+    });
+}
 
 pub fn render(
     frame: &mut Frame,
     games: &[Game],
+    list: &mut GameList,
     area: Rect,
-    list_state: &mut ListState,
     dim: bool,
+    theme: &PiecesTheme,
 ) {
-    let list_items: Vec<ListItem> = games
-        .iter()
-        .map(|game| ListItem::new(Line::from(game_description(game))))
-        .collect();
+    let GameList {
+        visible_games,
+        columns,
+        sort,
+        table_state,
+    } = list;
+
+    let header = Row::new(columns.iter().map(|&column| {
+        let indicator = match sort {
+            Some(sort) if sort.column == column && sort.descending => " ▼",
+            Some(sort) if sort.column == column => " ▲",
+            _ => "",
+        };
+        Cell::from(format!("{}{indicator}", title(column)))
+    }))
+    .style(Style::default().add_modifier(Modifier::BOLD | Modifier::UNDERLINED));
+
+    let rows = visible_games.iter().map(|&game| {
+        Row::new(
+            columns
+                .iter()
+                .map(|&column| Cell::from(cell(&games[game], column, theme))),
+        )
+    });
 
-    let list = List::new(list_items)
-        .block(Block::default().borders(Borders::TOP.union(Borders::BOTTOM)))
+    let mut block = Block::default().borders(Borders::TOP.union(Borders::BOTTOM));
+    if visible_games.len() < games.len() {
+        block = block.title(format!(
+            " Showing {} of {} games ",
+            visible_games.len(),
+            games.len()
+        ));
+    }
+
+    let table = Table::new(rows, columns.iter().map(|&column| width(column)))
+        .header(header)
+        .block(block)
         .style(if dim {
             Style::default().dim()
         } else {
@@ -30,40 +198,170 @@ pub fn render(
         .highlight_spacing(HighlightSpacing::Always)
         .highlight_symbol(">>");
 
-    frame.render_stateful_widget(list, area, list_state);
+    frame.render_stateful_widget(table, area, table_state);
 }
 
-fn game_description(game: &Game) -> Vec<Span> {
-    let tags = game.pgn().tags();
-
-    let white_player = tags.get_or_default("White", "Unknown");
-    let black_player = tags.get_or_default("Black", "Unknown");
+fn title(column: Column) -> &'static str {
+    match column {
+        Column::White => "White",
+        Column::Black => "Black",
+        Column::WhiteElo => "W Elo",
+        Column::BlackElo => "B Elo",
+        Column::Result => "Result",
+        Column::Date => "Date",
+        Column::Event => "Event",
+        Column::Eco => "ECO",
+        Column::Moves => "Moves",
+    }
+}
 
-    let event = tags.get("Event");
-    let date = tags.get("Date");
+fn width(column: Column) -> Constraint {
+    match column {
+        Column::White | Column::Black => Constraint::Fill(2),
+        Column::Event => Constraint::Fill(3),
+        Column::WhiteElo | Column::BlackElo | Column::Moves => Constraint::Length(7),
+        Column::Result => Constraint::Length(8),
+        Column::Date => Constraint::Length(10),
+        Column::Eco => Constraint::Length(5),
+    }
+}
 
+fn cell<'a>(game: &Game, column: Column, theme: &PiecesTheme) -> Span<'a> {
     let style = Style::default();
+    let text = text(game, column).unwrap_or_default();
 
-    let mut spans = vec![
-        Span::from(" "),
-        Span::styled(
-            white_player,
-            style.fg(Color::White).add_modifier(Modifier::BOLD),
-        ),
-        Span::styled(" vs. ", style.add_modifier(Modifier::ITALIC)),
-        Span::styled(
-            black_player,
-            style.fg(Color::DarkGray).add_modifier(Modifier::BOLD),
-        ),
-    ];
+    match column {
+        Column::White => Span::styled(text, style.fg(theme.white()).add_modifier(Modifier::BOLD)),
+        Column::Black => Span::styled(text, style.fg(theme.black()).add_modifier(Modifier::BOLD)),
+        _ => Span::styled(text, style),
+    }
+}
+
+fn text(game: &Game, column: Column) -> Option<String> {
+    let tags = game.pgn().tags();
 
-    if let Some(event) = event {
-        spans.push(Span::styled(format!(" | {event}"), style));
+    match column {
+        Column::White => Some(tags.get_or_default("White", "Unknown")),
+        Column::Black => Some(tags.get_or_default("Black", "Unknown")),
+        Column::WhiteElo => tags.get("WhiteElo").cloned(),
+        Column::BlackElo => tags.get("BlackElo").cloned(),
+        Column::Result => Some(game.pgn().result().to_string()),
+        Column::Date => tags.get("Date").cloned(),
+        Column::Event => tags.get("Event").cloned(),
+        Column::Eco => tags.get("ECO").cloned(),
+        Column::Moves => Some(move_count(game).to_string()),
     }
+}
 
-    if let Some(date) = date {
-        spans.push(Span::styled(format!(" | {date}"), style));
+fn sort_value(game: &Game, column: Column) -> Option<SortValue> {
+    match column {
+        Column::Moves => Some(SortValue::Number(move_count(game))),
+        Column::WhiteElo | Column::BlackElo => text(game, column)
+            .and_then(|elo| elo.trim().parse().ok())
+            .map(SortValue::Number),
+        // Unknown parts of a date are written as question marks, so it is treated as missing
+        _ => text(game, column)
+            .filter(|text| !text.is_empty() && !text.starts_with('?'))
+            .map(|text| SortValue::Text(text.to_lowercase())),
     }
+}
+
+// Counted by move number, as a game set up from a position may start on any move and with Black
+fn move_count(game: &Game) -> u64 {
+    let ply = game.pgn().ply();
+    match (ply.first(), ply.last()) {
+        (Some(first), Some(last)) => {
+            u64::try_from(last.move_number() - first.move_number() + 1).unwrap_or_default()
+        }
+        _ => 0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        model::{Board, Fen, GameResult, Pgn, PieceColour, Tags},
+        parse,
+    };
+
+    use super::*;
+
+    #[test]
+    fn counts_moves_by_move_number() {
+        let count = |text: &str| {
+            let pgn = parse::parse(text).unwrap().remove(0);
+            move_count(&Game::new(pgn, Vec::new()))
+        };
+        assert_eq!(count("[Result \"*\"]\n\n1. e4 e5 2. Nf3 *\n"), 2);
+        assert_eq!(count("[Result \"*\"]\n\n*\n"), 0);
+        assert_eq!(
+            count(
+                "[SetUp \"1\"]\n[FEN \"4k3/8/8/8/8/8/8/4K3 b - - 0 20\"]\n[Result \"*\"]\n\n\
+                20... Kf7 21. Ke2 *\n"
+            ),
+            2
+        );
+    }
+
+    mod sort_tests {
+        use super::*;
 
-    spans
+        #[test]
+        fn cycles_through_columns() {
+            let columns = [Column::White, Column::Date];
+            let sort = Sort::next(None, &columns);
+            assert_eq!(sort.map(|sort| sort.column), Some(Column::White));
+
+            let sort = Sort::next(sort, &columns);
+            assert_eq!(sort.map(|sort| sort.column), Some(Column::Date));
+            assert_eq!(Sort::next(sort, &columns), None);
+        }
+
+        #[test]
+        fn restores_original_order() {
+            let mut visible_games = vec![2, 0, 1];
+            sort(&games(), &mut visible_games, None);
+            assert_eq!(visible_games, vec![0, 1, 2]);
+        }
+
+        #[test]
+        fn sorts_ratings_by_value() {
+            let mut visible_games = vec![0, 1, 2];
+            let elo = Sort::next(None, &[Column::WhiteElo]);
+            sort(&games(), &mut visible_games, elo);
+            assert_eq!(visible_games, vec![1, 0, 2]);
+
+            sort(&games(), &mut visible_games, elo.map(Sort::reversed));
+            assert_eq!(visible_games, vec![0, 1, 2]);
+        }
+
+        #[test]
+        fn sorts_text_case_insensitively() {
+            let mut visible_games = vec![0, 1, 2];
+            sort(
+                &games(),
+                &mut visible_games,
+                Sort::next(None, &[Column::White]),
+            );
+            assert_eq!(visible_games, vec![2, 0, 1]);
+        }
+
+        fn games() -> Vec<Game> {
+            vec![
+                game(&[("White", "carlsen"), ("WhiteElo", "2850")]),
+                game(&[("White", "Nakamura"), ("WhiteElo", "999")]),
+                game(&[("White", "Anand")]),
+            ]
+        }
+
+        fn game(tags: &[(&str, &str)]) -> Game {
+            let tags = tags
+                .iter()
+                .map(|(key, value)| (key.to_string(), value.to_string()))
+                .collect::<Vec<(String, String)>>();
+            let fen = Fen::new(Board::builder().build(), PieceColour::White, 1);
+            let pgn = Pgn::new(Tags::new(tags), fen, GameResult::Ongoing, Vec::new());
+            Game::new(pgn, Vec::new())
+        }
+    }
 }
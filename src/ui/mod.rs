@@ -1,6 +1,6 @@
-use crate::model::Game;
+use crate::{config::Config, model::Game, uci::Engine};
 
-use self::{app::App, error::UiError};
+use self::{app::App, engine::EnginePanel, error::UiError};
 use std::io::{self, Stdout};
 
 use crossterm::{
@@ -9,19 +9,35 @@ use crossterm::{
 };
 use ratatui::prelude::*;
 
+pub use text::position as render_position;
+
+mod analysis;
 mod app;
+mod autoplay;
 mod board;
 mod centre;
 mod command;
+mod engine;
 mod error;
-mod fen;
+mod explorer;
 mod games;
 mod ply;
+mod prompt;
+mod stats;
 mod tags;
+mod text;
 
-pub fn launch(games: Vec<Game>) -> Result<(), UiError> {
+pub fn launch(games: Vec<Game>, config: Config) -> Result<(), UiError> {
+    // The engine is started first so that a bad path is reported before the terminal is taken over
+    let engine = match config.engine().path() {
+        Some(path) => Some(EnginePanel::new(
+            Engine::start(path, config.engine().options())
+                .map_err(|e| UiError::new(e.to_string()))?,
+        )),
+        None => None,
+    };
     let terminal = setup_terminal()?;
-    let mut app = App::new(terminal, games);
+    let mut app = App::new(terminal, games, config, engine);
     app.launch()?;
     Ok(())
 }
@@ -2,7 +2,7 @@ use std::fmt::Display;
 
 use ratatui::{
     layout::{Alignment, Rect},
-    style::{Color, Modifier, Style},
+    style::{Modifier, Style},
     text::{Line, Span},
     widgets::{
         block::{self, Title},
@@ -11,6 +11,7 @@ use ratatui::{
     Frame,
 };
 
+use crate::config::MovesTheme;
 use crate::model::{
     Check, GameResult, MoveQualifier, Movement, Piece, PieceColour, PieceType, Ply, PlyMovement,
     Position, COLUMNS, ROWS,
@@ -102,28 +103,18 @@ impl Display for MoveQualifier {
     }
 }
 
-impl Display for Ply {
+// A move in SAN, without its move number
+impl Display for PlyMovement {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let ply = match self.movement() {
-            PlyMovement::KingsideCastle { colour, check } => {
-                format_castle(self.move_number(), check.as_ref(), *colour, "O-O")
-            }
-            PlyMovement::QueensideCastle { colour, check } => {
-                format_castle(self.move_number(), check.as_ref(), *colour, "O-O-O")
-            }
+        let san = match self {
+            PlyMovement::KingsideCastle { check, .. } => format_castle(check.as_ref(), "O-O"),
+            PlyMovement::QueensideCastle { check, .. } => format_castle(check.as_ref(), "O-O-O"),
             PlyMovement::Move {
                 movement,
                 qualifier,
                 check,
                 capture,
-            } => format_move(
-                self.move_number(),
-                movement,
-                qualifier.as_ref(),
-                check.as_ref(),
-                *capture,
-                None,
-            ),
+            } => format_move(movement, qualifier.as_ref(), check.as_ref(), *capture, None),
             PlyMovement::Promotion {
                 movement,
                 promotes_to,
@@ -131,7 +122,6 @@ impl Display for Ply {
                 check,
                 capture,
             } => format_move(
-                self.move_number(),
                 movement,
                 qualifier.as_ref(),
                 check.as_ref(),
@@ -140,7 +130,17 @@ impl Display for Ply {
             ),
         };
 
-        write!(f, "{ply} ")
+        write!(f, "{san}")
+    }
+}
+
+impl Display for Ply {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let move_number = match self.movement().colour() {
+            PieceColour::White => format!("{}.", self.move_number()),
+            PieceColour::Black => String::new(),
+        };
+        write!(f, "{move_number}{} ", self.movement())
     }
 }
 
@@ -163,86 +163,114 @@ pub fn render(
     frame: &mut Frame,
     ply: &[Ply],
     current_ply: usize,
-    game_result: GameResult,
+    game_result: Option<GameResult>,
+    title: Option<String>,
+    theme: &MovesTheme,
     area: Rect,
 ) {
-    let mut spans: Vec<Span> = ply
-        .iter()
-        .enumerate()
-        .map(|(idx, p)| {
-            if idx == current_ply {
-                highlighted_ply(p)
-            } else {
-                standard_ply(p)
-            }
-        })
-        .collect();
+    // The board at index n is reached by playing the ply at index n - 1
+    let played_ply = current_ply.checked_sub(1);
+
+    let mut spans: Vec<Span> = Vec::new();
+    let mut highlighted_span = None;
+    for (idx, p) in ply.iter().enumerate() {
+        if Some(idx) == played_ply {
+            highlighted_span = Some(spans.len());
+            spans.push(highlighted_ply(p, theme));
+        } else {
+            spans.push(standard_ply(p, theme));
+        }
+        spans.extend(p.variations().iter().map(|line| variation(line, theme)));
+    }
 
-    spans.push(standard_game_result(&game_result));
+    if let Some(game_result) = game_result {
+        spans.push(standard_game_result(&game_result, theme));
+    }
 
-    let spans_per_page: usize = (area.area() / AVERAGE_PLY_LENGTH).into();
-    let current_page = current_ply / spans_per_page;
+    let spans_per_page = usize::from(area.area() / AVERAGE_PLY_LENGTH).max(1);
+    let current_page = highlighted_span.unwrap_or(0) / spans_per_page;
 
-    let pages = (spans.len() / spans_per_page) + 1;
+    let pages = spans.len().div_ceil(spans_per_page);
     let page = spans
         .chunks(spans_per_page)
         .nth(current_page)
-        .expect("Current page chunk should always be available")
-        .to_vec();
+        .map(<[Span]>::to_vec)
+        .unwrap_or_default();
 
-    let title = Title::from(format!("Page {}/{}", current_page + 1, pages))
+    let page_title = Title::from(format!("Page {}/{}", current_page + 1, pages))
         .position(block::Position::Bottom)
         .alignment(Alignment::Right);
 
+    let mut block = Block::default()
+        .title(if pages > 1 {
+            page_title
+        } else {
+            Title::default()
+        })
+        .borders(Borders::RIGHT)
+        .padding(Padding::horizontal(1));
+    if let Some(title) = title {
+        block = block.title(Title::from(title).alignment(Alignment::Left));
+    }
+
     let paragraph = Paragraph::new(vec![Line::from(page)])
         .wrap(Wrap { trim: true })
-        .block(
-            Block::default()
-                .title(if pages > 1 { title } else { Title::default() })
-                .borders(Borders::RIGHT)
-                .padding(Padding::horizontal(1)),
-        );
+        .block(block);
 
     frame.render_widget(paragraph, area);
 }
 
-fn standard_ply(ply: &Ply) -> Span {
-    Span::styled(format!("{ply}"), Style::default().fg(Color::DarkGray))
+// A line played instead of the ply before it. Black's moves are numbered at the start of a line
+// as there is no preceding White move to carry the number
+pub fn line_text(line: &[Ply]) -> String {
+    let mut text = match line.first() {
+        Some(first) if first.movement().colour() == PieceColour::Black => {
+            format!("{}... ", first.move_number())
+        }
+        _ => String::new(),
+    };
+    text.extend(line.iter().map(ToString::to_string));
+    text.trim_end().to_string()
+}
+
+fn variation<'a>(line: &[Ply], theme: &MovesTheme) -> Span<'a> {
+    Span::styled(
+        format!("({}) ", line_text(line)),
+        Style::default()
+            .fg(theme.standard())
+            .add_modifier(Modifier::ITALIC | Modifier::DIM),
+    )
+}
+
+fn standard_ply<'a>(ply: &Ply, theme: &MovesTheme) -> Span<'a> {
+    Span::styled(format!("{ply}"), Style::default().fg(theme.standard()))
 }
 
-fn highlighted_ply(ply: &Ply) -> Span {
-    Span::styled(format!("{ply}"), Style::default().fg(Color::Yellow))
+fn highlighted_ply<'a>(ply: &Ply, theme: &MovesTheme) -> Span<'a> {
+    Span::styled(format!("{ply}"), Style::default().fg(theme.highlighted()))
 }
 
-fn standard_game_result(game_result: &GameResult) -> Span {
+fn standard_game_result<'a>(game_result: &GameResult, theme: &MovesTheme) -> Span<'a> {
     Span::styled(
         format!("{game_result}"),
         Style::default()
             .add_modifier(Modifier::BOLD)
-            .fg(Color::LightGreen),
+            .fg(theme.result()),
     )
 }
 
-fn format_castle(
-    move_number: i16,
-    check: Option<&Check>,
-    colour: PieceColour,
-    castle_string: &str,
-) -> String {
-    let move_number = move_number_string(colour, move_number);
+fn format_castle(check: Option<&Check>, castle_string: &str) -> String {
     let check_string = check.map_or(String::new(), ToString::to_string);
-    format!("{move_number}{castle_string}{check_string}")
+    format!("{castle_string}{check_string}")
 }
 
 fn format_move(
-    move_number: i16,
     movement: &Movement,
     qualifier: Option<&MoveQualifier>,
     check: Option<&Check>,
     capture: bool,
     promotes_to: Option<&PieceType>,
 ) -> String {
-    let move_number = move_number_string(*movement.piece().colour(), move_number);
     let qualifier_string = qualifier.map_or(String::new(), ToString::to_string);
     let capture_string = if capture { "x" } else { "" };
     let check_string = check.map_or(String::new(), ToString::to_string);
@@ -251,19 +279,12 @@ fn format_move(
         Some(&piece_type) => format!("={}", Piece::new(*movement.piece().colour(), piece_type)),
     };
     format!(
-        "{move_number}{}{qualifier_string}{capture_string}{}{promotion_string}{check_string}",
+        "{}{qualifier_string}{capture_string}{}{promotion_string}{check_string}",
         format_piece_for_ply(movement.piece()),
         movement.position(),
     )
 }
 
-fn move_number_string(colour: PieceColour, move_number: i16) -> String {
-    match colour {
-        PieceColour::White => format!("{move_number}."),
-        PieceColour::Black => String::new(),
-    }
-}
-
 fn format_piece_for_ply(piece: Piece) -> &'static str {
     match (piece.colour(), piece.piece_type()) {
         (_, PieceType::Pawn) => "",
@@ -0,0 +1,153 @@
+use ratatui::{
+    layout::Rect,
+    style::{Color, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph},
+    Frame,
+};
+
+use crate::model::{PieceColour, Ply};
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum PromptKind {
+    GoToMove,
+    Search,
+    Move,
+    Position,
+    Pattern,
+}
+
+pub struct Prompt {
+    kind: PromptKind,
+    input: String,
+}
+
+impl Prompt {
+    pub fn new(kind: PromptKind, input: String) -> Self {
+        Prompt { kind, input }
+    }
+
+    pub fn kind(&self) -> PromptKind {
+        self.kind
+    }
+
+    pub fn input(&self) -> &str {
+        &self.input
+    }
+
+    pub fn push(&mut self, c: char) {
+        self.input.push(c);
+    }
+
+    pub fn pop(&mut self) {
+        self.input.pop();
+    }
+}
+
+pub fn render(frame: &mut Frame, prompt: &Prompt, area: Rect) {
+    let symbol = match prompt.kind {
+        PromptKind::GoToMove => ":",
+        PromptKind::Search => "/",
+        PromptKind::Move => "Move: ",
+        PromptKind::Position => "FEN: ",
+        PromptKind::Pattern => "Pattern: ",
+    };
+
+    let line = Line::from(vec![
+        Span::styled(symbol, Style::default().fg(Color::LightGreen)),
+        Span::from(prompt.input.clone()),
+        Span::styled(" ", Style::default().bg(Color::White)),
+    ]);
+
+    let paragraph = Paragraph::new(line).block(Block::default().borders(Borders::TOP));
+
+    frame.render_widget(paragraph, area);
+}
+
+pub fn render_message(frame: &mut Frame, message: &str, area: Rect) {
+    let paragraph = Paragraph::new(Span::styled(
+        message.to_string(),
+        Style::default().fg(Color::LightRed),
+    ))
+    .block(Block::default().borders(Borders::TOP));
+
+    frame.render_widget(paragraph, area);
+}
+
+// Returns the index of the board reached after the requested move, e.g. "34b" for Black's 34th
+pub fn board_index(ply: &[Ply], input: &str) -> Result<usize, String> {
+    let input = input.trim();
+    let digits = input
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(input.len());
+    let (move_number, suffix) = input.split_at(digits);
+
+    let move_number: i16 = move_number
+        .parse()
+        .map_err(|_| format!("'{input}' is not a valid move number"))?;
+
+    let colour = match suffix.trim().to_lowercase().as_str() {
+        "" | "w" | "." => PieceColour::White,
+        "b" | "..." => PieceColour::Black,
+        _ => return Err(format!("'{input}' is not a valid move number")),
+    };
+
+    ply.iter()
+        .position(|p| p.move_number() == move_number && p.movement().colour() == colour)
+        .map(|index| index + 1)
+        .ok_or_else(|| format!("Move '{input}' is not in this game"))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::model::{Movement, Piece, PieceType, PlyMovement, Position};
+
+    use super::*;
+
+    mod board_index_tests {
+        use super::*;
+
+        #[test]
+        fn returns_err_if_not_move_number() {
+            assert!(board_index(&ply(), "e4").is_err());
+            assert!(board_index(&ply(), "2x").is_err());
+        }
+
+        #[test]
+        fn returns_err_if_move_not_in_game() {
+            assert!(board_index(&ply(), "3b").is_err())
+        }
+
+        #[test]
+        fn finds_white_move_by_default() {
+            assert_eq!(board_index(&ply(), "2"), Ok(2))
+        }
+
+        #[test]
+        fn finds_black_move() {
+            assert_eq!(board_index(&ply(), "2b"), Ok(3));
+            assert_eq!(board_index(&ply(), "2..."), Ok(3));
+        }
+
+        fn ply() -> Vec<Ply> {
+            vec![
+                pawn_move(1, PieceColour::Black, Position::new(4, 4)),
+                pawn_move(2, PieceColour::White, Position::new(3, 3)),
+                pawn_move(2, PieceColour::Black, Position::new(4, 3)),
+            ]
+        }
+
+        fn pawn_move(move_number: i16, colour: PieceColour, position: Position) -> Ply {
+            Ply::new(
+                move_number,
+                PlyMovement::Move {
+                    movement: Movement::new(Piece::new(colour, PieceType::Pawn), position),
+                    qualifier: None,
+                    check: None,
+                    capture: false,
+                },
+                None,
+            )
+        }
+    }
+}
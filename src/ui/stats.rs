@@ -0,0 +1,45 @@
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Modifier, Style},
+    text::Line,
+    widgets::{Block, Borders, Clear, Paragraph},
+    Frame,
+};
+
+use crate::{model::Tags, stats::PlayerStats};
+
+use super::centre;
+
+// The statistics of the current game's players side by side, White on the left
+pub fn render(frame: &mut Frame, players: &[PlayerStats], tags: &Tags, area: Rect) {
+    let area = centre::centered_rect(90, 90, area);
+    let columns = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+        .split(area);
+
+    frame.render_widget(Clear, area);
+    for (tag, column) in ["White", "Black"].into_iter().zip(columns.iter()) {
+        let name = tags.get(tag).map_or("", |name| name.trim());
+        let lines = match players.iter().find(|player| player.name() == name) {
+            Some(player) => player
+                .lines()
+                .into_iter()
+                .enumerate()
+                .map(|(index, line)| match index {
+                    0 => Line::styled(line, Style::default().add_modifier(Modifier::BOLD)),
+                    _ => Line::from(line),
+                })
+                .collect(),
+            None => vec![Line::styled(
+                "No name given for this player",
+                Style::default().add_modifier(Modifier::DIM),
+            )],
+        };
+
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .title(format!(" {tag} "));
+        frame.render_widget(Paragraph::new(lines).block(block), *column);
+    }
+}
@@ -0,0 +1,186 @@
+use crate::{
+    model::{Board, Game, PieceColour, Position},
+    search::Occurrence,
+    write,
+};
+
+use super::{
+    board::{columns, file_label, glyph, rank_label, rows},
+    error::UiError,
+};
+
+// Plies shown either side of the current one in the move context
+const CONTEXT_PLY: usize = 3;
+
+// The board as text, laid out as the TUI draws it. Without colours to tell the sides apart, Black's
+// pieces are written in lower case unless using Unicode symbols, and empty squares as dots
+pub fn board(board: &Board, perspective: PieceColour, unicode: bool) -> String {
+    let mut lines: Vec<String> = rows(perspective)
+        .map(|row| {
+            let squares: String = columns(perspective)
+                .map(|column| match board.occupant(Position::new(row, column)) {
+                    Some(piece) if unicode => format!(" {} ", glyph(piece, true)),
+                    Some(piece) if *piece.colour() == PieceColour::Black => {
+                        format!(" {} ", glyph(piece, false).to_lowercase())
+                    }
+                    Some(piece) => format!(" {} ", glyph(piece, false)),
+                    None => String::from(" . "),
+                })
+                .collect();
+            format!("{}{squares}", rank_label(row))
+                .trim_end()
+                .to_string()
+        })
+        .collect();
+
+    let file_labels: String = columns(perspective).map(file_label).collect();
+    lines.push(format!("   {file_labels}").trim_end().to_string());
+    lines.join("\n")
+}
+
+// The board after the given number of plies, with where it comes in the game, its FEN and the
+// moves around it
+pub fn position(
+    game: &Game,
+    index: usize,
+    perspective: PieceColour,
+    unicode: bool,
+) -> Result<String, UiError> {
+    let pgn = game.pgn();
+    let board = game.boards().get(index).ok_or_else(|| {
+        UiError::new(format!(
+            "Ply {index} is past the end of the game, which has {} plies",
+            pgn.ply().len()
+        ))
+    })?;
+
+    let mut lines = vec![
+        format!(
+            "{} - {}",
+            pgn.tags().get_or_default("White", "Unknown"),
+            pgn.tags().get_or_default("Black", "Unknown")
+        ),
+        format!("Ply {index}, {}", Occurrence::new(0, index).describe(pgn)),
+        String::new(),
+        self::board(board, perspective, unicode),
+        String::new(),
+        format!(
+            "FEN: {}",
+            write::fen(board).map_err(|e| UiError::new(e.to_string()))?
+        ),
+    ];
+    if !pgn.ply().is_empty() {
+        lines.push(format!("Moves: {}", context(game, index)));
+    }
+    Ok(lines.join("\n"))
+}
+
+// A few moves either side of the board, with the move leading to it in brackets
+fn context(game: &Game, index: usize) -> String {
+    let ply = game.pgn().ply();
+    let start = index.saturating_sub(CONTEXT_PLY);
+    let end = (index + CONTEXT_PLY).min(ply.len());
+
+    let mut tokens = Vec::new();
+    if start > 0 {
+        tokens.push(String::from("..."));
+    }
+    for (i, p) in ply.iter().enumerate().take(end).skip(start) {
+        let text = match p.movement().colour() {
+            PieceColour::White => format!("{}. {}", p.move_number(), p.movement()),
+            PieceColour::Black if i == start || i + 1 == index => {
+                format!("{}... {}", p.move_number(), p.movement())
+            }
+            PieceColour::Black => p.movement().to_string(),
+        };
+        if i + 1 == index {
+            tokens.push(format!("[{text}]"));
+        } else {
+            tokens.push(text);
+        }
+    }
+    if end < ply.len() {
+        tokens.push(String::from("..."));
+    }
+    tokens.join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{parse, test_support};
+
+    use super::*;
+
+    mod board_tests {
+        use super::*;
+
+        #[test]
+        fn writes_board() {
+            let board = parse::fen("4k3/8/8/8/8/8/4P3/4K2R w K - 0 1").unwrap();
+            assert_eq!(
+                super::board(&board, PieceColour::White, false),
+                [
+                    " 8  .  .  .  .  k  .  .  .",
+                    " 7  .  .  .  .  .  .  .  .",
+                    " 6  .  .  .  .  .  .  .  .",
+                    " 5  .  .  .  .  .  .  .  .",
+                    " 4  .  .  .  .  .  .  .  .",
+                    " 3  .  .  .  .  .  .  .  .",
+                    " 2  .  .  .  .  P  .  .  .",
+                    " 1  .  .  .  .  K  .  .  R",
+                    "    a  b  c  d  e  f  g  h",
+                ]
+                .join("\n")
+            );
+        }
+
+        #[test]
+        fn writes_board_from_black_perspective() {
+            let board = parse::fen("4k3/8/8/8/8/8/4P3/4K2R w K - 0 1").unwrap();
+            let text = super::board(&board, PieceColour::Black, true);
+            let lines: Vec<&str> = text.lines().collect();
+
+            assert_eq!(lines[0], " 1  ♖  .  .  ♔  .  .  .  .");
+            assert_eq!(lines[8], "    h  g  f  e  d  c  b  a");
+        }
+    }
+
+    mod position_tests {
+        use super::*;
+
+        fn game() -> Game {
+            test_support::game(
+                "[White \"Fischer\"]\n[Black \"Spassky\"]\n[Result \"*\"]\n\n1. e4 e5 2. Nf3 Nc6 3. Bb5 a6 4. Ba4 Nf6 *\n",
+            )
+        }
+
+        #[test]
+        fn writes_position() {
+            let text = position(&game(), 4, PieceColour::White, false).unwrap();
+            let lines: Vec<&str> = text.lines().collect();
+
+            assert_eq!(lines[0], "Fischer - Spassky");
+            assert_eq!(lines[1], "Ply 4, after 2... Nc6");
+            assert_eq!(
+                lines[13],
+                "FEN: r1bqkbnr/pppp1ppp/2n5/4p3/4P3/5N2/PPPP1PPP/RNBQKB1R w KQkq - 2 3"
+            );
+            assert_eq!(
+                lines[14],
+                "Moves: ... 1... e5 2. Nf3 [2... Nc6] 3. Bb5 a6 4. Ba4 ..."
+            );
+        }
+
+        #[test]
+        fn writes_starting_position() {
+            let text = position(&game(), 0, PieceColour::White, false).unwrap();
+            assert!(text.contains("Ply 0, at the start"));
+            assert!(text.ends_with("Moves: 1. e4 e5 2. Nf3 ..."));
+        }
+
+        #[test]
+        fn returns_err_if_past_end() {
+            assert!(position(&game(), 9, PieceColour::White, false).is_err());
+        }
+    }
+}
@@ -0,0 +1,457 @@
+use std::{borrow::Cow, time::Duration};
+
+use gif::{Encoder, Frame, Repeat};
+
+use crate::model::{Board, Game, GameResult, PieceColour, PieceType, Ply, Position, COLUMNS, ROWS};
+
+use super::{layout, pgn, Highlights, WriteError};
+
+const SQUARE: usize = 48;
+// Pieces and text are drawn from small bitmaps, each pixel of which is scaled up to a block
+const PIECE_SCALE: usize = 2;
+const LABEL_SCALE: usize = 2;
+const CAPTION_SCALE: usize = 3;
+const MARGIN: usize = 16;
+const CAPTION: usize = 32;
+const BOARD: usize = SQUARE * 8;
+const WIDTH: usize = MARGIN + BOARD + MARGIN;
+const HEIGHT: usize = BOARD + MARGIN + CAPTION;
+// The final position stays up for this many frames' time before the animation starts again
+const FINAL_FRAMES: u32 = 3;
+
+const BACKGROUND: u8 = 0;
+const LIGHT_SQUARE: u8 = 1;
+const DARK_SQUARE: u8 = 2;
+const LIGHT_LAST_MOVE: u8 = 3;
+const DARK_LAST_MOVE: u8 = 4;
+const WHITE_PIECE: u8 = 5;
+const BLACK_PIECE: u8 = 6;
+// The same colours as the SVG diagrams, with the last move blended into the squares beneath it
+const PALETTE: [[u8; 3]; 7] = [
+    [0xff, 0xff, 0xff],
+    [0xf0, 0xd9, 0xb5],
+    [0xb5, 0x88, 0x63],
+    [0xdb, 0xd5, 0x88],
+    [0xc3, 0xb4, 0x67],
+    [0xff, 0xff, 0xff],
+    [0x00, 0x00, 0x00],
+];
+// Outlines and text are drawn in black
+const INK: u8 = BLACK_PIECE;
+
+// An animated GIF stepping through every board of the game, captioned with the move leading to each
+pub fn write(
+    game: &Game,
+    perspective: PieceColour,
+    delay: Duration,
+) -> Result<Vec<u8>, WriteError> {
+    let boards = game.boards();
+    let ply = game.pgn().ply();
+    let delay = u16::try_from(delay.as_millis() / 10).unwrap_or(u16::MAX);
+
+    let mut output = Vec::new();
+    let palette: Vec<u8> = PALETTE.iter().flatten().copied().collect();
+    let mut encoder = Encoder::new(&mut output, WIDTH as u16, HEIGHT as u16, &palette)
+        .map_err(|e| WriteError::new(format!("Failed to write GIF: {e}")))?;
+    encoder
+        .set_repeat(Repeat::Infinite)
+        .map_err(|e| WriteError::new(format!("Failed to write GIF: {e}")))?;
+
+    for (index, board) in boards.iter().enumerate() {
+        let previous = index
+            .checked_sub(1)
+            .and_then(|previous| Some((boards.get(previous)?, ply.get(previous)?)));
+        let mut caption = previous.map_or_else(String::new, |(_, ply)| caption(ply));
+        let last = index + 1 == boards.len();
+        if last && game.pgn().result() != GameResult::Ongoing {
+            caption = format!("{caption}  {}", pgn::result(game.pgn().result()));
+        }
+
+        let frame = Frame {
+            width: WIDTH as u16,
+            height: HEIGHT as u16,
+            delay: if last {
+                delay.saturating_mul(FINAL_FRAMES as u16)
+            } else {
+                delay
+            },
+            buffer: Cow::Owned(draw(board, previous, perspective, &caption)),
+            ..Frame::default()
+        };
+        encoder
+            .write_frame(&frame)
+            .map_err(|e| WriteError::new(format!("Failed to write GIF: {e}")))?;
+    }
+
+    drop(encoder);
+    Ok(output)
+}
+
+// e.g. "12. Nf3" or "12... Nf6"
+fn caption(ply: &Ply) -> String {
+    match ply.movement().colour() {
+        PieceColour::White => format!("{}. {}", ply.move_number(), ply.movement()),
+        PieceColour::Black => format!("{}... {}", ply.move_number(), ply.movement()),
+    }
+}
+
+// One frame as indices into the palette, row by row
+fn draw(
+    board: &Board,
+    previous: Option<(&Board, &Ply)>,
+    perspective: PieceColour,
+    caption: &str,
+) -> Vec<u8> {
+    let mut pixels = vec![BACKGROUND; WIDTH * HEIGHT];
+    let last_move = Highlights::new(previous).last_move();
+    let highlighted =
+        |position: Position| last_move.is_some_and(|(from, to)| position == from || position == to);
+
+    for row in 0..8 {
+        for col in 0..8 {
+            let position = Position::new(row, col);
+            let colour = match (layout::is_dark(position), highlighted(position)) {
+                (true, true) => DARK_LAST_MOVE,
+                (true, false) => DARK_SQUARE,
+                (false, true) => LIGHT_LAST_MOVE,
+                (false, false) => LIGHT_SQUARE,
+            };
+            let (x, y) = corner(position, perspective);
+            for line in pixels[y * WIDTH..(y + SQUARE) * WIDTH].chunks_mut(WIDTH) {
+                line[x..x + SQUARE].fill(colour);
+            }
+        }
+    }
+
+    let inset = (SQUARE - SPRITE_SIZE * PIECE_SCALE) / 2;
+    for (position, piece) in board.pieces() {
+        let (x, y) = corner(position, perspective);
+        let fill = match piece.colour() {
+            PieceColour::White => WHITE_PIECE,
+            PieceColour::Black => BLACK_PIECE,
+        };
+        stamp(
+            &mut pixels,
+            &sprite(*piece.piece_type()),
+            (x + inset, y + inset),
+            PIECE_SCALE,
+            fill,
+        );
+    }
+
+    let label_height = GLYPH_HEIGHT * LABEL_SCALE;
+    for (index, rank) in ROWS.chars().enumerate() {
+        let (_, y) = corner(Position::new(index as i8, 0), perspective);
+        text(
+            &mut pixels,
+            &rank.to_string(),
+            (MARGIN / 2, y + (SQUARE - label_height) / 2),
+            LABEL_SCALE,
+        );
+    }
+    for (index, file) in COLUMNS.chars().enumerate() {
+        let (x, _) = corner(Position::new(0, index as i8), perspective);
+        text(
+            &mut pixels,
+            &file.to_string(),
+            (x + SQUARE / 2, BOARD + (MARGIN - label_height) / 2),
+            LABEL_SCALE,
+        );
+    }
+
+    let caption_height = GLYPH_HEIGHT * CAPTION_SCALE;
+    text(
+        &mut pixels,
+        caption,
+        (WIDTH / 2, BOARD + MARGIN + (CAPTION - caption_height) / 2),
+        CAPTION_SCALE,
+    );
+    pixels
+}
+
+// The top left corner of the square in the frame
+fn corner(position: Position, perspective: PieceColour) -> (usize, usize) {
+    let (x, y) = layout::square(position, perspective);
+    (MARGIN + x as usize * SQUARE, y as usize * SQUARE)
+}
+
+// Draws a bitmap with its top left corner at the point, '#' in ink and 'o' in the fill colour
+fn stamp(pixels: &mut [u8], bitmap: &[&str], (x, y): (usize, usize), scale: usize, fill: u8) {
+    for (row, line) in bitmap.iter().enumerate() {
+        for (col, c) in line.chars().enumerate() {
+            let colour = match c {
+                '#' => INK,
+                'o' => fill,
+                _ => continue,
+            };
+            for dy in 0..scale {
+                let start = (y + row * scale + dy) * WIDTH + x + col * scale;
+                if let Some(block) = pixels.get_mut(start..start + scale) {
+                    block.fill(colour);
+                }
+            }
+        }
+    }
+}
+
+// Writes the text centred on x, with y the top of the line
+fn text(pixels: &mut [u8], text: &str, (x, y): (usize, usize), scale: usize) {
+    let advance = (GLYPH_WIDTH + 1) * scale;
+    let width = (text.chars().count() * advance).saturating_sub(scale);
+    let mut left = x.saturating_sub(width / 2);
+    for c in text.chars() {
+        if let Some(bitmap) = glyph(c) {
+            stamp(pixels, &bitmap, (left, y), scale, INK);
+        }
+        left += advance;
+    }
+}
+
+const SPRITE_SIZE: usize = 16;
+
+fn sprite(piece_type: PieceType) -> [&'static str; SPRITE_SIZE] {
+    match piece_type {
+        PieceType::King => [
+            ".......##.......",
+            ".....######.....",
+            ".......##.......",
+            ".....######.....",
+            "....#oooooo#....",
+            "...#oooooooo#...",
+            "...#oooooooo#...",
+            "....#oooooo#....",
+            ".....######.....",
+            ".....#oooo#.....",
+            "....#oooooo#....",
+            "...#oooooooo#...",
+            "..#oooooooooo#..",
+            "..############..",
+            "................",
+            "................",
+        ],
+        PieceType::Queen => [
+            "................",
+            ".#....#..#....#.",
+            ".#o#.#o##o#.#o#.",
+            ".#oo#oooooo#oo#.",
+            "..#oooooooooo#..",
+            "..#oooooooooo#..",
+            "...#oooooooo#...",
+            "...#oooooooo#...",
+            "....########....",
+            "....#oooooo#....",
+            "...#oooooooo#...",
+            "..#oooooooooo#..",
+            "..############..",
+            "................",
+            "................",
+            "................",
+        ],
+        PieceType::Rook => [
+            "................",
+            "................",
+            "...##.####.##...",
+            "...#o##oo##o#...",
+            "...#oooooooo#...",
+            "...##########...",
+            "....#oooooo#....",
+            "....#oooooo#....",
+            "....#oooooo#....",
+            "....#oooooo#....",
+            "....#oooooo#....",
+            "...##########...",
+            "..#oooooooooo#..",
+            "..############..",
+            "................",
+            "................",
+        ],
+        PieceType::Bishop => [
+            "................",
+            ".......##.......",
+            "......#oo#......",
+            ".....#oo#o#.....",
+            "....#oo#ooo#....",
+            "....#o#oooo#....",
+            "....#oooooo#....",
+            ".....#oooo#.....",
+            "......#oo#......",
+            ".....######.....",
+            ".....#oooo#.....",
+            "....#oooooo#....",
+            "...#oooooooo#...",
+            "...##########...",
+            "................",
+            "................",
+        ],
+        PieceType::Knight => [
+            "................",
+            "......#.#.......",
+            ".....#o#o##.....",
+            "....#oooooo#....",
+            "...#oo#ooooo#...",
+            "..#oooooooooo#..",
+            "..#ooooooooooo#.",
+            "..#o###ooooooo#.",
+            "...#..#ooooooo#.",
+            ".....#ooooooo#..",
+            "....#ooooooo#...",
+            "...#oooooooo#...",
+            "..#oooooooooo#..",
+            "..############..",
+            "................",
+            "................",
+        ],
+        PieceType::Pawn => [
+            "................",
+            "................",
+            "................",
+            "......####......",
+            ".....#oooo#.....",
+            ".....#oooo#.....",
+            "......#oo#......",
+            ".....#oooo#.....",
+            "......#oo#......",
+            "......#oo#......",
+            ".....#oooo#.....",
+            "....#oooooo#....",
+            "...#oooooooo#...",
+            "...##########...",
+            "................",
+            "................",
+        ],
+    }
+}
+
+const GLYPH_WIDTH: usize = 3;
+const GLYPH_HEIGHT: usize = 5;
+
+// Enough letters for coordinates, moves and results. Anything else is left as a gap
+fn glyph(c: char) -> Option<[&'static str; GLYPH_HEIGHT]> {
+    Some(match c {
+        '0' => ["###", "#.#", "#.#", "#.#", "###"],
+        '1' => [".#.", "##.", ".#.", ".#.", "###"],
+        '2' => ["###", "..#", "###", "#..", "###"],
+        '3' => ["###", "..#", ".##", "..#", "###"],
+        '4' => ["#.#", "#.#", "###", "..#", "..#"],
+        '5' => ["###", "#..", "###", "..#", "###"],
+        '6' => ["###", "#..", "###", "#.#", "###"],
+        '7' => ["###", "..#", "..#", ".#.", ".#."],
+        '8' => ["###", "#.#", "###", "#.#", "###"],
+        '9' => ["###", "#.#", "###", "..#", "###"],
+        'a' => ["...", "##.", ".##", "#.#", "###"],
+        'b' => ["#..", "#..", "##.", "#.#", "##."],
+        'c' => ["...", ".##", "#..", "#..", ".##"],
+        'd' => ["..#", "..#", ".##", "#.#", ".##"],
+        'e' => ["...", ".#.", "###", "#..", ".##"],
+        'f' => [".##", "#..", "##.", "#..", "#.."],
+        'g' => ["...", ".##", "#.#", ".##", "##."],
+        'h' => ["#..", "#..", "##.", "#.#", "#.#"],
+        'x' => ["...", "...", "#.#", ".#.", "#.#"],
+        'K' => ["#.#", "#.#", "##.", "#.#", "#.#"],
+        'Q' => [".#.", "#.#", "#.#", "##.", ".##"],
+        'R' => ["##.", "#.#", "##.", "#.#", "#.#"],
+        'B' => ["##.", "#.#", "##.", "#.#", "##."],
+        'N' => ["#.#", "###", "###", "#.#", "#.#"],
+        'O' => [".#.", "#.#", "#.#", "#.#", ".#."],
+        '+' => ["...", ".#.", "###", ".#.", "..."],
+        '#' => ["#.#", "###", "#.#", "###", "#.#"],
+        '=' => ["...", "###", "...", "###", "..."],
+        '-' => ["...", "...", "###", "...", "..."],
+        '.' => ["...", "...", "...", "...", ".#."],
+        '/' => ["..#", "..#", ".#.", "#..", "#.."],
+        '!' => [".#.", ".#.", ".#.", "...", ".#."],
+        '?' => ["##.", "..#", ".#.", "...", ".#."],
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use gif::DecodeOptions;
+
+    use crate::test_support::game_of;
+
+    use super::*;
+
+    fn frames(gif: &[u8]) -> Vec<gif::Frame<'static>> {
+        let mut options = DecodeOptions::new();
+        options.set_color_output(gif::ColorOutput::Indexed);
+        let mut decoder = options.read_info(gif).unwrap();
+        let mut frames = Vec::new();
+        while let Some(frame) = decoder.read_next_frame().unwrap() {
+            frames.push(frame.clone());
+        }
+        frames
+    }
+
+    fn pixel(frame: &gif::Frame, (x, y): (usize, usize)) -> u8 {
+        frame.buffer[y * WIDTH + x]
+    }
+
+    #[test]
+    fn writes_frame_for_each_board() {
+        let game = game_of("1. e4 e5 2. Nf3");
+        let frames = frames(&write(&game, PieceColour::White, Duration::from_millis(500)).unwrap());
+
+        assert_eq!(frames.len(), 4);
+        assert_eq!(
+            (frames[0].width, frames[0].height),
+            (WIDTH as u16, HEIGHT as u16)
+        );
+        assert_eq!(frames[0].delay, 50);
+        assert_eq!(frames[3].delay, 150);
+    }
+
+    #[test]
+    fn highlights_last_move() {
+        let game = game_of("1. e4");
+        let frames = frames(&write(&game, PieceColour::White, Duration::from_secs(1)).unwrap());
+        // The top left corner of each square is clear of the pieces
+        let e2 = corner(Position::new(1, 4), PieceColour::White);
+        let e4 = corner(Position::new(3, 4), PieceColour::White);
+
+        assert_eq!(pixel(&frames[0], e2), LIGHT_SQUARE);
+        assert_eq!(pixel(&frames[1], e2), LIGHT_LAST_MOVE);
+        assert_eq!(pixel(&frames[1], e4), LIGHT_LAST_MOVE);
+    }
+
+    #[test]
+    fn draws_pieces_from_perspective() {
+        let game = game_of("1. e4");
+        let frames = frames(&write(&game, PieceColour::Black, Duration::from_secs(1)).unwrap());
+        // The middle of the king's base, whose square is at the top from Black's side
+        let (x, y) = corner(Position::new(0, 4), PieceColour::Black);
+        let inset = (SQUARE - SPRITE_SIZE * PIECE_SCALE) / 2;
+        let base = (x + inset + 8 * PIECE_SCALE, y + inset + 12 * PIECE_SCALE);
+
+        assert!(y < SQUARE);
+        assert_eq!(pixel(&frames[0], base), WHITE_PIECE);
+    }
+
+    #[test]
+    fn captions_moves() {
+        let game = game_of("1. e4 e5");
+        assert_eq!(caption(&game.pgn().ply()[0]), "1. e4");
+        assert_eq!(caption(&game.pgn().ply()[1]), "1... e5");
+    }
+
+    #[test]
+    fn bitmaps_are_rectangular() {
+        for piece_type in [
+            PieceType::King,
+            PieceType::Queen,
+            PieceType::Rook,
+            PieceType::Bishop,
+            PieceType::Knight,
+            PieceType::Pawn,
+        ] {
+            assert!(sprite(piece_type)
+                .iter()
+                .all(|line| line.len() == SPRITE_SIZE));
+        }
+        assert!("0123456789abcdefghxKQRBNO+#=-./!?".chars().all(|c| glyph(c)
+            .unwrap()
+            .iter()
+            .all(|line| line.len() == GLYPH_WIDTH)));
+    }
+}
@@ -0,0 +1,18 @@
+use std::{error::Error, fmt::Display};
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct WriteError(String);
+
+impl WriteError {
+    pub fn new(message: impl Into<String>) -> Self {
+        WriteError(message.into())
+    }
+}
+
+impl Error for WriteError {}
+
+impl Display for WriteError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
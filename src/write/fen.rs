@@ -1,8 +1,8 @@
+use super::WriteError;
 use crate::model::AvailableCastle;
 use crate::model::{Board, Piece, PieceColour, PieceType, Position, MAX_POSITION, MIN_POSITION};
-use crate::ui::UiError;
 
-pub fn from_board(board: &Board) -> Result<String, UiError> {
+pub fn from_board(board: &Board) -> Result<String, WriteError> {
     let pieces = pieces(board)?;
     let active_colour = match board.active_colour() {
         PieceColour::White => "w",
@@ -39,7 +39,7 @@ fn castling_availability(available_castles: &[AvailableCastle]) -> String {
         .collect()
 }
 
-fn pieces(board: &Board) -> Result<String, UiError> {
+fn pieces(board: &Board) -> Result<String, WriteError> {
     let mut chars: Vec<char> = Vec::new();
     for row in (MIN_POSITION..=MAX_POSITION).rev() {
         let mut empty_columns = 0;
@@ -48,7 +48,7 @@ fn pieces(board: &Board) -> Result<String, UiError> {
             if let Some(piece) = board.occupant(position) {
                 if empty_columns > 0 {
                     chars.push(char::from_digit(empty_columns, 10).ok_or_else(|| {
-                        UiError::new(
+                        WriteError::new(
                             "Failed to parse char from empty columns in FEN string".to_string(),
                         )
                     })?);
@@ -59,7 +59,7 @@ fn pieces(board: &Board) -> Result<String, UiError> {
                 empty_columns += 1;
                 if col == MAX_POSITION {
                     chars.push(char::from_digit(empty_columns, 10).ok_or_else(|| {
-                        UiError::new(
+                        WriteError::new(
                             "Failed to parse char from empty columns in FEN string".to_string(),
                         )
                     })?);
@@ -0,0 +1,164 @@
+use serde::{Serialize, Serializer};
+
+use crate::{
+    engine,
+    model::{Board, Pgn, PieceColour, Ply},
+    uci,
+};
+
+use super::{fen, pgn, WriteError};
+
+#[derive(Serialize)]
+struct GameJson<'a> {
+    #[serde(serialize_with = "in_order")]
+    tags: Vec<(String, String)>,
+    result: &'static str,
+    fen: String,
+    comment: Option<&'a str>,
+    moves: Vec<MoveJson<'a>>,
+}
+
+#[derive(Serialize)]
+struct MoveJson<'a> {
+    move_number: i16,
+    colour: &'static str,
+    san: String,
+    uci: String,
+    // The position after the move
+    fen: String,
+    comment_before: Option<&'a str>,
+    comment: Option<&'a str>,
+    nags: &'a [u8],
+    variations: Vec<Vec<MoveJson<'a>>>,
+}
+
+// Writes the games as a JSON array, each with its tags and every move in both SAN and UCI
+// notation along with the FEN after it
+pub fn write(pgns: &[&Pgn]) -> Result<String, WriteError> {
+    let games = pgns
+        .iter()
+        .map(|pgn| {
+            let board = pgn.fen().starting_board();
+            Ok(GameJson {
+                tags: pgn::tags(pgn)?,
+                result: pgn::result(pgn.result()),
+                fen: fen(board)?,
+                comment: pgn.comment().map(String::as_str),
+                moves: moves(board, pgn.ply())?,
+            })
+        })
+        .collect::<Result<Vec<GameJson>, WriteError>>()?;
+
+    serde_json::to_string_pretty(&games)
+        .map_err(|e| WriteError::new(format!("Failed to write JSON: {e}")))
+}
+
+// Variations are played from the board before the move they replace
+fn moves<'a>(board: &Board, ply: &'a [Ply]) -> Result<Vec<MoveJson<'a>>, WriteError> {
+    let mut board = board.clone();
+    let mut line = Vec::new();
+
+    for p in ply {
+        let legal_move = engine::resolve_move(&board, p.movement())
+            .map_err(|e| WriteError::new(format!("{}: {e}", p.movement())))?;
+        let variations = p
+            .variations()
+            .iter()
+            .map(|variation| moves(&board, variation))
+            .collect::<Result<Vec<Vec<MoveJson>>, WriteError>>()?;
+
+        line.push(MoveJson {
+            move_number: p.move_number(),
+            colour: match p.movement().colour() {
+                PieceColour::White => "white",
+                PieceColour::Black => "black",
+            },
+            san: p.movement().to_string(),
+            uci: uci::long_algebraic(&legal_move),
+            fen: fen(legal_move.board())?,
+            comment_before: p.comment_before().map(String::as_str),
+            comment: p.comment().map(String::as_str),
+            nags: p.nags(),
+            variations,
+        });
+        board = legal_move.board().clone();
+    }
+
+    Ok(line)
+}
+
+// Tags are written as an object, keeping the order they would be written to PGN in
+fn in_order<S>(tags: &[(String, String)], serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    serializer.collect_map(tags.iter().map(|(key, value)| (key, value)))
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::Value;
+
+    use crate::parse;
+
+    use super::*;
+
+    fn json(text: &str) -> Value {
+        serde_json::from_str(text).unwrap()
+    }
+
+    fn export(input: &str) -> Value {
+        let pgn = parse::parse(input).unwrap().remove(0);
+        json(&write(&[&pgn]).unwrap())
+    }
+
+    #[test]
+    fn writes_tags_in_order() {
+        let pgn =
+            parse::parse("[White \"Fischer\"]\n[Event \"Match\"]\n[Result \"1-0\"]\n\n1. e4 1-0\n")
+                .unwrap()
+                .remove(0);
+        let text = write(&[&pgn]).unwrap();
+        let position = |tag: &str| text.find(&format!("\"{tag}\":")).unwrap();
+
+        assert!(position("Event") < position("White"));
+        assert!(position("White") < position("Result"));
+        assert_eq!(json(&text)[0]["result"], "1-0");
+    }
+
+    #[test]
+    fn writes_moves() {
+        let json = export("[Result \"*\"]\n\n1. e4 e5 2. Nf3 *\n");
+        let moves = json[0]["moves"].as_array().unwrap();
+
+        assert_eq!(moves.len(), 3);
+        assert_eq!(moves[0]["san"], "e4");
+        assert_eq!(moves[0]["uci"], "e2e4");
+        assert_eq!(moves[1]["colour"], "black");
+        assert_eq!(
+            moves[2]["fen"],
+            "rnbqkbnr/pppp1ppp/8/4p3/4P3/5N2/PPPP1PPP/RNBQKB1R b KQkq - 1 2"
+        );
+    }
+
+    #[test]
+    fn writes_annotations() {
+        let json = export("[Result \"*\"]\n\n1. e4 e5 2. Nf3 {Develops} $1 ({Or} 2. Bc4 Nc6) *\n");
+        let nf3 = &json[0]["moves"][2];
+
+        assert_eq!(nf3["comment"], "Develops");
+        assert_eq!(nf3["comment_before"], Value::Null);
+        assert_eq!(nf3["variations"][0][0]["comment_before"], "Or");
+        assert_eq!(nf3["nags"][0], 1);
+        assert_eq!(nf3["variations"][0][0]["uci"], "f1c4");
+        assert_eq!(nf3["variations"][0][1]["san"], "Nc6");
+    }
+
+    #[test]
+    fn returns_err_if_move_illegal() {
+        let pgn = parse::parse("[Result \"*\"]\n\n1. e4 e5 2. Ke3 *\n")
+            .unwrap()
+            .remove(0);
+        assert!(write(&[&pgn]).is_err());
+    }
+}
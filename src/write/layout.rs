@@ -0,0 +1,35 @@
+use crate::model::{PieceColour, Position};
+
+// Where a square is drawn, in squares across and down from the top left of the board as seen from
+// the given side
+pub fn square(position: Position, perspective: PieceColour) -> (i32, i32) {
+    let (row, col) = (i32::from(position.row()), i32::from(position.col()));
+    match perspective {
+        PieceColour::White => (col, 7 - row),
+        PieceColour::Black => (7 - col, row),
+    }
+}
+
+// a1 is a dark square
+pub fn is_dark(position: Position) -> bool {
+    (position.row() + position.col()) % 2 == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn flips_board_for_black() {
+        assert_eq!(square(Position::new(0, 0), PieceColour::White), (0, 7));
+        assert_eq!(square(Position::new(0, 0), PieceColour::Black), (7, 0));
+        assert_eq!(square(Position::new(6, 2), PieceColour::Black), (5, 6));
+    }
+
+    #[test]
+    fn colours_squares() {
+        assert!(is_dark(Position::new(0, 0)));
+        assert!(!is_dark(Position::new(0, 7)));
+        assert!(is_dark(Position::new(7, 7)));
+    }
+}
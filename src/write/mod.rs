@@ -0,0 +1,14 @@
+mod animation;
+mod error;
+mod fen;
+mod json;
+mod layout;
+mod pgn;
+mod svg;
+
+pub use animation::write as gif;
+pub use error::WriteError;
+pub use fen::from_board as fen;
+pub use json::write as json;
+pub use pgn::{result, write as pgn};
+pub use svg::{diagram, write as svg, Highlights};
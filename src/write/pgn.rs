@@ -0,0 +1,280 @@
+use crate::model::{GameResult, Pgn, PieceColour, Ply, DEFAULT_FEN, SEVEN_TAG_ROSTER};
+
+use super::{fen, WriteError};
+
+// Export format keeps lines of movetext under 80 characters
+const LINE_WIDTH: usize = 79;
+
+pub fn write(pgn: &Pgn) -> Result<String, WriteError> {
+    let mut text = String::new();
+    for (key, value) in tags(pgn)? {
+        text.push_str(&format!("[{key} \"{}\"]\n", escape(&value)));
+    }
+    text.push('\n');
+
+    let mut movetext = line(pgn.ply());
+    if let Some(game_comment) = pgn.comment() {
+        movetext.extend(comment(game_comment));
+    }
+    movetext.push(result(pgn.result()).to_string());
+    for line in reflow(&movetext) {
+        text.push_str(&line);
+        text.push('\n');
+    }
+    Ok(text)
+}
+
+// The Seven Tag Roster comes first in its usual order, followed by the remaining tags in the order
+// they were read, with SetUp and FEN last. Result and FEN aren't kept with the other tags once
+// parsed, so are restored here
+pub fn tags(pgn: &Pgn) -> Result<Vec<(String, String)>, WriteError> {
+    let mut tags: Vec<(String, String)> = SEVEN_TAG_ROSTER
+        .iter()
+        .filter_map(|&key| {
+            match key {
+                "Result" => Some(result(pgn.result()).to_string()),
+                key => pgn.tags().get(key).cloned(),
+            }
+            .map(|value| (key.to_string(), value))
+        })
+        .collect();
+
+    let mut others: Vec<(String, String)> = pgn
+        .tags()
+        .inner()
+        .iter()
+        .filter(|(key, _)| !SEVEN_TAG_ROSTER.contains(&key.as_str()))
+        .cloned()
+        .collect();
+
+    let fen = fen(pgn.fen().starting_board())?;
+    if fen != DEFAULT_FEN {
+        others.retain(|(key, _)| key != "SetUp");
+        others.push((String::from("SetUp"), String::from("1")));
+        others.push((String::from("FEN"), fen));
+    }
+
+    tags.append(&mut others);
+    Ok(tags)
+}
+
+// Movetext is split into the tokens lines may be broken between, which keeps move numbers with
+// their moves. Black's moves are numbered at the start of a line and after anything interrupting
+// the moves, such as a comment or variation. A comment before a move goes ahead of the move number
+// at the start of the line, and otherwise between the two so it isn't read as one on the move before
+fn line(ply: &[Ply]) -> Vec<String> {
+    let mut tokens = Vec::new();
+    let mut interrupted = true;
+
+    for (index, p) in ply.iter().enumerate() {
+        let number = match p.movement().colour() {
+            PieceColour::White => Some(format!("{}.", p.move_number())),
+            PieceColour::Black if interrupted || p.comment_before().is_some() => {
+                Some(format!("{}...", p.move_number()))
+            }
+            PieceColour::Black => None,
+        };
+        match (number, p.comment_before()) {
+            (Some(number), Some(before)) if index == 0 => {
+                tokens.extend(comment(before));
+                tokens.push(format!("{number} {}", p.movement()));
+            }
+            (Some(number), Some(before)) => {
+                tokens.push(number);
+                tokens.extend(comment(before));
+                tokens.push(p.movement().to_string());
+            }
+            (Some(number), None) => tokens.push(format!("{number} {}", p.movement())),
+            (None, _) => tokens.push(p.movement().to_string()),
+        }
+        tokens.extend(p.nags().iter().map(|nag| format!("${nag}")));
+        interrupted = false;
+
+        if let Some(after) = p.comment() {
+            tokens.extend(comment(after));
+            interrupted = true;
+        }
+        for variation in p.variations() {
+            tokens.extend(enclose(line(variation), '(', ')'));
+            interrupted = true;
+        }
+    }
+
+    tokens
+}
+
+// A brace comment can't hold a closing brace, which a comment read after a semicolon might, so
+// those are written after a semicolon again and run to the end of the line
+fn comment(text: &str) -> Vec<String> {
+    let words = text.split_whitespace().map(String::from);
+    if text.contains('}') {
+        vec![format!("; {}", words.collect::<Vec<String>>().join(" "))]
+    } else {
+        enclose(words, '{', '}')
+    }
+}
+
+// Brackets are kept apart from a rest of line comment, which would otherwise take them in
+fn enclose(tokens: impl IntoIterator<Item = String>, open: char, close: char) -> Vec<String> {
+    let mut tokens: Vec<String> = tokens.into_iter().collect();
+    match tokens.first_mut() {
+        Some(first) if !rest_of_line(first) => first.insert(0, open),
+        _ => tokens.insert(0, String::from(open)),
+    }
+    match tokens.last_mut() {
+        Some(last) if !rest_of_line(last) => last.push(close),
+        _ => tokens.push(String::from(close)),
+    }
+    tokens
+}
+
+fn rest_of_line(token: &str) -> bool {
+    token.starts_with(';')
+}
+
+fn reflow(tokens: &[String]) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut current = String::new();
+
+    for token in tokens {
+        if !current.is_empty() && current.len() + 1 + token.len() > LINE_WIDTH {
+            lines.push(std::mem::take(&mut current));
+        }
+        if !current.is_empty() {
+            current.push(' ');
+        }
+        current.push_str(token);
+        if rest_of_line(token) {
+            lines.push(std::mem::take(&mut current));
+        }
+    }
+    if !current.is_empty() {
+        lines.push(current);
+    }
+
+    lines
+}
+
+pub fn result(result: GameResult) -> &'static str {
+    match result {
+        GameResult::WhiteWin => "1-0",
+        GameResult::BlackWin => "0-1",
+        GameResult::Draw => "1/2-1/2",
+        GameResult::Ongoing => "*",
+    }
+}
+
+fn escape(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use crate::parse;
+
+    use super::*;
+
+    mod write_tests {
+        use super::*;
+
+        #[test]
+        fn writes_pgn() {
+            let pgn = &parse::parse(
+                "[White \"Player, One\"]\n[Result \"1-0\"]\n[Event \"Test\"]\n[Annotator \"Me\"]\n\n1. e4 e5 2. Nf3 {Developing} Nc6 1-0\n",
+            )
+            .unwrap()[0];
+
+            assert_eq!(
+                write(pgn).unwrap(),
+                "[Event \"Test\"]\n[White \"Player, One\"]\n[Result \"1-0\"]\n[Annotator \"Me\"]\n\n1. e4 e5 2. Nf3 {Developing} 2... Nc6 1-0\n"
+            );
+        }
+
+        #[test]
+        fn round_trips_closing_brace_in_comment() {
+            let input =
+                "[Result \"*\"]\n\n1. e4 ; Wins a } tempo\n(1. d4 ; Or {this}\n) 1... e5 *\n";
+            let pgns = parse::parse(input).unwrap();
+            let text = write(&pgns[0]).unwrap();
+            assert_eq!(text, input);
+
+            let reread = parse::parse(&text).unwrap();
+            assert_eq!(
+                reread[0].ply()[0].comment(),
+                Some(&String::from("Wins a } tempo"))
+            );
+            assert_eq!(reread, pgns);
+        }
+
+        #[test]
+        fn round_trips_game_without_moves() {
+            let input = "[Result \"1-0\"]\n\n{Won by forfeit} 1-0\n";
+            let pgns = parse::parse(input).unwrap();
+            assert_eq!(pgns[0].comment(), Some(&String::from("Won by forfeit")));
+            assert_eq!(write(&pgns[0]).unwrap(), input);
+        }
+
+        #[test]
+        fn round_trips_tags() {
+            let input = "[Event \"The \\\"Immortal\\\" Game\"]\n[Site \"?\"]\n[Date \"1851.06.21\"]\n\
+                [Round \"?\"]\n[White \"Anderssen\"]\n[Black \"Kieseritzky\"]\n[Result \"1-0\"]\n\
+                [Opening \"King's Gambit\"]\n[Annotator \"C:\\\\Notes\"]\n[ECO \"C33\"]\n\n1. e4 1-0\n";
+            let pgns = parse::parse(input).unwrap();
+            assert_eq!(
+                pgns[0].tags().get("Event"),
+                Some(&String::from("The \"Immortal\" Game"))
+            );
+            assert_eq!(write(&pgns[0]).unwrap(), input);
+        }
+
+        #[test]
+        fn writes_starting_position() {
+            let file = fs::read_to_string("./resources/test/test.pgn").unwrap();
+            let pgns = parse::parse(&file.replace("[White \"Player, Three\"]", "")).unwrap();
+
+            let text = write(&pgns[0]).unwrap();
+            assert!(text.contains("[SetUp \"1\"]\n[FEN \"r7/8/8/8/8/8/8/8 w k - 0 1\"]\n"));
+        }
+
+        #[test]
+        fn round_trips() {
+            let file = fs::read_to_string("./samples/example.pgn").unwrap();
+            let pgns = parse::parse(&file).unwrap();
+            let text = write(&pgns[0]).unwrap();
+
+            assert_eq!(parse::parse(&text).unwrap(), pgns);
+        }
+
+        #[test]
+        fn round_trips_annotations() {
+            let input = "[Result \"*\"]\n\n{Start} 1. e4 $1 {[%eval 0.35]} (1. d4 d5 ({Or} 1... Nf6) 2. c4) 1...\n\
+                {Sicilian} c5 2. {Open} Nf3 *\n";
+            let pgns = parse::parse(input).unwrap();
+            let text = write(&pgns[0]).unwrap();
+
+            assert_eq!(text, input);
+        }
+    }
+
+    mod reflow_tests {
+        use super::*;
+
+        #[test]
+        fn wraps_long_lines() {
+            let tokens = vec![String::from("10. exd5"); 20];
+            let lines = reflow(&tokens);
+
+            assert!(lines.iter().all(|line| line.len() <= LINE_WIDTH));
+            assert_eq!(lines.join(" "), tokens.join(" "));
+        }
+
+        #[test]
+        fn encloses_tokens() {
+            let tokens = enclose(["a", "b"].map(String::from), '{', '}');
+            assert_eq!(tokens, vec!["{a", "b}"]);
+            assert_eq!(enclose(Vec::new(), '{', '}'), vec!["{}"]);
+        }
+    }
+}
@@ -0,0 +1,355 @@
+use std::fmt::Write;
+
+use crate::{
+    engine,
+    model::{Board, Game, PieceColour, PieceType, Ply, Position, COLUMNS, ROWS},
+};
+
+use super::{layout, WriteError};
+
+const SQUARE: i32 = 45;
+// Room for the coordinates on the left and bottom, and the side to move marker on the right
+const MARGIN: i32 = 20;
+const BOARD: i32 = SQUARE * 8;
+const WIDTH: i32 = MARGIN + BOARD + MARGIN;
+const HEIGHT: i32 = BOARD + MARGIN;
+
+const LIGHT_SQUARE: &str = "#f0d9b5";
+const DARK_SQUARE: &str = "#b58863";
+const LAST_MOVE: &str = "#cdd26a";
+const FONT: &str = "DejaVu Sans, Arial Unicode MS, sans-serif";
+
+// Squares and arrows drawn over the board, from the move leading to it and the [%csl] and [%cal]
+// commands in that move's comment
+#[derive(Debug, PartialEq, Eq, Default)]
+pub struct Highlights {
+    last_move: Option<(Position, Position)>,
+    squares: Vec<(Position, &'static str)>,
+    arrows: Vec<(Position, Position, &'static str)>,
+}
+
+impl Highlights {
+    pub fn new(previous: Option<(&Board, &Ply)>) -> Self {
+        let Some((board, ply)) = previous else {
+            return Highlights::default();
+        };
+
+        let last_move = engine::resolve_move(board, ply.movement())
+            .ok()
+            .map(|legal_move| (legal_move.from(), legal_move.to()));
+        let comment = ply.comment().map_or("", String::as_str);
+
+        Highlights {
+            last_move,
+            squares: command(comment, "csl")
+                .into_iter()
+                .filter_map(|mark| {
+                    let (colour, square) = colour(mark)?;
+                    Some((position(square)?, colour))
+                })
+                .collect(),
+            arrows: command(comment, "cal")
+                .into_iter()
+                .filter_map(|mark| {
+                    let (colour, squares) = colour(mark)?;
+                    let (from, to) = squares.split_at_checked(2)?;
+                    Some((position(from)?, position(to)?, colour))
+                })
+                .collect(),
+        }
+    }
+
+    // The squares the move leading to the board was played from and to
+    pub fn last_move(&self) -> Option<(Position, Position)> {
+        self.last_move
+    }
+}
+
+// The board as a standalone SVG image, seen from the given side
+pub fn write(board: &Board, perspective: PieceColour, highlights: &Highlights) -> String {
+    let mut svg = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{WIDTH}\" height=\"{HEIGHT}\" viewBox=\"0 0 {WIDTH} {HEIGHT}\">\n"
+    );
+    svg.push_str(&markers(highlights));
+
+    for row in 0..8 {
+        for col in 0..8 {
+            let position = Position::new(row, col);
+            let (x, y) = corner(position, perspective);
+            let fill = if layout::is_dark(position) {
+                DARK_SQUARE
+            } else {
+                LIGHT_SQUARE
+            };
+            let _ = writeln!(
+                svg,
+                "<rect x=\"{x}\" y=\"{y}\" width=\"{SQUARE}\" height=\"{SQUARE}\" fill=\"{fill}\"/>"
+            );
+        }
+    }
+
+    let last_move = highlights
+        .last_move
+        .iter()
+        .flat_map(|&(from, to)| [(from, LAST_MOVE), (to, LAST_MOVE)]);
+    for (position, fill) in last_move.chain(highlights.squares.iter().copied()) {
+        let (x, y) = corner(position, perspective);
+        let _ = writeln!(
+            svg,
+            "<rect x=\"{x}\" y=\"{y}\" width=\"{SQUARE}\" height=\"{SQUARE}\" fill=\"{fill}\" fill-opacity=\"0.6\"/>"
+        );
+    }
+
+    for (position, piece) in board.pieces() {
+        let (x, y) = centre(position, perspective);
+        // The filled symbols are used for both sides so that White's pieces are solid too
+        let (fill, stroke) = match piece.colour() {
+            PieceColour::White => ("#ffffff", " stroke=\"#000000\" stroke-width=\"1.5\""),
+            PieceColour::Black => ("#000000", ""),
+        };
+        let _ = writeln!(
+            svg,
+            "<text x=\"{x}\" y=\"{y}\" font-family=\"{FONT}\" font-size=\"38\" text-anchor=\"middle\" dominant-baseline=\"central\" fill=\"{fill}\"{stroke}>{}</text>",
+            symbol(*piece.piece_type())
+        );
+    }
+
+    for &(from, to, colour) in &highlights.arrows {
+        let (x1, y1) = centre(from, perspective);
+        let (x2, y2) = centre(to, perspective);
+        let _ = writeln!(
+            svg,
+            "<line x1=\"{x1}\" y1=\"{y1}\" x2=\"{x2}\" y2=\"{y2}\" stroke=\"{colour}\" stroke-width=\"8\" stroke-opacity=\"0.8\" marker-end=\"url(#arrow-{})\"/>",
+            &colour[1..]
+        );
+    }
+
+    svg.push_str(&coordinates(perspective));
+    svg.push_str(&side_to_move(*board.active_colour(), perspective));
+    svg.push_str("</svg>\n");
+    svg
+}
+
+// The board after the given number of plies, highlighting the move leading to it
+pub fn diagram(game: &Game, index: usize, perspective: PieceColour) -> Result<String, WriteError> {
+    let board = game.boards().get(index).ok_or_else(|| {
+        WriteError::new(format!(
+            "Ply {index} is past the end of the game, which has {} plies",
+            game.pgn().ply().len()
+        ))
+    })?;
+    let previous = index
+        .checked_sub(1)
+        .map(|previous| (&game.boards()[previous], &game.pgn().ply()[previous]));
+    Ok(write(board, perspective, &Highlights::new(previous)))
+}
+
+fn markers(highlights: &Highlights) -> String {
+    let mut colours: Vec<&str> = highlights
+        .arrows
+        .iter()
+        .map(|&(_, _, colour)| colour)
+        .collect();
+    colours.sort_unstable();
+    colours.dedup();
+    if colours.is_empty() {
+        return String::new();
+    }
+
+    let mut defs = String::from("<defs>\n");
+    for colour in colours {
+        let _ = writeln!(
+            defs,
+            "<marker id=\"arrow-{}\" markerWidth=\"3\" markerHeight=\"3\" refX=\"1.5\" refY=\"1.5\" orient=\"auto\"><path d=\"M0,0 L3,1.5 L0,3 z\" fill=\"{colour}\" fill-opacity=\"0.8\"/></marker>",
+            &colour[1..]
+        );
+    }
+    defs.push_str("</defs>\n");
+    defs
+}
+
+fn coordinates(perspective: PieceColour) -> String {
+    let mut text = String::new();
+    for (index, rank) in ROWS.chars().enumerate() {
+        let (_, y) = centre(Position::new(index as i8, 0), perspective);
+        let _ = writeln!(
+            text,
+            "<text x=\"{}\" y=\"{y}\" font-family=\"{FONT}\" font-size=\"13\" text-anchor=\"middle\" dominant-baseline=\"central\">{rank}</text>",
+            MARGIN / 2
+        );
+    }
+    for (index, file) in COLUMNS.chars().enumerate() {
+        let (x, _) = centre(Position::new(0, index as i8), perspective);
+        let _ = writeln!(
+            text,
+            "<text x=\"{x}\" y=\"{}\" font-family=\"{FONT}\" font-size=\"13\" text-anchor=\"middle\" dominant-baseline=\"central\">{file}</text>",
+            BOARD + MARGIN / 2
+        );
+    }
+    text
+}
+
+// A disc in the side to move's colour beside their edge of the board
+fn side_to_move(active_colour: PieceColour, perspective: PieceColour) -> String {
+    let y = if active_colour == perspective {
+        BOARD - SQUARE / 2
+    } else {
+        SQUARE / 2
+    };
+    let fill = match active_colour {
+        PieceColour::White => "#ffffff",
+        PieceColour::Black => "#000000",
+    };
+    format!(
+        "<circle cx=\"{}\" cy=\"{y}\" r=\"6\" fill=\"{fill}\" stroke=\"#000000\" stroke-width=\"1.5\"/>\n",
+        MARGIN + BOARD + MARGIN / 2
+    )
+}
+
+// The top left corner of the square in the image
+fn corner(position: Position, perspective: PieceColour) -> (i32, i32) {
+    let (x, y) = layout::square(position, perspective);
+    (MARGIN + x * SQUARE, y * SQUARE)
+}
+
+fn centre(position: Position, perspective: PieceColour) -> (i32, i32) {
+    let (x, y) = corner(position, perspective);
+    (x + SQUARE / 2, y + SQUARE / 2)
+}
+
+fn symbol(piece_type: PieceType) -> char {
+    match piece_type {
+        PieceType::King => '♚',
+        PieceType::Queen => '♛',
+        PieceType::Rook => '♜',
+        PieceType::Bishop => '♝',
+        PieceType::Knight => '♞',
+        PieceType::Pawn => '♟',
+    }
+}
+
+// The comma separated arguments of a command such as [%csl Ge4,Rd5] embedded in a comment
+fn command<'a>(comment: &'a str, name: &str) -> Vec<&'a str> {
+    let prefix = format!("[%{name} ");
+    comment
+        .match_indices(&prefix)
+        .filter_map(|(start, _)| {
+            let rest = &comment[start + prefix.len()..];
+            rest.find(']').map(|end| &rest[..end])
+        })
+        .flat_map(|arguments| arguments.split(','))
+        .map(str::trim)
+        .collect()
+}
+
+// Marks start with a letter for their colour: red, green, blue or yellow
+fn colour(mark: &str) -> Option<(&'static str, &str)> {
+    let mut chars = mark.chars();
+    let colour = match chars.next()? {
+        'R' => "#d0312d",
+        'G' => "#15781b",
+        'B' => "#2c6fbb",
+        'Y' => "#e8b100",
+        _ => return None,
+    };
+    Some((colour, chars.as_str()))
+}
+
+fn position(square: &str) -> Option<Position> {
+    let mut chars = square.chars();
+    let col = COLUMNS.find(chars.next()?)?;
+    let row = ROWS.find(chars.next()?)?;
+    if chars.next().is_some() {
+        return None;
+    }
+    Position::try_from(i8::try_from(row).ok()?, i8::try_from(col).ok()?).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_support::game_of;
+
+    use super::*;
+
+    fn pgn(movetext: &str) -> (Vec<Board>, Vec<Ply>) {
+        let game = game_of(movetext);
+        (game.boards().to_vec(), game.pgn().ply().to_vec())
+    }
+
+    #[test]
+    fn highlights_last_move() {
+        let (boards, ply) = pgn("1. e4 e5");
+        let highlights = Highlights::new(Some((&boards[1], &ply[1])));
+        assert_eq!(
+            highlights.last_move,
+            Some((Position::new(6, 4), Position::new(4, 4)))
+        );
+    }
+
+    #[test]
+    fn reads_highlights_from_comment() {
+        let (boards, ply) = pgn("1. e4 {[%csl Gd4,Rf7][%cal Yg1f3] Aiming at f7}");
+        let highlights = Highlights::new(Some((&boards[0], &ply[0])));
+
+        assert_eq!(
+            highlights.squares,
+            vec![
+                (Position::new(3, 3), "#15781b"),
+                (Position::new(6, 5), "#d0312d")
+            ]
+        );
+        assert_eq!(
+            highlights.arrows,
+            vec![(Position::new(0, 6), Position::new(2, 5), "#e8b100")]
+        );
+    }
+
+    #[test]
+    fn ignores_malformed_highlights() {
+        let (boards, ply) = pgn("1. e4 {[%csl Xd4,Gz9,G][%cal Ge2]}");
+        let highlights = Highlights::new(Some((&boards[0], &ply[0])));
+        assert!(highlights.squares.is_empty());
+        assert!(highlights.arrows.is_empty());
+    }
+
+    #[test]
+    fn writes_svg() {
+        let (boards, _) = pgn("1. e4");
+        let svg = write(&boards[1], PieceColour::White, &Highlights::default());
+
+        assert!(svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\""));
+        assert!(svg.ends_with("</svg>\n"));
+        assert_eq!(svg.matches("<rect").count(), 64);
+        assert_eq!(svg.matches('♟').count(), 16);
+        // Black is to move, so the marker is at the top from White's side
+        assert!(svg.contains(&format!("cy=\"{}\" r=\"6\" fill=\"#000000\"", SQUARE / 2)));
+    }
+
+    #[test]
+    fn writes_diagram_from_game() {
+        let game = game_of("1. e4 e5");
+        let (boards, ply) = (game.boards(), game.pgn().ply());
+
+        assert_eq!(
+            diagram(&game, 2, PieceColour::Black).unwrap(),
+            write(
+                &boards[2],
+                PieceColour::Black,
+                &Highlights::new(Some((&boards[1], &ply[1])))
+            )
+        );
+        assert!(diagram(&game, 3, PieceColour::White).is_err());
+    }
+
+    #[test]
+    fn flips_board_for_black() {
+        assert_eq!(
+            corner(Position::new(0, 0), PieceColour::White),
+            (MARGIN, 7 * SQUARE)
+        );
+        assert_eq!(
+            corner(Position::new(0, 0), PieceColour::Black),
+            (MARGIN + 7 * SQUARE, 0)
+        );
+    }
+}